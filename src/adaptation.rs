@@ -0,0 +1,277 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Standard illuminants and chromatic adaptation between white points:
+//! http://en.wikipedia.org/wiki/Chromatic_adaptation
+
+use num;
+use num::traits;
+
+use FloatChannel;
+use {Xyz, Chromaticity};
+use Lab;
+use matrix::{self, Matrix3};
+
+fn cast<T: num::NumCast, U: num::NumCast>(n: T) -> U {
+    traits::cast(n).unwrap()
+}
+
+/// The CIE standard observers.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Observer {
+    /// The CIE 1931 2° standard observer.
+    Cie1931,
+    /// The CIE 1964 10° supplementary standard observer.
+    Cie1964,
+}
+
+/// The CIE standard illuminants.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Illuminant {
+    /// Incandescent light, about 2856 K.
+    A,
+    /// Noon sunlight, about 4874 K. Deprecated by the CIE.
+    B,
+    /// Average daylight, about 6774 K. Deprecated by the CIE.
+    C,
+    /// Horizon daylight, about 5003 K.
+    D50,
+    /// Mid-morning or mid-afternoon daylight, about 5503 K.
+    D55,
+    /// Noon daylight, about 6504 K.
+    D65,
+    /// North sky daylight, about 7504 K.
+    D75,
+    /// The equal energy illuminant.
+    E,
+    /// Cool white fluorescent light.
+    F2,
+    /// Broadband daylight fluorescent light.
+    F7,
+    /// Narrowband white fluorescent light.
+    F11,
+}
+
+/// A standard illuminant as seen by a standard observer.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct WhitePoint { pub illuminant: Illuminant, pub observer: Observer }
+
+impl WhitePoint {
+    /// D50 with the 2° observer.
+    pub const D50: WhitePoint = WhitePoint { illuminant: Illuminant::D50, observer: Observer::Cie1931 };
+    /// D65 with the 2° observer.
+    pub const D65: WhitePoint = WhitePoint { illuminant: Illuminant::D65, observer: Observer::Cie1931 };
+
+    #[inline]
+    pub fn new(illuminant: Illuminant, observer: Observer) -> WhitePoint {
+        WhitePoint { illuminant, observer }
+    }
+
+    /// Returns the chromaticity of the white point, as tabulated by the CIE.
+    /// Note that these differ slightly from the rounded D50 and D65 values
+    /// that `Chromaticity` uses for RGB spaces.
+    pub fn chromaticity(&self) -> Chromaticity {
+        let (x, y) = match (self.illuminant, self.observer) {
+            (Illuminant::A,   Observer::Cie1931) => (0.44757, 0.40745),
+            (Illuminant::A,   Observer::Cie1964) => (0.45117, 0.40594),
+            (Illuminant::B,   Observer::Cie1931) => (0.34842, 0.35161),
+            (Illuminant::B,   Observer::Cie1964) => (0.34980, 0.35270),
+            (Illuminant::C,   Observer::Cie1931) => (0.31006, 0.31616),
+            (Illuminant::C,   Observer::Cie1964) => (0.31039, 0.31905),
+            (Illuminant::D50, Observer::Cie1931) => (0.34567, 0.35850),
+            (Illuminant::D50, Observer::Cie1964) => (0.34773, 0.35952),
+            (Illuminant::D55, Observer::Cie1931) => (0.33242, 0.34743),
+            (Illuminant::D55, Observer::Cie1964) => (0.33411, 0.34877),
+            (Illuminant::D65, Observer::Cie1931) => (0.31271, 0.32902),
+            (Illuminant::D65, Observer::Cie1964) => (0.31382, 0.33100),
+            (Illuminant::D75, Observer::Cie1931) => (0.29902, 0.31485),
+            (Illuminant::D75, Observer::Cie1964) => (0.29968, 0.31740),
+            (Illuminant::E,   _)                 => (1.0 / 3.0, 1.0 / 3.0),
+            (Illuminant::F2,  Observer::Cie1931) => (0.37208, 0.37529),
+            (Illuminant::F2,  Observer::Cie1964) => (0.37925, 0.36733),
+            (Illuminant::F7,  Observer::Cie1931) => (0.31292, 0.32933),
+            (Illuminant::F7,  Observer::Cie1964) => (0.31569, 0.32960),
+            (Illuminant::F11, Observer::Cie1931) => (0.38052, 0.37713),
+            (Illuminant::F11, Observer::Cie1964) => (0.38541, 0.37123),
+        };
+        Chromaticity::new(x, y)
+    }
+
+    /// Returns the tristimulus value of the white point with a luminance of
+    /// one.
+    #[inline]
+    pub fn to_xyz<T:FloatChannel>(&self) -> Xyz<T> {
+        self.chromaticity().to_xyz()
+    }
+}
+
+impl From<WhitePoint> for Chromaticity {
+    #[inline]
+    fn from(white: WhitePoint) -> Chromaticity {
+        white.chromaticity()
+    }
+}
+
+/// A chromatic adaptation transform, which predicts how a color seen under
+/// one white point appears under another by scaling the responses of a cone
+/// space.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ChromaticAdaptation {
+    /// The Bradford transform used by ICC profiles and CSS.
+    Bradford,
+    /// The von Kries transform with the Hunt-Pointer-Estevez cone space.
+    VonKries,
+    /// The transform of the CIECAM02 color appearance model.
+    Cat02,
+    /// The transform of the CAM16 color appearance model.
+    Cat16,
+    /// Scales the XYZ components directly. Simple, but the least accurate.
+    XyzScaling,
+}
+
+impl ChromaticAdaptation {
+    /// The matrix from XYZ to the cone space of the transform.
+    const fn cone_matrix(self) -> Matrix3 {
+        match self {
+            ChromaticAdaptation::Bradford   => [[ 0.8951,    0.2664,   -0.1614  ],
+                                                [-0.7502,    1.7135,    0.0367  ],
+                                                [ 0.0389,   -0.0685,    1.0296  ]],
+            ChromaticAdaptation::VonKries   => [[ 0.40024,   0.70760,  -0.08081 ],
+                                                [-0.22630,   1.16532,   0.04570 ],
+                                                [ 0.0,       0.0,       0.91822 ]],
+            ChromaticAdaptation::Cat02      => [[ 0.7328,    0.4296,   -0.1624  ],
+                                                [-0.7036,    1.6975,    0.0061  ],
+                                                [ 0.0030,    0.0136,    0.9834  ]],
+            ChromaticAdaptation::Cat16      => [[ 0.401288,  0.650173, -0.051461],
+                                                [-0.250268,  1.204414,  0.045854],
+                                                [-0.002079,  0.048952,  0.953127]],
+            ChromaticAdaptation::XyzScaling => matrix::diagonal([1.0, 1.0, 1.0]),
+        }
+    }
+
+    /// Returns the matrix that adapts XYZ colors from one white to another.
+    #[inline]
+    pub fn matrix<F: Into<Chromaticity>, U: Into<Chromaticity>>(self, from: F, to: U) -> Matrix3 {
+        self.between(from.into(), to.into())
+    }
+
+    /// The `matrix` between two chromaticities, usable in constants.
+    pub const fn between(self, from: Chromaticity, to: Chromaticity) -> Matrix3 {
+        let cone = self.cone_matrix();
+        let src = matrix::mul_vec(&cone, from.tristimulus());
+        let dst = matrix::mul_vec(&cone, to.tristimulus());
+        let scale = matrix::diagonal([dst[0] / src[0], dst[1] / src[1], dst[2] / src[2]]);
+        matrix::mul(&matrix::invert(&cone), &matrix::mul(&scale, &cone))
+    }
+}
+
+impl<T:FloatChannel> Xyz<T> {
+    /// Adapts the color from one white point to another with the Bradford
+    /// transform.
+    #[inline]
+    pub fn adapt<F: Into<Chromaticity>, U: Into<Chromaticity>>(&self, from: F, to: U) -> Xyz<T> {
+        self.adapt_with(from, to, ChromaticAdaptation::Bradford)
+    }
+
+    /// Adapts the color from one white point to another with the given
+    /// transform.
+    pub fn adapt_with<F: Into<Chromaticity>, U: Into<Chromaticity>>(&self, from: F, to: U, method: ChromaticAdaptation) -> Xyz<T> {
+        let xyz = matrix::mul_vec(&method.matrix(from, to), [cast(self.x), cast(self.y), cast(self.z)]);
+        Xyz::new(cast(xyz[0]), cast(xyz[1]), cast(xyz[2]))
+    }
+}
+
+impl<T:FloatChannel> Lab<T> {
+    /// Converts the color from being relative to one white point to being
+    /// relative to another, adapting it with the Bradford transform.
+    #[inline]
+    pub fn adapt<F: Into<Chromaticity>, U: Into<Chromaticity>>(&self, from: F, to: U) -> Lab<T> {
+        self.adapt_with(from, to, ChromaticAdaptation::Bradford)
+    }
+
+    /// Converts the color from being relative to one white point to being
+    /// relative to another, adapting it with the given transform.
+    pub fn adapt_with<F: Into<Chromaticity>, U: Into<Chromaticity>>(&self, from: F, to: U, method: ChromaticAdaptation) -> Lab<T> {
+        let (from, to) = (from.into(), to.into());
+        let xyz = self.to_xyz_with::<f64>(from).adapt_with(from, to, method);
+        Lab::from_xyz(&xyz, to)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {Xyz, Lab, Chromaticity};
+    use super::{WhitePoint, Illuminant, Observer, ChromaticAdaptation};
+
+    fn assert_xyz_eq(a: Xyz<f64>, b: Xyz<f64>, epsilon: f64) {
+        assert!((a.x - b.x).abs() < epsilon && (a.y - b.y).abs() < epsilon && (a.z - b.z).abs() < epsilon,
+                "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn test_white_points() {
+        let d65 = WhitePoint::D65.to_xyz::<f64>();
+        assert_xyz_eq(d65, Xyz::new(0.95047, 1.0, 1.08883), 1e-4);
+        let d50 = WhitePoint::D50.to_xyz::<f64>();
+        assert_xyz_eq(d50, Xyz::new(0.96422, 1.0, 0.82521), 1e-4);
+        let a = WhitePoint::new(Illuminant::A, Observer::Cie1931).to_xyz::<f64>();
+        assert_xyz_eq(a, Xyz::new(1.09850, 1.0, 0.35585), 1e-4);
+        let d65_10 = WhitePoint::new(Illuminant::D65, Observer::Cie1964).to_xyz::<f64>();
+        assert_xyz_eq(d65_10, Xyz::new(0.94811, 1.0, 1.07304), 1e-4);
+        assert_eq!(Chromaticity::from(WhitePoint::new(Illuminant::E, Observer::Cie1964)), Chromaticity::new(1.0 / 3.0, 1.0 / 3.0));
+    }
+
+    #[test]
+    fn test_adapt_white() {
+        // Every transform maps the source white exactly onto the target white
+        let methods = [ChromaticAdaptation::Bradford, ChromaticAdaptation::VonKries,
+                       ChromaticAdaptation::Cat02, ChromaticAdaptation::Cat16,
+                       ChromaticAdaptation::XyzScaling];
+        for &method in methods.iter() {
+            let white = WhitePoint::D50.to_xyz::<f64>().adapt_with(WhitePoint::D50, WhitePoint::D65, method);
+            assert_xyz_eq(white, WhitePoint::D65.to_xyz(), 1e-12);
+            let xyz = Xyz::new(0.3, 0.4, 0.5);
+            let round_trip = xyz.adapt_with(WhitePoint::D65, WhitePoint::new(Illuminant::A, Observer::Cie1931), method)
+                                .adapt_with(WhitePoint::new(Illuminant::A, Observer::Cie1931), WhitePoint::D65, method);
+            assert_xyz_eq(round_trip, xyz, 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_bradford() {
+        // Reference matrix from http://www.brucelindbloom.com/Eqn_ChromAdapt.html
+        let m = ChromaticAdaptation::Bradford.matrix(WhitePoint::D65, WhitePoint::D50);
+        let expected = [[ 1.0478112, 0.0228866, -0.0501270],
+                        [ 0.0295424, 0.9904844, -0.0170491],
+                        [-0.0092345, 0.0150436,  0.7521316]];
+        for i in 0..3 {
+            for j in 0..3 {
+                assert!((m[i][j] - expected[i][j]).abs() < 1e-4, "{:?}", m);
+            }
+        }
+        let xyz = Xyz::new(0.4124, 0.2126, 0.0193).adapt(WhitePoint::D65, WhitePoint::D50);
+        assert_xyz_eq(xyz, Xyz::new(0.4361, 0.2225, 0.0139), 1e-3);
+    }
+
+    #[test]
+    fn test_adapt_lab() {
+        // A white relative to D65 stays white when re-expressed relative to D50
+        let white = Lab::<f64>::new(100.0, 0.0, 0.0).adapt(Chromaticity::D65, Chromaticity::D50);
+        assert!((white.l - 100.0).abs() < 1e-9 && white.a.abs() < 1e-9 && white.b.abs() < 1e-9);
+        let lab = Lab::<f64>::new(50.0, 20.0, -30.0);
+        let round_trip = lab.adapt(WhitePoint::D65, WhitePoint::D50).adapt(WhitePoint::D50, WhitePoint::D65);
+        assert!((round_trip.l - 50.0).abs() < 1e-9 && (round_trip.a - 20.0).abs() < 1e-9 && (round_trip.b + 30.0).abs() < 1e-9);
+    }
+}
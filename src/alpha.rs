@@ -16,16 +16,27 @@
 use std::ops::{Mul, Div, Add, Sub, Index, IndexMut};
 use std::slice;
 use num::Saturating;
+use space::LinearSrgb;
 use {Color, Channel, FloatChannel};
-use {Rgb, Rg, ToRgb, Hsv, Srgb, YCbCr};
+use {Rgb, Rg, ToRgb, Hsv, Hsl, Lab, Lch, Oklab, Oklch, Luv, Lchuv, Hsluv, Hpluv, Srgb, ToSrgb, YCbCr, ToYCbCr, YCbCrMatrix, YCbCrRange};
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[repr(C)]
 pub struct AlphaColor<T, C> { pub c: C, pub a: T }
 
-pub type Rgba<T> = AlphaColor<T, Rgb<T>>;
+pub type Rgba<T, S = LinearSrgb> = AlphaColor<T, Rgb<T, S>>;
 pub type Hsva<T> = AlphaColor<T, Hsv<T>>;
+pub type Hsla<T> = AlphaColor<T, Hsl<T>>;
 pub type Srgba<T> = AlphaColor<T, Srgb<T>>;
 pub type YCbCra<T> = AlphaColor<T, YCbCr<T>>;
+pub type Laba<T> = AlphaColor<T, Lab<T>>;
+pub type Lcha<T> = AlphaColor<T, Lch<T>>;
+pub type Oklaba<T> = AlphaColor<T, Oklab<T>>;
+pub type Oklcha<T> = AlphaColor<T, Oklch<T>>;
+pub type Luva<T> = AlphaColor<T, Luv<T>>;
+pub type Lchuva<T> = AlphaColor<T, Lchuv<T>>;
+pub type Hsluva<T> = AlphaColor<T, Hsluv<T>>;
+pub type Hpluva<T> = AlphaColor<T, Hpluv<T>>;
 
 impl<T: Channel, C: Color<T>> Color<T> for AlphaColor<T, C> {
     /// Clamps the components of the color to the range `(lo,hi)`.
@@ -68,7 +79,7 @@ impl<T: Channel, C: Color<T>> Color<T> for AlphaColor<T, C> {
 macro_rules! rgba{
     ( $r: expr, $g: expr, $b: expr, $a: expr ) => ({
         use $crate::{Rgba,Rgb};
-        Rgba{ c: Rgb{ r: $r, g: $g, b: $b }, a: $a } 
+        Rgba{ c: Rgb::new($r, $g, $b), a: $a }
     });
     ( $to_rgb: expr, $a: expr ) => ({
         use $crate::{Rgba,ToRgb};
@@ -404,6 +415,76 @@ impl<T:Clone + FloatChannel> ToRgba for Hsv<T> {
     }
 }
 
+impl<T: Channel> ToRgba for Hsl<T> {
+    #[inline]
+    fn to_rgba<U: Channel>(&self) -> Rgba<U>{
+        Rgba{c: self.to_rgb(), a: 1.0f32.to_channel()}
+    }
+}
+
+pub trait ToSrgba{
+    fn to_srgba<T: Channel>(&self) -> Srgba<T>;
+}
+
+impl<T: Channel, C: ToSrgb> ToSrgba for AlphaColor<T,C>{
+    #[inline]
+    fn to_srgba<U: Channel>(&self) -> Srgba<U>{
+        Srgba{c: self.c.to_srgb(), a: self.a.to_channel()}
+    }
+}
+
+impl<T: Channel> ToSrgba for Rgb<T> {
+    #[inline]
+    fn to_srgba<U: Channel>(&self) -> Srgba<U>{
+        Srgba{c: self.to_srgb(), a: 1.0f32.to_channel()}
+    }
+}
+
+impl<T: Channel> ToSrgba for Srgb<T> {
+    #[inline]
+    fn to_srgba<U: Channel>(&self) -> Srgba<U>{
+        Srgba{c: self.to_srgb(), a: 1.0f32.to_channel()}
+    }
+}
+
+impl<T: Channel> ToRgba for Srgb<T> {
+    #[inline]
+    fn to_rgba<U: Channel>(&self) -> Rgba<U>{
+        Rgba{c: self.to_rgb(), a: 1.0f32.to_channel()}
+    }
+}
+
+pub trait ToYCbCra{
+    fn to_ycbcra_with<T: Channel>(&self, matrix: YCbCrMatrix, range: YCbCrRange) -> YCbCra<T>;
+
+    /// Converts the color using the full range BT.601 matrix, as used by JPEG.
+    #[inline]
+    fn to_ycbcra<T: Channel>(&self) -> YCbCra<T> {
+        self.to_ycbcra_with(YCbCrMatrix::Bt601, YCbCrRange::Full)
+    }
+}
+
+impl<T: Channel, C: ToYCbCr> ToYCbCra for AlphaColor<T,C>{
+    #[inline]
+    fn to_ycbcra_with<U: Channel>(&self, matrix: YCbCrMatrix, range: YCbCrRange) -> YCbCra<U>{
+        YCbCra{c: self.c.to_ycbcr_with(matrix, range), a: self.a.to_channel()}
+    }
+}
+
+impl<T: Channel> ToYCbCra for Rgb<T> {
+    #[inline]
+    fn to_ycbcra_with<U: Channel>(&self, matrix: YCbCrMatrix, range: YCbCrRange) -> YCbCra<U>{
+        YCbCra{c: self.to_ycbcr_with(matrix, range), a: 1.0f32.to_channel()}
+    }
+}
+
+impl<T: Channel> ToYCbCra for Srgb<T> {
+    #[inline]
+    fn to_ycbcra_with<U: Channel>(&self, matrix: YCbCrMatrix, range: YCbCrRange) -> YCbCra<U>{
+        YCbCra{c: self.to_ycbcr_with(matrix, range), a: 1.0f32.to_channel()}
+    }
+}
+
 impl<T:Channel, C: Mul<Output=C>> Mul for AlphaColor<T,C> {
     type Output = AlphaColor<T,C>;
 
@@ -480,13 +561,13 @@ impl<T:Channel + Saturating, C: Saturating> Saturating for AlphaColor<T,C>{
 
 impl<T, C: AsRef<[T]>> Index<usize> for AlphaColor<T,C> {
     type Output = T;
-    fn index<'a>(&'a self, index: usize) -> &'a T {
+    fn index(&self, index: usize) -> &T {
         self.as_ref().index(index)
     }
 }
 
 impl<T, C: AsRef<[T]> + AsMut<[T]>> IndexMut<usize> for AlphaColor<T,C> {
-    fn index_mut<'a>(&'a mut self, index: usize) -> &'a mut T {
+    fn index_mut(&mut self, index: usize) -> &mut T {
         self.as_mut().index_mut(index)
     }
 }
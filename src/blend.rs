@@ -0,0 +1,278 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Blend modes, as specified by Compositing and Blending Level 1 and the PDF
+//! specification: https://www.w3.org/TR/compositing-1/#blending
+
+use num;
+use num::traits;
+
+use Channel;
+use {Rgb, Rgba};
+use space::RgbSpace;
+
+fn cast<T: num::NumCast, U: num::NumCast>(n: T) -> U {
+    traits::cast(n).unwrap()
+}
+
+/// A blend mode, which mixes the source color with the backdrop color where
+/// they overlap.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BlendMode {
+    /// The source color replaces the backdrop.
+    Normal,
+    /// Multiplies the colors, which always darkens.
+    Multiply,
+    /// Multiplies the complements of the colors, which always lightens.
+    Screen,
+    /// Multiplies or screens depending on the backdrop.
+    Overlay,
+    /// Keeps the darker of the components.
+    Darken,
+    /// Keeps the lighter of the components.
+    Lighten,
+    /// Brightens the backdrop to reflect the source.
+    ColorDodge,
+    /// Darkens the backdrop to reflect the source.
+    ColorBurn,
+    /// Multiplies or screens depending on the source.
+    HardLight,
+    /// Darkens or lightens depending on the source, like a diffused spotlight.
+    SoftLight,
+    /// The absolute difference of the components.
+    Difference,
+    /// Like `Difference`, but with lower contrast.
+    Exclusion,
+    /// The hue of the source with the saturation and luminosity of the
+    /// backdrop.
+    Hue,
+    /// The saturation of the source with the hue and luminosity of the
+    /// backdrop.
+    Saturation,
+    /// The hue and saturation of the source with the luminosity of the
+    /// backdrop.
+    Color,
+    /// The luminosity of the source with the hue and saturation of the
+    /// backdrop.
+    Luminosity,
+}
+
+impl BlendMode {
+    /// Returns true for the modes that are applied to each component
+    /// separately.
+    pub fn is_separable(self) -> bool {
+        !matches!(self, BlendMode::Hue | BlendMode::Saturation | BlendMode::Color | BlendMode::Luminosity)
+    }
+
+    /// Blends a single backdrop component `cb` with a source component `cs`.
+    fn separable(self, cb: f64, cs: f64) -> f64 {
+        match self {
+            BlendMode::Multiply => cb * cs,
+            BlendMode::Screen => cb + cs - cb * cs,
+            BlendMode::Overlay => BlendMode::HardLight.separable(cs, cb),
+            BlendMode::Darken => cb.min(cs),
+            BlendMode::Lighten => cb.max(cs),
+            BlendMode::ColorDodge => {
+                if cb == 0.0 { 0.0 } else if cs >= 1.0 { 1.0 } else { (cb / (1.0 - cs)).min(1.0) }
+            }
+            BlendMode::ColorBurn => {
+                if cb >= 1.0 { 1.0 } else if cs == 0.0 { 0.0 } else { 1.0 - ((1.0 - cb) / cs).min(1.0) }
+            }
+            BlendMode::HardLight => {
+                if cs <= 0.5 {
+                    BlendMode::Multiply.separable(cb, 2.0 * cs)
+                } else {
+                    BlendMode::Screen.separable(cb, 2.0 * cs - 1.0)
+                }
+            }
+            BlendMode::SoftLight => {
+                if cs <= 0.5 {
+                    cb - (1.0 - 2.0 * cs) * cb * (1.0 - cb)
+                } else {
+                    let d = if cb <= 0.25 { ((16.0 * cb - 12.0) * cb + 4.0) * cb } else { cb.sqrt() };
+                    cb + (2.0 * cs - 1.0) * (d - cb)
+                }
+            }
+            BlendMode::Difference => (cb - cs).abs(),
+            BlendMode::Exclusion => cb + cs - 2.0 * cb * cs,
+            _ => cs,
+        }
+    }
+
+    /// Blends a backdrop color `cb` with a source color `cs`, both with
+    /// components in `(0,1)`.
+    fn apply(self, cb: [f64; 3], cs: [f64; 3]) -> [f64; 3] {
+        match self {
+            BlendMode::Hue => set_lum(set_sat(cs, sat(cb)), lum(cb)),
+            BlendMode::Saturation => set_lum(set_sat(cb, sat(cs)), lum(cb)),
+            BlendMode::Color => set_lum(cs, lum(cb)),
+            BlendMode::Luminosity => set_lum(cb, lum(cs)),
+            _ => [self.separable(cb[0], cs[0]), self.separable(cb[1], cs[1]), self.separable(cb[2], cs[2])],
+        }
+    }
+}
+
+#[inline]
+fn lum(c: [f64; 3]) -> f64 {
+    0.3 * c[0] + 0.59 * c[1] + 0.11 * c[2]
+}
+
+/// Brings a color back into `(0,1)` while preserving its luminosity.
+fn clip_color(c: [f64; 3]) -> [f64; 3] {
+    let l = lum(c);
+    let n = c[0].min(c[1]).min(c[2]);
+    let x = c[0].max(c[1]).max(c[2]);
+    let mut c = c;
+    for v in c.iter_mut() {
+        if n < 0.0 {
+            *v = l + (*v - l) * l / (l - n);
+        }
+        if x > 1.0 {
+            *v = l + (*v - l) * (1.0 - l) / (x - l);
+        }
+    }
+    c
+}
+
+fn set_lum(c: [f64; 3], l: f64) -> [f64; 3] {
+    let d = l - lum(c);
+    clip_color([c[0] + d, c[1] + d, c[2] + d])
+}
+
+#[inline]
+fn sat(c: [f64; 3]) -> f64 {
+    c[0].max(c[1]).max(c[2]) - c[0].min(c[1]).min(c[2])
+}
+
+fn set_sat(c: [f64; 3], s: f64) -> [f64; 3] {
+    let max = c[0].max(c[1]).max(c[2]);
+    let min = c[0].min(c[1]).min(c[2]);
+    if max > min {
+        let scale = s / (max - min);
+        [(c[0] - min) * scale, (c[1] - min) * scale, (c[2] - min) * scale]
+    } else {
+        [0.0; 3]
+    }
+}
+
+/// Blending of a source color onto a backdrop.
+pub trait Blend {
+    /// Blends this color, as the source, onto `backdrop` with the given mode.
+    /// The result is then composited with the source-over operator, so a
+    /// translucent source only partially covers the backdrop.
+    fn blend(&self, backdrop: &Self, mode: BlendMode) -> Self;
+}
+
+impl<T:Channel, S:RgbSpace> Blend for Rgb<T, S> {
+    fn blend(&self, backdrop: &Rgb<T, S>, mode: BlendMode) -> Rgb<T, S> {
+        let c = mode.apply([backdrop.r.to_channel_f64(), backdrop.g.to_channel_f64(), backdrop.b.to_channel_f64()],
+                           [self.r.to_channel_f64(), self.g.to_channel_f64(), self.b.to_channel_f64()]);
+        Rgb::with_space(Channel::from(c[0]), Channel::from(c[1]), Channel::from(c[2]), S::default())
+    }
+}
+
+impl<T:Channel, S:RgbSpace> Blend for Rgba<T, S> {
+    fn blend(&self, backdrop: &Rgba<T, S>, mode: BlendMode) -> Rgba<T, S> {
+        let cb = [backdrop.c.r.to_channel_f64(), backdrop.c.g.to_channel_f64(), backdrop.c.b.to_channel_f64()];
+        let cs = [self.c.r.to_channel_f64(), self.c.g.to_channel_f64(), self.c.b.to_channel_f64()];
+        let (ab, as_) = (backdrop.a.to_channel_f64(), self.a.to_channel_f64());
+        let blended = mode.apply(cb, cs);
+        let alpha = as_ + ab * (1.0 - as_);
+        let component = |i: usize| -> T {
+            if alpha == 0.0 {
+                return cast(0);
+            }
+            // The source is only blended where the backdrop is opaque
+            let cs = (1.0 - ab) * cs[i] + ab * blended[i];
+            Channel::from((cs * as_ + cb[i] * ab * (1.0 - as_)) / alpha)
+        };
+        Rgba {
+            c: Rgb::with_space(component(0), component(1), component(2), S::default()),
+            a: Channel::from(alpha),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {Rgb, Rgba};
+    use super::{Blend, BlendMode};
+    use super::BlendMode::*;
+
+    fn assert_rgb_eq(a: Rgb<f64>, b: Rgb<f64>) {
+        assert!((a.r - b.r).abs() < 1e-4 && (a.g - b.g).abs() < 1e-4 && (a.b - b.b).abs() < 1e-4,
+                "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn test_separable() {
+        let backdrop = rgb!(0.2, 0.4, 0.8);
+        let source = rgb!(0.5, 0.1, 0.75);
+        let blend = |mode: BlendMode| source.blend(&backdrop, mode);
+        assert_rgb_eq(blend(Normal), source);
+        assert_rgb_eq(blend(Multiply), rgb!(0.1, 0.04, 0.6));
+        assert_rgb_eq(blend(Screen), rgb!(0.6, 0.46, 0.95));
+        assert_rgb_eq(blend(Overlay), rgb!(0.2, 0.08, 0.9));
+        assert_rgb_eq(blend(Darken), rgb!(0.2, 0.1, 0.75));
+        assert_rgb_eq(blend(Lighten), rgb!(0.5, 0.4, 0.8));
+        assert_rgb_eq(blend(ColorDodge), rgb!(0.4, 0.4444, 1.0));
+        assert_rgb_eq(blend(ColorBurn), rgb!(0.0, 0.0, 0.7333));
+        assert_rgb_eq(blend(HardLight), rgb!(0.2, 0.08, 0.9));
+        assert_rgb_eq(blend(SoftLight), rgb!(0.2, 0.208, 0.8472));
+        assert_rgb_eq(blend(Difference), rgb!(0.3, 0.3, 0.05));
+        assert_rgb_eq(blend(Exclusion), rgb!(0.5, 0.42, 0.35));
+    }
+
+    #[test]
+    fn test_non_separable() {
+        let red = rgb!(1.0, 0.0, 0.0);
+        let blue = rgb!(0.0, 0.0, 1.0);
+        let gray = rgb!(0.5, 0.5, 0.5);
+        assert_rgb_eq(gray.blend(&red, Luminosity), rgb!(1.0, 0.2857, 0.2857));
+        assert_rgb_eq(blue.blend(&red, Hue), rgb!(0.2135, 0.2135, 1.0));
+        assert_rgb_eq(gray.blend(&red, Saturation), rgb!(0.3, 0.3, 0.3));
+        assert_rgb_eq(red.blend(&gray, Color), rgb!(1.0, 0.2857, 0.2857));
+        // The backdrop luminosity is preserved by the other modes
+        let backdrop = rgb!(0.3, 0.6, 0.2);
+        for &mode in [Hue, Saturation, Color].iter() {
+            let c = rgb!(0.9, 0.1, 0.4).blend(&backdrop, mode);
+            let lum = |c: Rgb<f64>| 0.3 * c.r + 0.59 * c.g + 0.11 * c.b;
+            assert!((lum(c) - lum(backdrop)).abs() < 1e-9, "{:?}", mode);
+        }
+    }
+
+    #[test]
+    fn test_integer_channels() {
+        assert_eq!(rgb!(255u8, 128, 128).blend(&rgb!(128, 255, 128), Multiply), rgb!(128, 128, 64));
+        assert_eq!(rgb!(0xFFFFu16, 0, 0x8000).blend(&rgb!(0x8000, 0x8000, 0xFFFF), Screen),
+                   rgb!(0xFFFF, 0x8000, 0xFFFF));
+    }
+
+    #[test]
+    fn test_alpha() {
+        let backdrop = rgba!(0.2f64, 0.4, 0.8, 1.0);
+        let source = rgba!(0.5f64, 0.1, 0.75, 0.5);
+        assert_eq!(source.blend(&backdrop, Normal), source.over(&backdrop));
+        let c = source.blend(&backdrop, Multiply);
+        assert_rgb_eq(c.c, rgb!(0.15, 0.22, 0.7));
+        assert_eq!(c.a, 1.0);
+        // Over a transparent backdrop the mode has no effect
+        assert_eq!(source.blend(&rgba!(0.2, 0.4, 0.8, 0.0), Difference), source);
+        // A transparent source leaves the backdrop unchanged
+        assert_eq!(rgba!(0.5, 0.1, 0.75, 0.0).blend(&backdrop, Screen), backdrop);
+        let u8_color: Rgba<u8> = rgba!(255, 0, 0, 255).blend(&rgba!(0, 0, 255, 255), Lighten);
+        assert_eq!(u8_color, rgba!(255, 0, 255, 255));
+    }
+}
@@ -0,0 +1,296 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Conversion of whole pixel buffers into caller provided slices.
+//!
+//! The results are the same as converting each pixel on its own, but
+//! conversions from `u8` channels go through a lookup table, as does decoding
+//! 8 bit sRGB, and `f32` to `u8` conversions take a branch free path.
+//!
+//! All functions panic if the destination does not have room for exactly as
+//! many pixels as the source.
+
+use std::any::TypeId;
+
+use Channel;
+use {Rgb, Rgba, ToRgb, Srgb, ToSrgb, Hsv, ToHsv};
+use {YCbCr, ToYCbCr, YCbCrMatrix, YCbCrRange};
+use packed::Layout;
+use space::RgbSpace;
+use srgb;
+
+#[inline]
+fn is<A: 'static, B: 'static>() -> bool {
+    TypeId::of::<A>() == TypeId::of::<B>()
+}
+
+#[inline]
+fn check_len(src: usize, dst: usize) {
+    assert_eq!(src, dst, "the source and destination have a different number of pixels");
+}
+
+/// How channels of one type are converted to `U`, picked once per buffer.
+enum Path<U> {
+    /// A table indexed by the `u8` source channel.
+    Table(Vec<U>),
+    /// From `f32` to `u8`.
+    FloatToByte,
+    Generic,
+}
+
+impl<U:Channel + 'static> Path<U> {
+    fn new<T:Channel + 'static>() -> Path<U> {
+        if is::<T, u8>() && !is::<U, u8>() {
+            Path::Table((0..=0xFF_u8).map(Channel::from).collect())
+        } else if is::<T, f32>() && is::<U, u8>() {
+            Path::FloatToByte
+        } else {
+            Path::Generic
+        }
+    }
+
+    #[inline]
+    fn convert<T:Channel>(&self, value: T) -> U {
+        match *self {
+            Path::Table(ref table) => table[value.to_channel_u8() as usize],
+            // The same as `to_channel_u8`, as clamping NaN gives NaN, which
+            // casts to zero
+            Path::FloatToByte => Channel::from((value.to_channel_f32().clamp(0.0, 1.0) * 255.0).round() as u8),
+            Path::Generic => Channel::from(value),
+        }
+    }
+
+    #[inline]
+    fn rgb<T:Channel, S:RgbSpace>(&self, c: &Rgb<T, S>) -> Rgb<U, S> {
+        Rgb::with_space(self.convert(c.r), self.convert(c.g), self.convert(c.b), S::default())
+    }
+
+    #[inline]
+    fn rgba<T:Channel, S:RgbSpace>(&self, c: &Rgba<T, S>) -> Rgba<U, S> {
+        Rgba { c: self.rgb(&c.c), a: self.convert(c.a) }
+    }
+}
+
+/// Converts a buffer of channels, such as the samples of an image.
+pub fn convert_channels<T:Channel + 'static, U:Channel + 'static>(src: &[T], dst: &mut [U]) {
+    check_len(src.len(), dst.len());
+    let path = Path::new::<T>();
+    for (d, &s) in dst.iter_mut().zip(src) {
+        *d = path.convert(s);
+    }
+}
+
+/// Converts a buffer of RGB colors to another channel type.
+pub fn convert_rgb<T, U, S>(src: &[Rgb<T, S>], dst: &mut [Rgb<U, S>])
+    where T: Channel + 'static, U: Channel + 'static, S: RgbSpace
+{
+    check_len(src.len(), dst.len());
+    let path = Path::new::<T>();
+    for (d, s) in dst.iter_mut().zip(src) {
+        *d = path.rgb(s);
+    }
+}
+
+/// Converts a buffer of RGBA colors to another channel type.
+pub fn convert_rgba<T, U, S>(src: &[Rgba<T, S>], dst: &mut [Rgba<U, S>])
+    where T: Channel + 'static, U: Channel + 'static, S: RgbSpace
+{
+    check_len(src.len(), dst.len());
+    let path = Path::new::<T>();
+    for (d, s) in dst.iter_mut().zip(src) {
+        *d = path.rgba(s);
+    }
+}
+
+/// Converts interleaved 8 bit `r, g, b` data, three bytes per pixel.
+pub fn rgb_from_bytes<U:Channel + 'static, S:RgbSpace>(src: &[u8], dst: &mut [Rgb<U, S>]) {
+    check_len(src.len(), dst.len() * 3);
+    let path = Path::new::<u8>();
+    for (d, s) in dst.iter_mut().zip(src.chunks(3)) {
+        *d = Rgb::with_space(path.convert(s[0]), path.convert(s[1]), path.convert(s[2]), S::default());
+    }
+}
+
+/// Converts RGB colors to interleaved 8 bit `r, g, b` data, three bytes per
+/// pixel.
+pub fn rgb_to_bytes<T:Channel + 'static, S:RgbSpace>(src: &[Rgb<T, S>], dst: &mut [u8]) {
+    check_len(src.len() * 3, dst.len());
+    let path = Path::<u8>::new::<T>();
+    for (d, s) in dst.chunks_mut(3).zip(src) {
+        d[0] = path.convert(s.r);
+        d[1] = path.convert(s.g);
+        d[2] = path.convert(s.b);
+    }
+}
+
+/// Converts interleaved 8 bit data with four bytes per pixel, in the order
+/// of the channels of `layout`. Layouts without alpha give opaque colors.
+pub fn rgba_from_bytes<U:Channel + 'static, S:RgbSpace>(src: &[u8], layout: Layout, dst: &mut [Rgba<U, S>]) {
+    check_len(src.len(), dst.len() * 4);
+    let path = Path::new::<u8>();
+    for (d, s) in dst.iter_mut().zip(src.chunks(4)) {
+        let bytes = Rgba::<u8, S>::from_packed(u32::from_be_bytes([s[0], s[1], s[2], s[3]]), layout);
+        *d = path.rgba(&bytes);
+    }
+}
+
+/// Converts RGBA colors to interleaved 8 bit data with four bytes per pixel,
+/// in the order of the channels of `layout`.
+pub fn rgba_to_bytes<T:Channel + 'static, S:RgbSpace>(src: &[Rgba<T, S>], layout: Layout, dst: &mut [u8]) {
+    check_len(src.len() * 4, dst.len());
+    let path = Path::<u8>::new::<T>();
+    for (d, s) in dst.chunks_mut(4).zip(src) {
+        d.copy_from_slice(&path.rgba(s).to_packed(layout).to_be_bytes());
+    }
+}
+
+/// Decodes sRGB colors to linear RGB. 8 bit colors are decoded through a
+/// lookup table.
+pub fn srgb_to_rgb<T:Channel + 'static, U:Channel>(src: &[Srgb<T>], dst: &mut [Rgb<U>]) {
+    check_len(src.len(), dst.len());
+    if is::<T, u8>() {
+        let table: Vec<U> = (0..=0xFF_u8).map(|i| Channel::from(srgb::decode(i.to_channel_f64()))).collect();
+        let decode = |c: T| table[c.to_channel_u8() as usize];
+        for (d, s) in dst.iter_mut().zip(src) {
+            *d = Rgb::new(decode(s.r), decode(s.g), decode(s.b));
+        }
+    } else {
+        to_rgb(src, dst);
+    }
+}
+
+/// Converts colors of any type to linear RGB.
+pub fn to_rgb<C:ToRgb, U:Channel>(src: &[C], dst: &mut [Rgb<U>]) {
+    check_len(src.len(), dst.len());
+    for (d, s) in dst.iter_mut().zip(src) {
+        *d = s.to_rgb();
+    }
+}
+
+/// Converts colors of any type to sRGB.
+pub fn to_srgb<C:ToSrgb, U:Channel>(src: &[C], dst: &mut [Srgb<U>]) {
+    check_len(src.len(), dst.len());
+    for (d, s) in dst.iter_mut().zip(src) {
+        *d = s.to_srgb();
+    }
+}
+
+/// Converts colors of any type to HSV.
+pub fn to_hsv<C:ToHsv, U:Channel>(src: &[C], dst: &mut [Hsv<U>]) {
+    check_len(src.len(), dst.len());
+    for (d, s) in dst.iter_mut().zip(src) {
+        *d = s.to_hsv();
+    }
+}
+
+/// Converts colors of any type to Y'CbCr with the given matrix and range.
+pub fn to_ycbcr<C:ToYCbCr, U:Channel>(src: &[C], matrix: YCbCrMatrix, range: YCbCrRange, dst: &mut [YCbCr<U>]) {
+    check_len(src.len(), dst.len());
+    for (d, s) in dst.iter_mut().zip(src) {
+        *d = s.to_ycbcr_with(matrix, range);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {Channel, Rgb, Rgba, ToRgb, Srgb, Hsv, ToHsv, YCbCr, ToYCbCr, YCbCrMatrix, YCbCrRange, Layout};
+    use super::*;
+
+    fn pixels() -> Vec<Rgb<u8>> {
+        (0..256).map(|i| Rgb::new(i as u8, (i * 7) as u8, (255 - i) as u8)).collect()
+    }
+
+    #[test]
+    fn test_channels() {
+        let bytes: Vec<u8> = (0..=0xFF).collect();
+        let mut floats = vec![0.0f32; 256];
+        convert_channels(&bytes, &mut floats);
+        let mut back = vec![0u8; 256];
+        convert_channels(&floats, &mut back);
+        assert_eq!(back, bytes);
+        for i in 0..256 {
+            assert_eq!(floats[i], bytes[i].to_channel_f32());
+        }
+        let odd = [-1.0f32, 2.0, f32::NAN, 0.5, 0.2];
+        let mut out = [0u8; 5];
+        convert_channels(&odd, &mut out);
+        assert_eq!(out, [0, 0xFF, 0, 0x80, 0x33]);
+        let mut wide = [0u16; 5];
+        convert_channels(&odd, &mut wide);
+        assert_eq!(wide, [0, 0xFFFF, 0, 0x8000, 0x3333]);
+    }
+
+    #[test]
+    fn test_rgb() {
+        let src = pixels();
+        let mut dst = vec![Rgb::new(0.0f32, 0.0, 0.0); src.len()];
+        convert_rgb(&src, &mut dst);
+        let expected: Vec<Rgb<f32>> = src.iter().map(|c| c.to_rgb()).collect();
+        assert_eq!(dst, expected);
+        let mut back = vec![Rgb::new(0u8, 0, 0); src.len()];
+        convert_rgb(&dst, &mut back);
+        assert_eq!(back, src);
+        let src: Vec<Rgba<u8>> = src.iter().map(|&c| Rgba { c, a: c.g }).collect();
+        let mut dst = vec![Rgba { c: Rgb::new(0u16, 0, 0), a: 0 }; src.len()];
+        convert_rgba(&src, &mut dst);
+        assert!(dst.iter().zip(&src).all(|(d, s)| d.c.r == s.c.r.to_channel_u16() && d.a == s.a.to_channel_u16()));
+    }
+
+    #[test]
+    fn test_bytes() {
+        let bytes = [0x11u8, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88];
+        let mut rgba = [Rgba { c: Rgb::new(0u8, 0, 0), a: 0 }; 2];
+        rgba_from_bytes(&bytes, Layout::Bgra, &mut rgba);
+        assert_eq!(rgba[0], rgba!(0x33, 0x22, 0x11, 0x44));
+        rgba_from_bytes(&bytes, Layout::Xrgb, &mut rgba);
+        assert_eq!(rgba[1], rgba!(0x66, 0x77, 0x88, 0xFF));
+        let mut out = [0u8; 8];
+        rgba_to_bytes(&rgba, Layout::Argb, &mut out);
+        assert_eq!(out, [0xFF, 0x22, 0x33, 0x44, 0xFF, 0x66, 0x77, 0x88]);
+        let mut rgb = [Rgb::new(0.0f32, 0.0, 0.0); 2];
+        rgb_from_bytes(&bytes[..6], &mut rgb);
+        assert_eq!(rgb[1], Rgb::new(0x44u8, 0x55, 0x66).to_rgb());
+        let mut out = [0u8; 6];
+        rgb_to_bytes(&rgb, &mut out);
+        assert_eq!(out, bytes[..6]);
+    }
+
+    #[test]
+    fn test_color_types() {
+        let src: Vec<Srgb<u8>> = pixels().iter().map(|c| Srgb::new(c.r, c.g, c.b)).collect();
+        let mut linear = vec![Rgb::new(0.0f32, 0.0, 0.0); src.len()];
+        srgb_to_rgb(&src, &mut linear);
+        let expected: Vec<Rgb<f32>> = src.iter().map(|c| c.to_rgb()).collect();
+        assert_eq!(linear, expected);
+        let mut encoded = vec![Srgb::new(0u8, 0, 0); src.len()];
+        to_srgb(&linear, &mut encoded);
+        assert_eq!(encoded, src);
+        let mut hsv = vec![Hsv::new(::angle::Deg(0.0f32), 0.0, 0.0); src.len()];
+        to_hsv(&pixels(), &mut hsv);
+        assert!(hsv.iter().zip(pixels()).all(|(h, c)| *h == c.to_hsv()));
+        let mut ycbcr = vec![YCbCr::new(0u8, 0, 0); src.len()];
+        to_ycbcr(&src, YCbCrMatrix::Bt709, YCbCrRange::Limited, &mut ycbcr);
+        assert!(ycbcr.iter().zip(&src).all(|(y, c)| *y == c.to_ycbcr_with(YCbCrMatrix::Bt709, YCbCrRange::Limited)));
+        let mut rgb = vec![Rgb::new(0u8, 0, 0); src.len()];
+        to_rgb(&ycbcr, &mut rgb);
+        assert_eq!(rgb[0], ycbcr[0].to_rgb());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_length_mismatch() {
+        convert_channels(&[0u8; 3], &mut [0.0f32; 4]);
+    }
+}
@@ -14,9 +14,21 @@
 // limitations under the License.
 
 //! Color channel conversions and utility methods
+//!
+//! Conversions between integer channels and from floats to integers round to
+//! the nearest value. Floats outside of `(0,1)` saturate to the range of the
+//! integer channel and `NaN` becomes zero. Widening an integer channel and
+//! narrowing it back, or converting an integer channel to a float and back,
+//! gives the original value.
+//!
+//! `i8` and `i16` are signed normalized (SNORM) channels in `(-1,1)`. As in
+//! Direct3D and Vulkan, both the minimum value and the one above it map to
+//! `-1`, so zero is exact. Converting a negative SNORM value to an unsigned
+//! channel saturates to zero.
 
 use num::{Float, NumCast, Num, zero, one};
-use std::{u8, u16};
+
+use half::f16;
 
 pub trait Channel: Copy + Sized + Clone + PartialOrd<Self> + Num + NumCast {
     fn from<T:Channel>(chan: T) -> Self;
@@ -26,6 +38,13 @@ pub trait Channel: Copy + Sized + Clone + PartialOrd<Self> + Num + NumCast {
     fn to_channel_f32(self) -> f32;
     fn to_channel_f64(self) -> f64;
 
+    // These go through `f64` unless overridden, so that implementations
+    // written before they were added keep working. `f64` overrides them all.
+    #[inline] fn to_channel_u32(self) -> u32 { Channel::from(self.to_channel_f64()) }
+    #[inline] fn to_channel_i8(self)  -> i8  { Channel::from(self.to_channel_f64()) }
+    #[inline] fn to_channel_i16(self) -> i16 { Channel::from(self.to_channel_f64()) }
+    #[inline] fn to_channel_f16(self) -> f16 { Channel::from(self.to_channel_f64()) }
+
     fn invert_channel(self) -> Self;
 
     fn clamp(self, lo: Self, hi: Self) -> Self {
@@ -52,14 +71,34 @@ pub trait Channel: Copy + Sized + Clone + PartialOrd<Self> + Num + NumCast {
     
     #[inline]
     fn mix(self, rhs: Self, value: Self) -> Self {
-        (self + (rhs - self).normalized_mul(value))
+        self + (rhs - self).normalized_mul(value)
     }
 }
 
+/// Converts a float in `(0,1)` to an integer channel with the maximum `max`,
+/// rounding to the nearest value and saturating. `NaN` becomes zero.
+#[inline]
+fn quantize_f32(value: f32, max: f32) -> f32 {
+    if value > 0.0 { (value.min(1.0) * max).round() } else { 0.0 }
+}
+
+#[inline]
+fn quantize_f64(value: f64, max: f64) -> f64 {
+    if value > 0.0 { (value.min(1.0) * max).round() } else { 0.0 }
+}
+
+/// Converts a float in `(-1,1)` to a SNORM channel with the maximum `max`,
+/// rounding to the nearest value and saturating. `NaN` becomes zero.
+#[inline]
+fn quantize_snorm(value: f64, max: f64) -> f64 {
+    if value.is_nan() { 0.0 } else { (value.clamp(-1.0, 1.0) * max).round() }
+}
+
 impl Channel for u8 {
     #[inline] fn from<T:Channel>(chan: T) -> u8 { chan.to_channel_u8() }
     #[inline] fn to_channel_u8(self)  -> u8  { self }
     #[inline] fn to_channel_u16(self) -> u16 { ((self as u16) << 8) | self as u16 }
+    #[inline] fn to_channel_u32(self) -> u32 { (self as u32) * 0x0101_0101 }
     #[inline] fn to_channel_f32(self) -> f32 { (self as f32) / (0xFF_u8 as f32) }
     #[inline] fn to_channel_f64(self) -> f64 { (self as f64) / (0xFF_u8 as f64) }
 
@@ -70,20 +109,100 @@ impl Channel for u8 {
 
 impl Channel for u16 {
     #[inline] fn from<T:Channel>(chan: T) -> u16 { chan.to_channel_u16() }
-    #[inline] fn to_channel_u8(self)  -> u8  { (self >> 8) as u8 }
+    #[inline] fn to_channel_u8(self)  -> u8  { ((self as u32 + 128) / 257) as u8 }
     #[inline] fn to_channel_u16(self) -> u16 { self }
-    #[inline] fn to_channel_f32(self) -> f32 { (self / 0xFFFF) as f32 }
-    #[inline] fn to_channel_f64(self) -> f64 { (self / 0xFFFF) as f64 }
+    #[inline] fn to_channel_u32(self) -> u32 { (self as u32) * 0x0001_0001 }
+    #[inline] fn to_channel_f32(self) -> f32 { (self as f32) / (0xFFFF_u16 as f32) }
+    #[inline] fn to_channel_f64(self) -> f64 { (self as f64) / (0xFFFF_u16 as f64) }
 
     #[inline] fn invert_channel(self) -> u16 { !self }
     
     #[inline] fn max() -> u16{ u16::MAX }
 }
 
+impl Channel for u32 {
+    #[inline] fn from<T:Channel>(chan: T) -> u32 { chan.to_channel_u32() }
+    #[inline] fn to_channel_u8(self)  -> u8  { ((self as u64 + 0x80_8080) / 0x0101_0101) as u8 }
+    #[inline] fn to_channel_u16(self) -> u16 { ((self as u64 + 0x8000) / 0x0001_0001) as u16 }
+    #[inline] fn to_channel_u32(self) -> u32 { self }
+    #[inline] fn to_channel_f32(self) -> f32 { self.to_channel_f64() as f32 }
+    #[inline] fn to_channel_f64(self) -> f64 { (self as f64) / (0xFFFF_FFFF_u32 as f64) }
+
+    #[inline] fn invert_channel(self) -> u32 { !self }
+
+    /// Multiplies in `f64`, as `f32` cannot represent every `u32`.
+    #[inline]
+    fn normalized_mul(self, rhs: Self) -> Self {
+        Channel::from(self.to_channel_f64() * rhs.to_channel_f64())
+    }
+
+    #[inline]
+    fn normalized_div(self, rhs: Self) -> Self {
+        Channel::from(self.to_channel_f64() / rhs.to_channel_f64())
+    }
+
+    #[inline] fn max() -> u32{ u32::MAX }
+}
+
+impl Channel for i8 {
+    #[inline] fn from<T:Channel>(chan: T) -> i8 { chan.to_channel_i8() }
+    #[inline] fn to_channel_u8(self)  -> u8  { self.to_channel_f64().to_channel_u8() }
+    #[inline] fn to_channel_u16(self) -> u16 { self.to_channel_f64().to_channel_u16() }
+    #[inline] fn to_channel_i8(self)  -> i8  { self }
+    #[inline] fn to_channel_f32(self) -> f32 { ((self as f32) / (0x7F_i8 as f32)).max(-1.0) }
+    #[inline] fn to_channel_f64(self) -> f64 { ((self as f64) / (0x7F_i8 as f64)).max(-1.0) }
+
+    /// Returns `1 - self`, saturating to `1` for negative values.
+    #[inline] fn invert_channel(self) -> i8 { (0x7F - (self as i16)).min(0x7F) as i8 }
+
+    #[inline] fn max() -> i8{ i8::MAX }
+}
+
+impl Channel for i16 {
+    #[inline] fn from<T:Channel>(chan: T) -> i16 { chan.to_channel_i16() }
+    #[inline] fn to_channel_u8(self)  -> u8  { self.to_channel_f64().to_channel_u8() }
+    #[inline] fn to_channel_u16(self) -> u16 { self.to_channel_f64().to_channel_u16() }
+    #[inline] fn to_channel_i16(self) -> i16 { self }
+    #[inline] fn to_channel_f32(self) -> f32 { ((self as f32) / (0x7FFF_i16 as f32)).max(-1.0) }
+    #[inline] fn to_channel_f64(self) -> f64 { ((self as f64) / (0x7FFF_i16 as f64)).max(-1.0) }
+
+    /// Returns `1 - self`, saturating to `1` for negative values.
+    #[inline] fn invert_channel(self) -> i16 { (0x7FFF - (self as i32)).min(0x7FFF) as i16 }
+
+    #[inline] fn max() -> i16{ i16::MAX }
+}
+
+impl Channel for f16 {
+    #[inline] fn from<T:Channel>(chan: T) -> f16 { chan.to_channel_f16() }
+    #[inline] fn to_channel_u8(self)  -> u8  { self.to_f32().to_channel_u8() }
+    #[inline] fn to_channel_u16(self) -> u16 { self.to_f32().to_channel_u16() }
+    #[inline] fn to_channel_f16(self) -> f16 { self }
+    #[inline] fn to_channel_f32(self) -> f32 { self.to_f32() }
+    #[inline] fn to_channel_f64(self) -> f64 { self.to_f64() }
+
+    #[inline] fn invert_channel(self) -> f16 { f16::ONE - self }
+
+    #[inline]
+    fn normalized_mul(self, rhs: Self) -> Self {
+        self * rhs
+    }
+
+    #[inline]
+    fn normalized_div(self, rhs: Self) -> Self {
+        self / rhs
+    }
+
+    #[inline] fn max() -> f16{ f16::ONE }
+}
+
 impl Channel for f32 {
     #[inline] fn from<T:Channel>(chan: T) -> f32 { chan.to_channel_f32() }
-    #[inline] fn to_channel_u8(self)  -> u8  { (self * (0xFF_u8 as f32)) as u8 }
-    #[inline] fn to_channel_u16(self) -> u16 { (self * (0xFFFF_u16 as f32)) as u16 }
+    #[inline] fn to_channel_u8(self)  -> u8  { quantize_f32(self, 0xFF_u8 as f32) as u8 }
+    #[inline] fn to_channel_u16(self) -> u16 { quantize_f32(self, 0xFFFF_u16 as f32) as u16 }
+    #[inline] fn to_channel_u32(self) -> u32 { quantize_f64(self as f64, 0xFFFF_FFFF_u32 as f64) as u32 }
+    #[inline] fn to_channel_i8(self)  -> i8  { quantize_snorm(self as f64, 0x7F_i8 as f64) as i8 }
+    #[inline] fn to_channel_i16(self) -> i16 { quantize_snorm(self as f64, 0x7FFF_i16 as f64) as i16 }
+    #[inline] fn to_channel_f16(self) -> f16 { f16::from_f32(self) }
     #[inline] fn to_channel_f32(self) -> f32 { self }
     #[inline] fn to_channel_f64(self) -> f64 { self as f64 }
 
@@ -104,8 +223,12 @@ impl Channel for f32 {
 
 impl Channel for f64 {
     #[inline] fn from<T:Channel>(chan: T) -> f64 { chan.to_channel_f64() }
-    #[inline] fn to_channel_u8(self)  -> u8  { (self * (0xFF_u8 as f64)) as u8 }
-    #[inline] fn to_channel_u16(self) -> u16 { (self * (0xFFFF_u16 as f64)) as u16 }
+    #[inline] fn to_channel_u8(self)  -> u8  { quantize_f64(self, 0xFF_u8 as f64) as u8 }
+    #[inline] fn to_channel_u16(self) -> u16 { quantize_f64(self, 0xFFFF_u16 as f64) as u16 }
+    #[inline] fn to_channel_u32(self) -> u32 { quantize_f64(self, 0xFFFF_FFFF_u32 as f64) as u32 }
+    #[inline] fn to_channel_i8(self)  -> i8  { quantize_snorm(self, 0x7F_i8 as f64) as i8 }
+    #[inline] fn to_channel_i16(self) -> i16 { quantize_snorm(self, 0x7FFF_i16 as f64) as i16 }
+    #[inline] fn to_channel_f16(self) -> f16 { f16::from_f64(self) }
     #[inline] fn to_channel_f32(self) -> f32 { self as f32 }
     #[inline] fn to_channel_f64(self) -> f64 { self }
 
@@ -137,6 +260,7 @@ impl FloatChannel for f64 {}
 #[cfg(test)]
 mod tests {
     use super::Channel;
+    use half::f16;
 
     #[test]
     fn test_to_channel_u8() {
@@ -171,7 +295,8 @@ mod tests {
         assert_eq!(0x0000_u16.to_channel_u8(), 0x00_u8);
         assert_eq!(0x3300_u16.to_channel_u8(), 0x33_u8);
         assert_eq!(0x6666_u16.to_channel_u8(), 0x66_u8);
-        assert_eq!(0xAA00_u16.to_channel_u8(), 0xAA_u8);
+        assert_eq!(0xAA00_u16.to_channel_u8(), 0xA9_u8);
+        assert_eq!(0xAA80_u16.to_channel_u8(), 0xAA_u8);
         assert_eq!(0xFFFF_u16.to_channel_u8(), 0xFF_u8);
 
         assert_eq!(0x0000_u16.to_channel_u16(), 0x0000_u16);
@@ -181,9 +306,11 @@ mod tests {
         assert_eq!(0xFFFF_u16.to_channel_u16(), 0xFFFF_u16);
 
         assert_eq!(0x0000_u16.to_channel_f32(), 0f32);
+        assert_eq!(0x7FFF_u16.to_channel_f32(), 0x7FFF as f32 / 0xFFFF as f32);
         assert_eq!(0xFFFF_u16.to_channel_f32(), 1f32);
 
         assert_eq!(0x0000_u16.to_channel_f64(), 0f64);
+        assert_eq!(0x7FFF_u16.to_channel_f64(), 0x7FFF as f64 / 0xFFFF as f64);
         assert_eq!(0xFFFF_u16.to_channel_f64(), 1f64);
     }
 
@@ -197,14 +324,14 @@ mod tests {
     #[test]
     fn test_to_channel_f32() {
         assert_eq!(0.00f32.to_channel_u8(), 0x00);
-        assert_eq!(0.25f32.to_channel_u8(), 0x3F);
-        assert_eq!(0.50f32.to_channel_u8(), 0x7F);
+        assert_eq!(0.25f32.to_channel_u8(), 0x40);
+        assert_eq!(0.50f32.to_channel_u8(), 0x80);
         assert_eq!(0.75f32.to_channel_u8(), 0xBF);
         assert_eq!(1.00f32.to_channel_u8(), 0xFF);
 
         assert_eq!(0.00f32.to_channel_u16(), 0x0000);
-        assert_eq!(0.25f32.to_channel_u16(), 0x3FFF);
-        assert_eq!(0.50f32.to_channel_u16(), 0x7FFF);
+        assert_eq!(0.25f32.to_channel_u16(), 0x4000);
+        assert_eq!(0.50f32.to_channel_u16(), 0x8000);
         assert_eq!(0.75f32.to_channel_u16(), 0xBFFF);
         assert_eq!(1.00f32.to_channel_u16(), 0xFFFF);
 
@@ -225,14 +352,14 @@ mod tests {
     #[test]
     fn test_to_channel_f64() {
         assert_eq!(0.00f64.to_channel_u8(), 0x00);
-        assert_eq!(0.25f64.to_channel_u8(), 0x3F);
-        assert_eq!(0.50f64.to_channel_u8(), 0x7F);
+        assert_eq!(0.25f64.to_channel_u8(), 0x40);
+        assert_eq!(0.50f64.to_channel_u8(), 0x80);
         assert_eq!(0.75f64.to_channel_u8(), 0xBF);
         assert_eq!(1.00f64.to_channel_u8(), 0xFF);
 
         assert_eq!(0.00f64.to_channel_u16(), 0x0000);
-        assert_eq!(0.25f64.to_channel_u16(), 0x3FFF);
-        assert_eq!(0.50f64.to_channel_u16(), 0x7FFF);
+        assert_eq!(0.25f64.to_channel_u16(), 0x4000);
+        assert_eq!(0.50f64.to_channel_u16(), 0x8000);
         assert_eq!(0.75f64.to_channel_u16(), 0xBFFF);
         assert_eq!(1.00f64.to_channel_u16(), 0xFFFF);
 
@@ -249,4 +376,126 @@ mod tests {
         assert_eq!(0.50f64.invert_channel(), 0.50f64);
         assert_eq!(1.00f64.invert_channel(), 0.00f64);
     }
+
+    #[test]
+    fn test_saturation() {
+        assert_eq!((-0.5f32).to_channel_u8(), 0x00);
+        assert_eq!(1.5f32.to_channel_u8(), 0xFF);
+        assert_eq!(f32::INFINITY.to_channel_u16(), 0xFFFF);
+        assert_eq!(f32::NEG_INFINITY.to_channel_u16(), 0x0000);
+        assert_eq!((-0.5f64).to_channel_u16(), 0x0000);
+        assert_eq!(2.0f64.to_channel_u16(), 0xFFFF);
+        assert_eq!(f64::NAN.to_channel_u8(), 0x00);
+        assert_eq!(f64::NAN.to_channel_u16(), 0x0000);
+        assert_eq!(f32::NAN.to_channel_u8(), 0x00);
+        assert!(f64::NAN.to_channel_f32().is_nan());
+    }
+
+    #[test]
+    fn test_round_trip_u8() {
+        for i in 0..=0xFF_u8 {
+            assert_eq!(i.to_channel_u16().to_channel_u8(), i);
+            assert_eq!(i.to_channel_f32().to_channel_u8(), i);
+            assert_eq!(i.to_channel_f64().to_channel_u8(), i);
+            assert_eq!(i.to_channel_f32().to_channel_u16(), i.to_channel_u16());
+            assert_eq!(i.to_channel_f64().to_channel_u16(), i.to_channel_u16());
+        }
+    }
+
+    #[test]
+    fn test_round_trip_u16() {
+        for i in 0..=0xFFFF_u16 {
+            assert_eq!(i.to_channel_f32().to_channel_u16(), i);
+            assert_eq!(i.to_channel_f64().to_channel_u16(), i);
+            // Narrowing rounds to the nearest u8, the same as through a float
+            let expected = (i as f64 / 257.0).round() as u8;
+            assert_eq!(i.to_channel_u8(), expected);
+            assert_eq!(i.to_channel_f64().to_channel_u8(), expected);
+        }
+    }
+
+    #[test]
+    fn test_u32() {
+        assert_eq!(0x80_u8.to_channel_u32(), 0x8080_8080);
+        assert_eq!(0x1234_u16.to_channel_u32(), 0x1234_1234);
+        assert_eq!(0x8080_8080_u32.to_channel_u8(), 0x80);
+        assert_eq!(0x807F_FFFF_u32.to_channel_u8(), 0x80);
+        assert_eq!(0x1234_1234_u32.to_channel_u16(), 0x1234);
+        assert_eq!(0xFFFF_FFFF_u32.to_channel_f64(), 1.0);
+        assert_eq!(0.5f64.to_channel_u32(), 0x8000_0000);
+        assert_eq!(2.0f32.to_channel_u32(), 0xFFFF_FFFF);
+        assert_eq!(0xFFFF_FFFF_u32.invert_channel(), 0);
+        for i in 0..=0xFFFF_u16 {
+            assert_eq!(i.to_channel_u32().to_channel_u16(), i);
+        }
+        for i in (0..=0xFFFF_FFFF_u32).step_by(0x0001_0003) {
+            assert_eq!(i.to_channel_f64().to_channel_u32(), i);
+        }
+    }
+
+    #[test]
+    fn test_snorm() {
+        assert_eq!(0x7F_i8.to_channel_f32(), 1.0);
+        assert_eq!((-0x7F_i8).to_channel_f32(), -1.0);
+        assert_eq!((-0x80_i8).to_channel_f32(), -1.0);
+        assert_eq!(0_i8.to_channel_f64(), 0.0);
+        assert_eq!((-0x8000_i16).to_channel_f64(), -1.0);
+        assert_eq!((-1.0f32).to_channel_i8(), -0x7F);
+        assert_eq!((-2.0f64).to_channel_i16(), -0x7FFF);
+        assert_eq!(f64::NAN.to_channel_i8(), 0);
+        assert_eq!(0.5f32.to_channel_i8(), 0x40);
+        // Negative values saturate when converted to unsigned channels
+        assert_eq!((-0x40_i8).to_channel_u8(), 0);
+        assert_eq!(0x7F_i8.to_channel_u8(), 0xFF);
+        assert_eq!(0xFF_u8.to_channel_i16(), 0x7FFF);
+        assert_eq!(0x7FFF_i16.to_channel_i8(), 0x7F);
+        assert_eq!((-0x7FFF_i16).to_channel_i8(), -0x7F);
+        assert_eq!(0_i8.invert_channel(), 0x7F);
+        assert_eq!(0x7F_i8.invert_channel(), 0);
+        assert_eq!((-0x7F_i8).invert_channel(), 0x7F);
+        assert_eq!(0x7FFF_i16.invert_channel(), 0);
+    }
+
+    #[test]
+    fn test_round_trip_snorm() {
+        for i in -0x7F..=0x7F_i8 {
+            assert_eq!(i.to_channel_f32().to_channel_i8(), i);
+            assert_eq!(i.to_channel_f64().to_channel_i8(), i);
+            assert_eq!(i.to_channel_i16().to_channel_i8(), i);
+        }
+        assert_eq!((-0x80_i8).to_channel_f32().to_channel_i8(), -0x7F);
+        for i in -0x7FFF..=0x7FFF_i16 {
+            assert_eq!(i.to_channel_f32().to_channel_i16(), i);
+            assert_eq!(i.to_channel_f64().to_channel_i16(), i);
+        }
+        for i in 0..=0xFF_u8 {
+            assert_eq!(i.to_channel_i16().to_channel_u8(), i);
+        }
+    }
+
+    #[test]
+    fn test_f16() {
+        assert_eq!(f16::ONE.to_channel_u8(), 0xFF);
+        assert_eq!(0xFF_u8.to_channel_f16(), f16::ONE);
+        assert_eq!(0.5f32.to_channel_f16().to_channel_f32(), 0.5);
+        assert_eq!(f16::NAN.to_channel_u16(), 0);
+        assert_eq!(f16::from_f32(0.25).invert_channel(), f16::from_f32(0.75));
+        // 8 bit values survive the round trip through half floats
+        for i in 0..=0xFF_u8 {
+            assert_eq!(i.to_channel_f16().to_channel_u8(), i);
+        }
+    }
+
+    #[test]
+    fn test_colors() {
+        use {Rgb, Rgba, ToRgb, ToHsv};
+        let color = Rgb::new(0xFF_u8, 0x80, 0x00);
+        let snorm = color.to_rgb::<i16>();
+        assert_eq!(snorm, Rgb::new(0x7FFF, 0x4040, 0));
+        assert_eq!(snorm.to_rgb::<u8>(), color);
+        let half = Rgba { c: color.to_rgb::<f16>(), a: f16::ONE };
+        assert_eq!(half.c.to_rgb::<u8>(), color);
+        assert_eq!(half.c.to_hsv::<u16>(), color.to_hsv::<u16>());
+        assert_eq!(color.to_rgb::<u32>().to_rgb::<u8>(), color);
+    }
 }
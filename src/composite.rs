@@ -0,0 +1,272 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Porter-Duff alpha compositing, as specified by Compositing and Blending
+//! Level 1: https://www.w3.org/TR/compositing-1/#porterduffcompositingoperators
+
+use num;
+use num::traits;
+
+use Channel;
+use {Rgb, Rgba};
+use space::RgbSpace;
+
+fn cast<T: num::NumCast, U: num::NumCast>(n: T) -> U {
+    traits::cast(n).unwrap()
+}
+
+/// The Porter-Duff compositing operators, which combine a source color with
+/// the backdrop (destination) it is drawn onto.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PorterDuff {
+    /// Neither the source nor the backdrop are shown.
+    Clear,
+    /// Only the source is shown.
+    Copy,
+    /// The source is drawn over the backdrop. This is the usual way of
+    /// painting.
+    SourceOver,
+    /// The backdrop is drawn over the source.
+    DestinationOver,
+    /// The source is shown where the backdrop is.
+    SourceIn,
+    /// The backdrop is shown where the source is.
+    DestinationIn,
+    /// The source is shown where the backdrop is not.
+    SourceOut,
+    /// The backdrop is shown where the source is not.
+    DestinationOut,
+    /// The source is drawn over the backdrop, where the backdrop is.
+    SourceAtop,
+    /// The backdrop is drawn over the source, where the source is.
+    DestinationAtop,
+    /// The parts of the source and the backdrop that do not overlap.
+    Xor,
+    /// The sum of the source and the backdrop, also known as `lighter`.
+    Plus,
+}
+
+/// A compositing factor, expressed in terms of the source and backdrop
+/// alphas so that it can be evaluated exactly for integer channels.
+#[derive(Clone, Copy)]
+enum Factor {
+    Zero,
+    One,
+    Alpha,
+    InverseAlpha,
+}
+
+impl Factor {
+    /// Evaluates the factor for `alpha`, with `one` being the channel maximum.
+    #[inline]
+    fn eval<N: Copy + ::std::ops::Sub<Output = N>>(self, alpha: N, zero: N, one: N) -> N {
+        match self {
+            Factor::Zero => zero,
+            Factor::One => one,
+            Factor::Alpha => alpha,
+            Factor::InverseAlpha => one - alpha,
+        }
+    }
+}
+
+impl PorterDuff {
+    /// Returns the factors `Fa`, applied to the source in terms of the
+    /// backdrop alpha, and `Fb`, applied to the backdrop in terms of the
+    /// source alpha.
+    fn factors(self) -> (Factor, Factor) {
+        use self::Factor::*;
+        match self {
+            PorterDuff::Clear           => (Zero, Zero),
+            PorterDuff::Copy            => (One, Zero),
+            PorterDuff::SourceOver      => (One, InverseAlpha),
+            PorterDuff::DestinationOver => (InverseAlpha, One),
+            PorterDuff::SourceIn        => (Alpha, Zero),
+            PorterDuff::DestinationIn   => (Zero, Alpha),
+            PorterDuff::SourceOut       => (InverseAlpha, Zero),
+            PorterDuff::DestinationOut  => (Zero, InverseAlpha),
+            PorterDuff::SourceAtop      => (Alpha, InverseAlpha),
+            PorterDuff::DestinationAtop => (InverseAlpha, Alpha),
+            PorterDuff::Xor             => (InverseAlpha, InverseAlpha),
+            PorterDuff::Plus            => (One, One),
+        }
+    }
+}
+
+impl<T:Channel, S:RgbSpace> Rgba<T, S> {
+    /// Composites this color, as the source, onto `backdrop` with the given
+    /// operator. Both colors and the result have straight alpha; a fully
+    /// transparent result is transparent black.
+    ///
+    /// Integer channels are computed exactly and rounded to the nearest value,
+    /// with ties rounding up.
+    pub fn composite(&self, backdrop: &Rgba<T, S>, op: PorterDuff) -> Rgba<T, S> {
+        let (fa, fb) = op.factors();
+        let max: f64 = cast(T::max());
+        if max > 1.0 {
+            // Alphas are in units of `m`, premultiplied colors in units of
+            // `m^2` and the weighted sums in units of `m^3`, which fits in an
+            // i128 for 32 bit channels.
+            let m: i128 = cast(T::max());
+            let (sa, ba): (i128, i128) = (cast(self.a), cast(backdrop.a));
+            let (fa, fb) = (fa.eval(ba, 0, m), fb.eval(sa, 0, m));
+            let mut alpha = sa * fa + ba * fb;
+            if op == PorterDuff::Plus {
+                alpha = alpha.min(m * m);
+            }
+            let component = |s: T, b: T| -> T {
+                if alpha == 0 {
+                    return cast(0);
+                }
+                let (s, b): (i128, i128) = (cast(s), cast(b));
+                let premultiplied = (s * sa * fa + b * ba * fb).min(alpha * m);
+                cast((2 * premultiplied + alpha) / (2 * alpha))
+            };
+            Rgba {
+                c: Rgb::with_space(component(self.c.r, backdrop.c.r),
+                                   component(self.c.g, backdrop.c.g),
+                                   component(self.c.b, backdrop.c.b),
+                                   S::default()),
+                a: cast((2 * alpha + m) / (2 * m)),
+            }
+        } else {
+            let (sa, ba) = (self.a.to_channel_f64(), backdrop.a.to_channel_f64());
+            let (fa, fb) = (fa.eval(ba, 0.0, 1.0), fb.eval(sa, 0.0, 1.0));
+            let mut alpha = sa * fa + ba * fb;
+            if op == PorterDuff::Plus {
+                alpha = alpha.min(1.0);
+            }
+            let component = |s: T, b: T| -> T {
+                if alpha == 0.0 {
+                    return cast(0);
+                }
+                let premultiplied = s.to_channel_f64() * sa * fa + b.to_channel_f64() * ba * fb;
+                Channel::from(premultiplied.min(alpha) / alpha)
+            };
+            Rgba {
+                c: Rgb::with_space(component(self.c.r, backdrop.c.r),
+                                   component(self.c.g, backdrop.c.g),
+                                   component(self.c.b, backdrop.c.b),
+                                   S::default()),
+                a: Channel::from(alpha),
+            }
+        }
+    }
+
+    /// Draws this color over `backdrop`, the `SourceOver` operator.
+    #[inline]
+    pub fn over(&self, backdrop: &Rgba<T, S>) -> Rgba<T, S> {
+        self.composite(backdrop, PorterDuff::SourceOver)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use Rgba;
+    use super::PorterDuff;
+    use super::PorterDuff::*;
+
+    static OPERATORS: [PorterDuff; 12] = [Clear, Copy, SourceOver, DestinationOver, SourceIn, DestinationIn,
+                                          SourceOut, DestinationOut, SourceAtop, DestinationAtop, Xor, Plus];
+
+    #[test]
+    fn test_opaque() {
+        let red = rgba!(1.0f64, 0.0, 0.0, 1.0);
+        let blue = rgba!(0.0f64, 0.0, 1.0, 1.0);
+        let transparent = rgba!(0.0f64, 0.0, 0.0, 0.0);
+        assert_eq!(red.composite(&blue, Clear), transparent);
+        assert_eq!(red.composite(&blue, Copy), red);
+        assert_eq!(red.composite(&blue, SourceOver), red);
+        assert_eq!(red.composite(&blue, DestinationOver), blue);
+        assert_eq!(red.composite(&blue, SourceIn), red);
+        assert_eq!(red.composite(&blue, DestinationIn), blue);
+        assert_eq!(red.composite(&blue, SourceOut), transparent);
+        assert_eq!(red.composite(&blue, DestinationOut), transparent);
+        assert_eq!(red.composite(&blue, SourceAtop), red);
+        assert_eq!(red.composite(&blue, DestinationAtop), blue);
+        assert_eq!(red.composite(&blue, Xor), transparent);
+        assert_eq!(red.composite(&blue, Plus), rgba!(1.0, 0.0, 1.0, 1.0));
+    }
+
+    #[test]
+    fn test_translucent() {
+        let src = rgba!(1.0f64, 0.0, 0.0, 0.5);
+        let dst = rgba!(0.0f64, 0.0, 1.0, 0.5);
+        assert_eq!(src.over(&dst), rgba!(2.0 / 3.0, 0.0, 1.0 / 3.0, 0.75));
+        assert_eq!(src.composite(&dst, SourceIn), rgba!(1.0, 0.0, 0.0, 0.25));
+        assert_eq!(src.composite(&dst, SourceOut), rgba!(1.0, 0.0, 0.0, 0.25));
+        assert_eq!(src.composite(&dst, SourceAtop), rgba!(0.5, 0.0, 0.5, 0.5));
+        assert_eq!(src.composite(&dst, Xor), rgba!(0.5, 0.0, 0.5, 0.5));
+        assert_eq!(src.composite(&dst, Plus), rgba!(0.5, 0.0, 0.5, 1.0));
+        // Over a transparent backdrop the source is unchanged
+        assert_eq!(src.over(&rgba!(0.3, 0.6, 0.9, 0.0)), src);
+    }
+
+    /// The result of compositing two u8 colors, rounded to nearest with ties
+    /// rounding up. The small bias keeps exact ties from rounding down due to
+    /// floating point error.
+    fn reference(src: Rgba<u8>, dst: Rgba<u8>, op: PorterDuff) -> Rgba<u8> {
+        let f = |c: u8| c as f64 / 255.0;
+        let (sa, ba) = (f(src.a), f(dst.a));
+        let (fa, fb) = match op {
+            Clear => (0.0, 0.0),
+            Copy => (1.0, 0.0),
+            SourceOver => (1.0, 1.0 - sa),
+            DestinationOver => (1.0 - ba, 1.0),
+            SourceIn => (ba, 0.0),
+            DestinationIn => (0.0, sa),
+            SourceOut => (1.0 - ba, 0.0),
+            DestinationOut => (0.0, 1.0 - sa),
+            SourceAtop => (ba, 1.0 - sa),
+            DestinationAtop => (1.0 - ba, sa),
+            Xor => (1.0 - ba, 1.0 - sa),
+            Plus => (1.0, 1.0),
+        };
+        let alpha = (sa * fa + ba * fb).min(1.0);
+        let c = |s: u8, b: u8| {
+            if alpha == 0.0 { 0 } else {
+                ((f(s) * sa * fa + f(b) * ba * fb).min(alpha) / alpha * 255.0 + 1e-9).round() as u8
+            }
+        };
+        rgba!(c(src.c.r, dst.c.r), c(src.c.g, dst.c.g), c(src.c.b, dst.c.b), (alpha * 255.0 + 1e-9).round() as u8)
+    }
+
+    #[test]
+    fn test_u8_rounding() {
+        for &op in OPERATORS.iter() {
+            for sa in (0..256).step_by(15) {
+                for ba in (0..256).step_by(17) {
+                    for &(s, b) in [(0, 255), (255, 0), (100, 200), (37, 211), (128, 127)].iter() {
+                        let src = rgba!(s as u8, b as u8, s as u8, sa as u8);
+                        let dst = rgba!(b as u8, s as u8, 64, ba as u8);
+                        assert_eq!(src.composite(&dst, op), reference(src, dst, op), "{:?} {:?} {:?}", op, src, dst);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_channel_types() {
+        let src = rgba!(0xFFFFu16, 0, 0, 0x8000);
+        let dst = rgba!(0u16, 0, 0xFFFF, 0xFFFF);
+        assert_eq!(src.over(&dst), rgba!(0x8000, 0, 0x7FFF, 0xFFFF));
+        let src = rgba!(0xFFFF_FFFFu32, 0, 0, 0x8000_0000);
+        let dst = rgba!(0u32, 0, 0xFFFF_FFFF, 0xFFFF_FFFF);
+        assert_eq!(src.over(&dst), rgba!(0x8000_0000, 0, 0x7FFF_FFFF, 0xFFFF_FFFF));
+        let src = rgba!(1.0f32, 0.0, 0.0, 0.5);
+        let dst = rgba!(0.0f32, 0.0, 1.0, 1.0);
+        assert_eq!(src.over(&dst), rgba!(0.5, 0.0, 0.5, 1.0));
+    }
+}
@@ -0,0 +1,1054 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Parsing and serialization of CSS Color Module Level 4 color strings:
+//! https://www.w3.org/TR/css-color-4/
+//!
+//! Every syntax resolves to gamma encoded sRGB. Colors specified in wider
+//! gamuts, such as `lab()` or `color(display-p3 …)`, are converted but not
+//! clamped when parsed into float channels, so they can end up outside of
+//! `(0,1)`.
+
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use angle::*;
+
+use Channel;
+use {Rgb, ToRgb, Rgba};
+use {Srgb, ToSrgb, Srgba, AlphaColor};
+use {Hsl, ToHsl, Hsv, ToHsv, Lab, Oklab};
+use {Xyz, Chromaticity, RgbPrimaries};
+use ChromaticAdaptation;
+use matrix;
+use hue;
+use rgb::consts;
+use space::{self, RgbSpace, LinearSrgb};
+
+/// The reason a color string could not be parsed.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ParseColorErrorKind {
+    /// The string was empty or only contained whitespace.
+    Empty,
+    /// The string ended in the middle of a color.
+    UnexpectedEnd,
+    /// A character that is not valid at this point was encountered.
+    UnexpectedCharacter,
+    /// A hex color did not have 3, 4, 6 or 8 hexadecimal digits.
+    InvalidHex,
+    /// The keyword is not a named color.
+    UnknownName,
+    /// The function is not a supported color function.
+    UnknownFunction,
+    /// The color space passed to `color()` is not supported.
+    UnknownColorSpace,
+    /// A number could not be parsed.
+    InvalidNumber,
+    /// A component has a type or unit that is not valid at its position.
+    InvalidComponent,
+    /// The color function was given too few components.
+    MissingComponent,
+    /// The color function was given too many components.
+    TooManyComponents,
+}
+
+/// An error returned when parsing a color string fails.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ParseColorError {
+    kind: ParseColorErrorKind,
+    position: usize,
+}
+
+impl ParseColorError {
+    /// The reason the string could not be parsed.
+    #[inline]
+    pub fn kind(&self) -> ParseColorErrorKind {
+        self.kind
+    }
+
+    /// The byte offset in the input at which the error was detected.
+    #[inline]
+    pub fn position(&self) -> usize {
+        self.position
+    }
+}
+
+impl fmt::Display for ParseColorError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let reason = match self.kind {
+            ParseColorErrorKind::Empty => "empty color string",
+            ParseColorErrorKind::UnexpectedEnd => "unexpected end of color string",
+            ParseColorErrorKind::UnexpectedCharacter => "unexpected character",
+            ParseColorErrorKind::InvalidHex => "invalid hex color",
+            ParseColorErrorKind::UnknownName => "unknown color name",
+            ParseColorErrorKind::UnknownFunction => "unknown color function",
+            ParseColorErrorKind::UnknownColorSpace => "unknown color space",
+            ParseColorErrorKind::InvalidNumber => "invalid number",
+            ParseColorErrorKind::InvalidComponent => "invalid color component",
+            ParseColorErrorKind::MissingComponent => "missing color component",
+            ParseColorErrorKind::TooManyComponents => "too many color components",
+        };
+        write!(f, "{} at position {}", reason, self.position)
+    }
+}
+
+impl Error for ParseColorError {}
+
+/// A single argument of a color function.
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Value {
+    Number(f64),
+    Percentage(f64),
+    /// An angle, converted to degrees.
+    Angle(f64),
+    /// The `none` keyword, standing in for a missing component.
+    None,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Component {
+    value: Value,
+    position: usize,
+}
+
+impl Component {
+    /// Resolves a number or percentage, where `100%` corresponds to `reference`.
+    fn number(&self, reference: f64) -> Result<f64, ParseColorError> {
+        match self.value {
+            Value::Number(n) => Ok(n),
+            Value::Percentage(p) => Ok(p / 100.0 * reference),
+            Value::None => Ok(0.0),
+            Value::Angle(_) => Err(ParseColorError { kind: ParseColorErrorKind::InvalidComponent, position: self.position }),
+        }
+    }
+
+    /// Resolves a hue in degrees. Bare numbers are interpreted as degrees.
+    fn hue(&self) -> Result<f64, ParseColorError> {
+        match self.value {
+            Value::Number(n) | Value::Angle(n) => Ok(n),
+            Value::None => Ok(0.0),
+            Value::Percentage(_) => Err(ParseColorError { kind: ParseColorErrorKind::InvalidComponent, position: self.position }),
+        }
+    }
+}
+
+/// The arguments of a color function: its components and optional alpha.
+struct Arguments {
+    components: Vec<Component>,
+    alpha: Option<Component>,
+    legacy: bool,
+}
+
+impl Arguments {
+    fn alpha(&self) -> Result<f64, ParseColorError> {
+        match self.alpha {
+            Some(ref alpha) => Ok(alpha.number(1.0)?.clamp(0.0, 1.0)),
+            None => Ok(1.0),
+        }
+    }
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn error(&self, kind: ParseColorErrorKind) -> ParseColorError {
+        ParseColorError { kind, position: self.pos }
+    }
+
+    /// Reports `UnexpectedEnd` at the end of the input, and `kind` otherwise.
+    fn error_or_end(&self, kind: ParseColorErrorKind) -> ParseColorError {
+        match self.peek() {
+            Some(_) => self.error(kind),
+            None => self.error(ParseColorErrorKind::UnexpectedEnd),
+        }
+    }
+
+    #[inline]
+    fn peek(&self) -> Option<u8> {
+        self.input.as_bytes().get(self.pos).cloned()
+    }
+
+    fn eat(&mut self, byte: u8) -> bool {
+        if self.peek() == Some(byte) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(b' ') | Some(b'\t') | Some(b'\n') | Some(b'\r') | Some(b'\x0C') = self.peek() {
+            self.pos += 1;
+        }
+    }
+
+    fn ident(&mut self) -> &'a str {
+        let start = self.pos;
+        while let Some(b) = self.peek() {
+            if b.is_ascii_alphanumeric() || b == b'-' || b == b'_' {
+                self.pos += 1;
+            } else {
+                break;
+            }
+        }
+        &self.input[start..self.pos]
+    }
+
+    fn digits(&mut self) -> usize {
+        let start = self.pos;
+        while let Some(b'0'..=b'9') = self.peek() {
+            self.pos += 1;
+        }
+        self.pos - start
+    }
+
+    fn color(&mut self) -> Result<Srgba<f64>, ParseColorError> {
+        self.skip_whitespace();
+        let color = match self.peek() {
+            None => return Err(self.error(ParseColorErrorKind::Empty)),
+            Some(b'#') => self.hex()?,
+            Some(b) if b.is_ascii_alphabetic() => {
+                let start = self.pos;
+                let name = self.ident();
+                if self.eat(b'(') {
+                    self.function(name, start)?
+                } else if name.eq_ignore_ascii_case("transparent") {
+                    Srgba { c: Srgb::new(0.0, 0.0, 0.0), a: 0.0 }
+                } else {
+                    match consts::from_name(name) {
+                        Some(c) => Srgba { c: c.to_srgb(), a: 1.0 },
+                        None => return Err(ParseColorError { kind: ParseColorErrorKind::UnknownName, position: start }),
+                    }
+                }
+            }
+            Some(_) => return Err(self.error(ParseColorErrorKind::UnexpectedCharacter)),
+        };
+        self.skip_whitespace();
+        match self.peek() {
+            None => Ok(color),
+            Some(_) => Err(self.error(ParseColorErrorKind::UnexpectedCharacter)),
+        }
+    }
+
+    fn hex(&mut self) -> Result<Srgba<f64>, ParseColorError> {
+        let start = self.pos;
+        self.pos += 1;
+        while let Some(b) = self.peek() {
+            if b.is_ascii_alphanumeric() {
+                self.pos += 1;
+            } else {
+                break;
+            }
+        }
+        let digits = &self.input[start + 1..self.pos];
+        let invalid = ParseColorError { kind: ParseColorErrorKind::InvalidHex, position: start };
+        let mut values = [0xFF_u8; 4];
+        match digits.len() {
+            3 | 4 => for (i, c) in digits.chars().enumerate() {
+                let v = c.to_digit(16).ok_or(invalid)? as u8;
+                values[i] = v << 4 | v;
+            },
+            6 | 8 => for (i, value) in values.iter_mut().enumerate().take(digits.len() / 2) {
+                *value = u8::from_str_radix(&digits[i * 2..i * 2 + 2], 16).map_err(|_| invalid)?;
+            },
+            _ => return Err(invalid),
+        }
+        Ok(Srgba { c: Srgb::new(values[0], values[1], values[2]).to_srgb(), a: values[3].to_channel_f64() })
+    }
+
+    fn component(&mut self) -> Result<Component, ParseColorError> {
+        let start = self.pos;
+        match self.peek() {
+            Some(b) if b.is_ascii_alphabetic() => {
+                return if self.ident().eq_ignore_ascii_case("none") {
+                    Ok(Component { value: Value::None, position: start })
+                } else {
+                    Err(ParseColorError { kind: ParseColorErrorKind::InvalidComponent, position: start })
+                };
+            }
+            None => return Err(self.error(ParseColorErrorKind::UnexpectedEnd)),
+            _ => {}
+        }
+
+        if !self.eat(b'+') {
+            self.eat(b'-');
+        }
+        let mut mantissa = self.digits();
+        if self.peek() == Some(b'.') {
+            self.pos += 1;
+            let fraction = self.digits();
+            if fraction == 0 {
+                return Err(ParseColorError { kind: ParseColorErrorKind::InvalidNumber, position: start });
+            }
+            mantissa += fraction;
+        }
+        if mantissa == 0 {
+            return Err(self.error_or_end(ParseColorErrorKind::UnexpectedCharacter));
+        }
+        if let Some(b'e') | Some(b'E') = self.peek() {
+            let bytes = self.input.as_bytes();
+            let sign = match bytes.get(self.pos + 1) { Some(&b'+') | Some(&b'-') => 1, _ => 0 };
+            if let Some(b'0'..=b'9') = bytes.get(self.pos + 1 + sign).cloned() {
+                self.pos += 1 + sign;
+                self.digits();
+            }
+        }
+        let n: f64 = self.input[start..self.pos].parse()
+            .map_err(|_| ParseColorError { kind: ParseColorErrorKind::InvalidNumber, position: start })?;
+
+        let value = match self.peek() {
+            Some(b'%') => {
+                self.pos += 1;
+                Value::Percentage(n)
+            }
+            Some(b) if b.is_ascii_alphabetic() => {
+                let unit_start = self.pos;
+                let unit = self.ident().to_ascii_lowercase();
+                match &unit[..] {
+                    "deg" => Value::Angle(n),
+                    "grad" => Value::Angle(n * 0.9),
+                    "rad" => Value::Angle(n.to_degrees()),
+                    "turn" => Value::Angle(n * 360.0),
+                    _ => return Err(ParseColorError { kind: ParseColorErrorKind::InvalidComponent, position: unit_start }),
+                }
+            }
+            _ => Value::Number(n),
+        };
+        Ok(Component { value, position: start })
+    }
+
+    /// Parses the arguments of a color function up to and including the
+    /// closing parenthesis. If `legacy` is set, the comma separated syntax
+    /// of CSS Color Level 3 is accepted as well.
+    fn arguments(&mut self, count: usize, legacy: bool) -> Result<Arguments, ParseColorError> {
+        self.skip_whitespace();
+        let mut components = vec![self.component()?];
+        let mut alpha = None;
+        self.skip_whitespace();
+        let commas = legacy && self.peek() == Some(b',');
+        if commas {
+            while self.eat(b',') {
+                self.skip_whitespace();
+                let component = self.component()?;
+                if component.value == Value::None {
+                    return Err(ParseColorError { kind: ParseColorErrorKind::InvalidComponent, position: component.position });
+                }
+                components.push(component);
+                self.skip_whitespace();
+            }
+            if components[0].value == Value::None {
+                return Err(ParseColorError { kind: ParseColorErrorKind::InvalidComponent, position: components[0].position });
+            }
+            if components.len() > count + 1 {
+                return Err(ParseColorError { kind: ParseColorErrorKind::TooManyComponents, position: components[count + 1].position });
+            }
+            if components.len() == count + 1 {
+                alpha = components.pop();
+            }
+        } else {
+            while components.len() < count {
+                match self.peek() {
+                    Some(b')') | Some(b'/') | Some(b',') => break,
+                    _ => {}
+                }
+                components.push(self.component()?);
+                self.skip_whitespace();
+            }
+            if components.len() == count && self.eat(b'/') {
+                self.skip_whitespace();
+                alpha = Some(self.component()?);
+                self.skip_whitespace();
+            }
+        }
+        if components.len() < count {
+            return Err(self.error_or_end(ParseColorErrorKind::MissingComponent));
+        }
+        if !self.eat(b')') {
+            return Err(match self.peek() {
+                Some(b'+') | Some(b'-') | Some(b'.') | Some(b'0'..=b'9') => self.error(ParseColorErrorKind::TooManyComponents),
+                _ => self.error_or_end(ParseColorErrorKind::UnexpectedCharacter),
+            });
+        }
+        Ok(Arguments { components, alpha, legacy: commas })
+    }
+
+    fn function(&mut self, name: &str, start: usize) -> Result<Srgba<f64>, ParseColorError> {
+        let name = name.to_ascii_lowercase();
+        match &name[..] {
+            "rgb" | "rgba" => {
+                let args = self.arguments(3, true)?;
+                let c = &args.components;
+                if args.legacy {
+                    // The legacy syntax does not allow mixing numbers and percentages
+                    let percentages = matches!(c[0].value, Value::Percentage(_));
+                    for component in &c[1..] {
+                        if percentages != matches!(component.value, Value::Percentage(_)) {
+                            return Err(ParseColorError { kind: ParseColorErrorKind::InvalidComponent, position: component.position });
+                        }
+                    }
+                }
+                let channel = |c: &Component| c.number(255.0).map(|v| (v / 255.0).clamp(0.0, 1.0));
+                Ok(Srgba { c: Srgb::new(channel(&c[0])?, channel(&c[1])?, channel(&c[2])?), a: args.alpha()? })
+            }
+            "hsl" | "hsla" => {
+                let args = self.arguments(3, true)?;
+                let c = &args.components;
+                let s = c[1].number(100.0)?.max(0.0) / 100.0;
+                let l = c[2].number(100.0)? / 100.0;
+                Ok(Srgba { c: Hsl::new(Deg(c[0].hue()?), s, l).to_srgb(), a: args.alpha()? })
+            }
+            "hwb" => {
+                let args = self.arguments(3, false)?;
+                let c = &args.components;
+                let w = c[1].number(100.0)? / 100.0;
+                let b = c[2].number(100.0)? / 100.0;
+                let color = if w + b >= 1.0 {
+                    let gray = w / (w + b);
+                    Srgb::new(gray, gray, gray)
+                } else {
+                    let rgb = Hsl::new(Deg(c[0].hue()?), 1.0, 0.5).to_srgb::<f64>();
+                    let f = |v: f64| v * (1.0 - w - b) + w;
+                    Srgb::new(f(rgb.r), f(rgb.g), f(rgb.b))
+                };
+                Ok(Srgba { c: color, a: args.alpha()? })
+            }
+            "lab" | "lch" => {
+                let args = self.arguments(3, false)?;
+                let c = &args.components;
+                let l = c[0].number(100.0)?.max(0.0);
+                let (a, b) = if name == "lab" {
+                    (c[1].number(125.0)?, c[2].number(125.0)?)
+                } else {
+                    let chroma = c[1].number(150.0)?.max(0.0);
+                    let (sin, cos) = c[2].hue()?.to_radians().sin_cos();
+                    (chroma * cos, chroma * sin)
+                };
+                // CSS specifies L*a*b* relative to D50
+                let xyz = Lab::new(l, a, b).to_xyz_with::<f64>(Chromaticity::D50);
+                Ok(Srgba { c: from_xyz(xyz, Chromaticity::D50), a: args.alpha()? })
+            }
+            "oklab" | "oklch" => {
+                let args = self.arguments(3, false)?;
+                let c = &args.components;
+                let l = c[0].number(1.0)?.max(0.0);
+                let (a, b) = if name == "oklab" {
+                    (c[1].number(0.4)?, c[2].number(0.4)?)
+                } else {
+                    let chroma = c[1].number(0.4)?.max(0.0);
+                    let (sin, cos) = c[2].hue()?.to_radians().sin_cos();
+                    (chroma * cos, chroma * sin)
+                };
+                let rgb = Oklab::new(l, a, b).to_rgb::<f64>();
+                Ok(Srgba { c: encode(rgb.r, rgb.g, rgb.b), a: args.alpha()? })
+            }
+            "color" => {
+                self.skip_whitespace();
+                let space_start = self.pos;
+                let space = self.ident().to_ascii_lowercase();
+                if space.is_empty() {
+                    return Err(self.error_or_end(ParseColorErrorKind::UnexpectedCharacter));
+                }
+                let args = self.arguments(3, false)?;
+                let c = &args.components;
+                let v = [c[0].number(1.0)?, c[1].number(1.0)?, c[2].number(1.0)?];
+                let color = match color_space(&space, v) {
+                    Some(color) => color,
+                    None => return Err(ParseColorError { kind: ParseColorErrorKind::UnknownColorSpace, position: space_start }),
+                };
+                Ok(Srgba { c: color, a: args.alpha()? })
+            }
+            _ => Err(ParseColorError { kind: ParseColorErrorKind::UnknownFunction, position: start }),
+        }
+    }
+}
+
+/// Converts XYZ relative to `white` to gamma encoded sRGB.
+fn from_xyz(xyz: Xyz<f64>, white: Chromaticity) -> Srgb<f64> {
+    let mut v = [xyz.x, xyz.y, xyz.z];
+    if white != Chromaticity::D65 {
+        v = matrix::mul_vec(&ChromaticAdaptation::Bradford.matrix(white, Chromaticity::D65), v);
+    }
+    let rgb = matrix::mul_vec(&RgbPrimaries::SRGB.from_xyz_matrix(), v);
+    encode(rgb[0], rgb[1], rgb[2])
+}
+
+/// Applies the sRGB transfer function to linear components, mirrored for
+/// negative values as CSS does for out of gamut colors.
+fn encode(r: f64, g: f64, b: f64) -> Srgb<f64> {
+    Srgb::new(LinearSrgb::encode(r), LinearSrgb::encode(g), LinearSrgb::encode(b))
+}
+
+/// Converts encoded components in the RGB space `S` to gamma encoded sRGB.
+fn from_space<S: RgbSpace>(v: [f64; 3]) -> Srgb<f64> {
+    let rgb = Rgb::<f64, S>::from_encoded(v).to_space::<LinearSrgb>();
+    encode(rgb.r, rgb.g, rgb.b)
+}
+
+/// Converts the components of a `color()` function in one of the predefined
+/// color spaces to gamma encoded sRGB.
+fn color_space(name: &str, v: [f64; 3]) -> Option<Srgb<f64>> {
+    match name {
+        "srgb" => Some(Srgb::new(v[0], v[1], v[2])),
+        "srgb-linear" => Some(encode(v[0], v[1], v[2])),
+        "xyz" | "xyz-d65" => Some(from_xyz(Xyz::new(v[0], v[1], v[2]), Chromaticity::D65)),
+        "xyz-d50" => Some(from_xyz(Xyz::new(v[0], v[1], v[2]), Chromaticity::D50)),
+        "display-p3" => Some(from_space::<space::DisplayP3>(v)),
+        "a98-rgb" => Some(from_space::<space::AdobeRgb>(v)),
+        "prophoto-rgb" => Some(from_space::<space::ProPhotoRgb>(v)),
+        "rec2020" => Some(from_space::<space::Rec2020>(v)),
+        _ => None,
+    }
+}
+
+/// Parses any CSS Color Level 4 color, such as `#ff8000`, `rebeccapurple`,
+/// `rgb(255 128 0 / 50%)`, `hsl(30, 100%, 50%)` or `oklch(70% 0.2 50)`.
+impl<T:Channel> FromStr for AlphaColor<T, Srgb<T>> {
+    type Err = ParseColorError;
+
+    fn from_str(s: &str) -> Result<Self, ParseColorError> {
+        let color = Parser { input: s, pos: 0 }.color()?;
+        Ok(Srgba { c: Srgb::new(Channel::from(color.c.r), Channel::from(color.c.g), Channel::from(color.c.b)), a: Channel::from(color.a) })
+    }
+}
+
+/// Parses any CSS Color Level 4 color, discarding its alpha.
+impl<T:Channel> FromStr for Srgb<T> {
+    type Err = ParseColorError;
+
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, ParseColorError> {
+        s.parse::<Srgba<T>>().map(|color| color.c)
+    }
+}
+
+/// Parses any CSS color and decodes the sRGB transfer function, giving
+/// linear components.
+impl<T:Channel> FromStr for AlphaColor<T, Rgb<T>> {
+    type Err = ParseColorError;
+
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, ParseColorError> {
+        s.parse::<Srgba<f64>>().map(|color| Rgba { c: color.c.to_rgb(), a: Channel::from(color.a) })
+    }
+}
+
+/// Parses any CSS color into linear components, discarding its alpha.
+impl<T:Channel> FromStr for Rgb<T> {
+    type Err = ParseColorError;
+
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, ParseColorError> {
+        s.parse::<Rgba<T>>().map(|color| color.c)
+    }
+}
+
+/// Parses any CSS color and converts its components to HSV, discarding its
+/// alpha. Integer channels get the hue rounded to whole degrees.
+impl<T:Channel> FromStr for Hsv<T> {
+    type Err = ParseColorError;
+
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, ParseColorError> {
+        s.parse::<Srgb<f64>>().map(|color| {
+            let hsv = color.to_rgb::<f64>().to_hsv::<f64>();
+            Hsv::new(hue::from_degrees(hsv.h.value().rem_euclid(360.0)), Channel::from(hsv.s), Channel::from(hsv.v))
+        })
+    }
+}
+
+/// The syntax to serialize a color with.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CssFormat {
+    /// `#rrggbb`, or `#rrggbbaa` for colors with an alpha channel.
+    Hex,
+    /// The shortest of `#rgb`, `#rgba`, `#rrggbb` and `#rrggbbaa` that
+    /// exactly represents the color at 8 bits per channel. The alpha is
+    /// omitted if the color is opaque.
+    ShortHex,
+    /// `rgb(r g b)` with components from `0` to `255`, or `rgb(r g b / a)`
+    /// for translucent colors.
+    Rgb,
+    /// `hsl(h s% l%)`, or `hsl(h s% l% / a)` for translucent colors.
+    Hsl,
+    /// `color(srgb r g b)` with components from `0` to `1`, or
+    /// `color(srgb r g b / a)` for translucent colors.
+    Color,
+    /// `color(srgb-linear r g b)` with linear components from `0` to `1`, or
+    /// `color(srgb-linear r g b / a)` for translucent colors.
+    LinearColor,
+}
+
+/// A color prepared for serialization as a CSS string, created by
+/// `ToCss::to_css`.
+///
+/// Non-hex numbers are written with the shortest representation that parses
+/// back to the same `f64`, unless a precision is given with `{:.N}`. Without
+/// a precision, parsing the output yields the original color: exactly for
+/// `Color`, for `Rgb`, `Hsl` and `LinearColor` up to the rounding of integer
+/// channels and a few ulps for float channels, and for hex formats at 8 bits
+/// per encoded sRGB channel. Linear colors only survive 8 bit encoding in
+/// the float syntaxes, as the transfer function is not one to one on bytes.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Css {
+    format: CssFormat,
+    rgb: [f64; 3],
+    /// The hue, saturation and lightness, if the color is specified in a
+    /// cylindrical space and its hue should be preserved.
+    hsl: Option<[f64; 3]>,
+    /// The linear components, if the color is specified in linear light, so
+    /// that they are written without going through the transfer function.
+    linear: Option<[f64; 3]>,
+    alpha: Option<f64>,
+}
+
+impl Css {
+    fn hsl(&self) -> [f64; 3] {
+        match self.hsl {
+            Some(hsl) => hsl,
+            None => {
+                let hsl = Srgb::new(self.rgb[0], self.rgb[1], self.rgb[2]).to_hsl::<f64>();
+                [hsl.h.value(), hsl.s, hsl.l]
+            }
+        }
+    }
+
+    fn linear(&self) -> [f64; 3] {
+        match self.linear {
+            Some(linear) => linear,
+            None => [LinearSrgb::decode(self.rgb[0]), LinearSrgb::decode(self.rgb[1]), LinearSrgb::decode(self.rgb[2])],
+        }
+    }
+
+    fn hex(&self, f: &mut fmt::Formatter, short: bool) -> fmt::Result {
+        let mut bytes = [0u8; 4];
+        for (byte, &value) in bytes.iter_mut().zip(self.rgb.iter()) {
+            *byte = value.to_channel_u8();
+        }
+        bytes[3] = self.alpha.map_or(0xFF, Channel::to_channel_u8);
+        let len = if self.alpha.is_some() && !(short && bytes[3] == 0xFF) { 4 } else { 3 };
+        let nibbles = short && bytes[..len].iter().all(|&b| b >> 4 == b & 0xF);
+        f.write_str("#")?;
+        for &b in &bytes[..len] {
+            if nibbles {
+                write!(f, "{:x}", b & 0xF)?;
+            } else {
+                write!(f, "{:02x}", b)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes the closing alpha and parenthesis of a color function.
+    fn close(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.alpha {
+            Some(alpha) if alpha < 1.0 => {
+                f.write_str(" / ")?;
+                number(f, alpha)?;
+                f.write_str(")")
+            }
+            _ => f.write_str(")"),
+        }
+    }
+}
+
+/// Writes a number in the precision requested by the formatter, or in the
+/// shortest representation that round-trips otherwise.
+fn number(f: &mut fmt::Formatter, value: f64) -> fmt::Result {
+    match f.precision() {
+        Some(precision) => {
+            let s = format!("{:.*}", precision, value);
+            let s = if s.contains('.') { s.trim_end_matches('0').trim_end_matches('.') } else { &s[..] };
+            f.write_str(if s == "-0" { "0" } else { s })
+        }
+        None => write!(f, "{}", if value == 0.0 { 0.0 } else { value }),
+    }
+}
+
+impl fmt::Display for Css {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.format {
+            CssFormat::Hex => self.hex(f, false),
+            CssFormat::ShortHex => self.hex(f, true),
+            CssFormat::Rgb => {
+                f.write_str("rgb(")?;
+                for (i, &value) in self.rgb.iter().enumerate() {
+                    if i > 0 { f.write_str(" ")?; }
+                    number(f, value * 255.0)?;
+                }
+                self.close(f)
+            }
+            CssFormat::Hsl => {
+                let hsl = self.hsl();
+                f.write_str("hsl(")?;
+                number(f, hsl[0])?;
+                f.write_str(" ")?;
+                number(f, hsl[1] * 100.0)?;
+                f.write_str("% ")?;
+                number(f, hsl[2] * 100.0)?;
+                f.write_str("%")?;
+                self.close(f)
+            }
+            CssFormat::Color => {
+                f.write_str("color(srgb")?;
+                for &value in self.rgb.iter() {
+                    f.write_str(" ")?;
+                    number(f, value)?;
+                }
+                self.close(f)
+            }
+            CssFormat::LinearColor => {
+                f.write_str("color(srgb-linear")?;
+                for &value in self.linear().iter() {
+                    f.write_str(" ")?;
+                    number(f, value)?;
+                }
+                self.close(f)
+            }
+        }
+    }
+}
+
+/// Serialization of colors as CSS strings.
+pub trait ToCss {
+    fn to_css(&self, format: CssFormat) -> Css;
+}
+
+impl<T:Channel> ToCss for Rgb<T> {
+    /// Encodes the components with the sRGB transfer function, as CSS colors
+    /// are written in sRGB.
+    #[inline]
+    fn to_css(&self, format: CssFormat) -> Css {
+        let rgb = self.to_srgb::<f64>();
+        let linear = [self.r.to_channel_f64(), self.g.to_channel_f64(), self.b.to_channel_f64()];
+        Css { format, rgb: [rgb.r, rgb.g, rgb.b], hsl: None, linear: Some(linear), alpha: None }
+    }
+}
+
+impl<T:Channel> ToCss for Srgb<T> {
+    #[inline]
+    fn to_css(&self, format: CssFormat) -> Css {
+        Css { format, rgb: [self.r.to_channel_f64(), self.g.to_channel_f64(), self.b.to_channel_f64()], hsl: None, linear: None, alpha: None }
+    }
+}
+
+impl<T:Channel> ToCss for Hsv<T> {
+    /// Converts the components to HSL, keeping the hue of neutral colors.
+    #[inline]
+    fn to_css(&self, format: CssFormat) -> Css {
+        let hsv = self.to_hsv::<f64>();
+        let rgb = hsv.to_srgb::<f64>();
+        let hsl = hsv.to_hsl::<f64>();
+        Css { format, rgb: [rgb.r, rgb.g, rgb.b], hsl: Some([hsl.h.value(), hsl.s, hsl.l]), linear: None, alpha: None }
+    }
+}
+
+impl<T:Channel, C: ToCss> ToCss for AlphaColor<T, C> {
+    #[inline]
+    fn to_css(&self, format: CssFormat) -> Css {
+        Css { alpha: Some(self.a.to_channel_f64()), ..self.c.to_css(format) }
+    }
+}
+
+/// Formats the color as `color(srgb-linear r g b)`, which keeps the linear
+/// components instead of rounding their encoded values to 8 bits.
+impl<T:Channel> fmt::Display for Rgb<T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.to_css(CssFormat::LinearColor), f)
+    }
+}
+
+/// Formats the color as `#rrggbb`.
+impl<T:Channel> fmt::Display for Srgb<T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.to_css(CssFormat::Hex), f)
+    }
+}
+
+/// Formats the color as `hsl(h s% l%)`.
+impl<T:Channel> fmt::Display for Hsv<T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.to_css(CssFormat::Hsl), f)
+    }
+}
+
+/// Formats the color as `color(srgb-linear r g b / a)`.
+impl<T:Channel> fmt::Display for AlphaColor<T, Rgb<T>> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.to_css(CssFormat::LinearColor), f)
+    }
+}
+
+/// Formats the color as `#rrggbbaa`.
+impl<T:Channel> fmt::Display for AlphaColor<T, Srgb<T>> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.to_css(CssFormat::Hex), f)
+    }
+}
+
+/// Formats the color as `hsl(h s% l% / a)`.
+impl<T:Channel> fmt::Display for AlphaColor<T, Hsv<T>> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.to_css(CssFormat::Hsl), f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {Rgb, Rgba, ToRgb, Srgb, Srgba, ToSrgb, Hsv, Hsva};
+    use super::{ParseColorError, ParseColorErrorKind, CssFormat, ToCss};
+    use angle::*;
+
+    fn parse(s: &str) -> Srgba<f64> {
+        s.parse().unwrap_or_else(|e| panic!("{:?}: {}", s, e))
+    }
+
+    fn error(s: &str) -> (ParseColorErrorKind, usize) {
+        let e: ParseColorError = s.parse::<Srgba<f64>>().unwrap_err();
+        (e.kind(), e.position())
+    }
+
+    fn assert_approx(s: &str, r: f64, g: f64, b: f64, a: f64) {
+        let c = parse(s);
+        for &(x, y) in [(c.c.r, r), (c.c.g, g), (c.c.b, b), (c.a, a)].iter() {
+            assert!((x - y).abs() < 1e-3, "{:?} parsed as {:?}", s, c);
+        }
+    }
+
+    #[test]
+    fn test_hex() {
+        assert_eq!("#f80".parse::<Srgba<u8>>(), Ok(Srgba { c: Srgb::new(0xFF, 0x88, 0x00), a: 0xFF }));
+        assert_eq!("#f808".parse::<Srgba<u8>>(), Ok(Srgba { c: Srgb::new(0xFF, 0x88, 0x00), a: 0x88 }));
+        assert_eq!("#FF8001".parse::<Srgba<u8>>(), Ok(Srgba { c: Srgb::new(0xFF, 0x80, 0x01), a: 0xFF }));
+        assert_eq!("#ff800180".parse::<Srgba<u8>>(), Ok(Srgba { c: Srgb::new(0xFF, 0x80, 0x01), a: 0x80 }));
+        assert_eq!("  #102030 ".parse::<Srgb<u8>>(), Ok(Srgb::new(0x10, 0x20, 0x30)));
+        assert_eq!(error("#12345"), (ParseColorErrorKind::InvalidHex, 0));
+        assert_eq!(error("#ggg"), (ParseColorErrorKind::InvalidHex, 0));
+    }
+
+    #[test]
+    fn test_names() {
+        assert_eq!("red".parse::<Srgb<u8>>(), Ok(Srgb::new(0xFF, 0x00, 0x00)));
+        assert_eq!("CornflowerBlue".parse::<Srgb<u8>>(), Ok(Srgb::new(0x64, 0x95, 0xED)));
+        assert_eq!("transparent".parse::<Srgba<u8>>(), Ok(Srgba { c: Srgb::new(0, 0, 0), a: 0 }));
+        assert_eq!(error("reddish"), (ParseColorErrorKind::UnknownName, 0));
+    }
+
+    #[test]
+    fn test_rgb() {
+        let orange = Ok(Srgba { c: Srgb::new(0xFF_u8, 0x80, 0x00), a: 0xFF });
+        assert_eq!("rgb(255, 128, 0)".parse(), orange);
+        assert_eq!("rgb(255 128 0)".parse(), orange);
+        assert_eq!("RGBA(255,128,0,1)".parse(), orange);
+        assert_eq!("rgb(100% 50.2% 0%)".parse(), orange);
+        assert_eq!("rgb(255 128 none / 100%)".parse(), orange);
+        assert_eq!("rgb(300 128 -20)".parse(), orange);
+        assert_approx("rgba(255, 0, 0, 0.5)", 1.0, 0.0, 0.0, 0.5);
+        assert_approx("rgb(0 0 255 / 25%)", 0.0, 0.0, 1.0, 0.25);
+        assert_approx("rgb(1e2% 0 0 / .5)", 1.0, 0.0, 0.0, 0.5);
+        assert_eq!(error("rgb(255, 50%, 0)"), (ParseColorErrorKind::InvalidComponent, 9));
+        assert_eq!(error("rgb(255 128)"), (ParseColorErrorKind::MissingComponent, 11));
+        assert_eq!(error("rgb(255 128 0 12)"), (ParseColorErrorKind::TooManyComponents, 14));
+        assert_eq!(error("rgb(255 128 0deg)"), (ParseColorErrorKind::InvalidComponent, 12));
+        assert_eq!(error("rgb(255 128 0"), (ParseColorErrorKind::UnexpectedEnd, 13));
+        assert_eq!(error("rgb(255, 128, 0) x"), (ParseColorErrorKind::UnexpectedCharacter, 17));
+        assert_eq!(error("rgb(255, 128, x)"), (ParseColorErrorKind::InvalidComponent, 14));
+        assert_eq!(error("rgb(255, 128, .)"), (ParseColorErrorKind::InvalidNumber, 14));
+    }
+
+    #[test]
+    fn test_hsl_hwb() {
+        let orange = Ok(Srgb::new(0xFF_u8, 0x80, 0x00));
+        assert_eq!("hsl(30, 100%, 50%)".parse(), orange);
+        assert_eq!("hsl(30deg 100% 50%)".parse(), orange);
+        assert_eq!("hsla(0.5turn 100 50 / 1)".parse(), Ok(Srgb::new(0x00_u8, 0xFF, 0xFF)));
+        assert_eq!("hsl(-330 100% 50%)".parse(), orange);
+        assert_eq!("hwb(30 0% 0%)".parse(), orange);
+        assert_eq!("hwb(120 20% 30%)".parse(), Ok(Srgb::new(0x33_u8, 0xB3, 0x33)));
+        assert_approx("hwb(0 60% 60%)", 0.5, 0.5, 0.5, 1.0);
+        assert_eq!(error("hsl(30%, 100%, 50%)"), (ParseColorErrorKind::InvalidComponent, 4));
+        assert_eq!(error("hwb(30, 0%, 0%)"), (ParseColorErrorKind::MissingComponent, 6));
+    }
+
+    #[test]
+    fn test_lab_lch() {
+        assert_approx("lab(54.2917 80.8125 69.8851)", 1.0, 0.0, 0.0, 1.0);
+        assert_approx("lab(54.2917% 64.65% 55.9081%)", 1.0, 0.0, 0.0, 1.0);
+        assert_approx("lch(54.2917 106.8390 40.8526)", 1.0, 0.0, 0.0, 1.0);
+        assert_approx("lab(100 0 0)", 1.0, 1.0, 1.0, 1.0);
+        assert_approx("lch(29.5676 131.2015 301.3638 / 0.5)", 0.0, 0.0, 1.0, 0.5);
+    }
+
+    #[test]
+    fn test_oklab_oklch() {
+        assert_approx("oklab(0.62796 0.22486 0.12585)", 1.0, 0.0, 0.0, 1.0);
+        assert_approx("oklab(62.796% 56.215% 31.4625%)", 1.0, 0.0, 0.0, 1.0);
+        assert_approx("oklch(0.62796 0.25768 29.2339)", 1.0, 0.0, 0.0, 1.0);
+        assert_approx("oklch(45.2014% 0.313214 264.052 / 20%)", 0.0, 0.0, 1.0, 0.2);
+    }
+
+    #[test]
+    fn test_color_function() {
+        assert_approx("color(srgb 1 0.5 0)", 1.0, 0.5, 0.0, 1.0);
+        assert_approx("color(srgb-linear 1 0.214041 0 / 50%)", 1.0, 0.5, 0.0, 0.5);
+        assert_approx("color(display-p3 1 0 0)", 1.0930, -0.2267, -0.1501, 1.0);
+        assert_approx("color(display-p3 0.917488 0.200287 0.138561)", 1.0, 0.0, 0.0, 1.0);
+        assert_approx("color(rec2020 0.791977 0.230976 0.073761)", 1.0, 0.0, 0.0, 1.0);
+        assert_approx("color(a98-rgb 0.858579 0 0)", 1.0, 0.0, 0.0, 1.0);
+        assert_approx("color(prophoto-rgb 0.702278 0.275752 0.103486)", 1.0, 0.0, 0.0, 1.0);
+        assert_approx("color(xyz 0.412391 0.212639 0.019331)", 1.0, 0.0, 0.0, 1.0);
+        assert_approx("color(xyz-d50 0.436075 0.222504 0.013932)", 1.0, 0.0, 0.0, 1.0);
+        assert_eq!(error("color(cmyk 1 0 0)"), (ParseColorErrorKind::UnknownColorSpace, 6));
+        assert_eq!(error("colour(srgb 1 0 0)"), (ParseColorErrorKind::UnknownFunction, 0));
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(error(""), (ParseColorErrorKind::Empty, 0));
+        assert_eq!(error("   "), (ParseColorErrorKind::Empty, 3));
+        assert_eq!(error("$"), (ParseColorErrorKind::UnexpectedCharacter, 0));
+        assert_eq!(format!("{}", "rgb(".parse::<Srgb<u8>>().unwrap_err()),
+                   "unexpected end of color string at position 4");
+    }
+
+    #[test]
+    fn test_format_hex() {
+        assert_eq!(format!("{}", Srgb::<u8>::new(0xFF, 0x80, 0x00)), "#ff8000");
+        assert_eq!(format!("{}", Srgb::<f32>::new(1.0, 0.5, 0.0)), "#ff8000");
+        assert_eq!(format!("{}", Srgba { c: Srgb::<u8>::new(0xFF, 0x80, 0x00), a: 0x80 }), "#ff800080");
+        assert_eq!(format!("{}", Srgb::<u8>::new(0xFF, 0x88, 0x00).to_css(CssFormat::ShortHex)), "#f80");
+        assert_eq!(format!("{}", Srgba { c: Srgb::<u8>::new(0xFF, 0x88, 0x00), a: 0xFF }.to_css(CssFormat::ShortHex)), "#f80");
+        assert_eq!(format!("{}", Srgba { c: Srgb::<u8>::new(0xFF, 0x88, 0x00), a: 0x88 }.to_css(CssFormat::ShortHex)), "#f808");
+        assert_eq!(format!("{}", Srgba { c: Srgb::<u8>::new(0xFF, 0x88, 0x01), a: 0xFF }.to_css(CssFormat::ShortHex)), "#ff8801");
+        assert_eq!(format!("{}", Srgba { c: Srgb::<u8>::new(0xFF, 0x88, 0x00), a: 0xFF }.to_css(CssFormat::Hex)), "#ff8800ff");
+    }
+
+    #[test]
+    fn test_format_functions() {
+        assert_eq!(format!("{}", Srgb::<u8>::new(0xFF, 0x80, 0x00).to_css(CssFormat::Rgb)), "rgb(255 128 0)");
+        assert_eq!(format!("{}", Srgba { c: Srgb::<f64>::new(1.0, 0.2, 0.0), a: 0.5 }.to_css(CssFormat::Rgb)), "rgb(255 51 0 / 0.5)");
+        assert_eq!(format!("{}", Srgba { c: Srgb::<f64>::new(1.0, 0.2, 0.0), a: 1.0 }.to_css(CssFormat::Rgb)), "rgb(255 51 0)");
+        assert_eq!(format!("{:.3}", Srgb::<f64>::new(1.0 / 3.0, 0.5, 0.0).to_css(CssFormat::Color)), "color(srgb 0.333 0.5 0)");
+        assert_eq!(format!("{}", Srgb::<u8>::new(0xFF, 0x80, 0x00).to_css(CssFormat::Hsl)), "hsl(30.11764705882353 100% 50%)");
+        assert_eq!(format!("{:.2}", Srgb::<u8>::new(0xFF, 0x80, 0x00).to_css(CssFormat::Hsl)), "hsl(30.12 100% 50%)");
+        // Hsv is defined over linear RGB, so the lightness and most hues change
+        assert_eq!(format!("{:.2}", Hsv::<f32>::new(Deg(30.0), 1.0, 1.0)), "hsl(44.12 100% 50%)");
+        assert_eq!(format!("{:.2}", Hsva { c: Hsv::<f64>::new(Deg(200.0), 0.0, 0.5), a: 0.25 }), "hsl(200 0% 73.54% / 0.25)");
+    }
+
+    #[test]
+    fn test_linear() {
+        // Linear components are encoded with the sRGB transfer function
+        let gray = Rgb::<f64>::new(0.214, 0.214, 0.214);
+        assert_eq!(gray.to_srgb::<u8>(), Srgb::new(0x7F, 0x7F, 0x7F));
+        assert_eq!(format!("{}", gray.to_css(CssFormat::Hex)), "#7f7f7f");
+        assert_eq!(format!("{}", gray), "color(srgb-linear 0.214 0.214 0.214)");
+        assert_eq!(format!("{}", Rgba { c: gray, a: 0.5 }), "color(srgb-linear 0.214 0.214 0.214 / 0.5)");
+        assert_eq!(format!("{:.3}", Srgb::new(0x80_u8, 0x80, 0x80).to_css(CssFormat::LinearColor)), "color(srgb-linear 0.216 0.216 0.216)");
+        assert_eq!(format!("{:.3}", gray.to_css(CssFormat::Color)), "color(srgb 0.5 0.5 0.5)");
+        let parsed = "#808080".parse::<Rgb<f64>>().unwrap();
+        assert_eq!(parsed, "#808080".parse::<Srgb<f64>>().unwrap().to_rgb());
+        assert!((parsed.r - 0.2158605).abs() < 1e-6, "{:?}", parsed);
+        assert_eq!("rgb(255 128 0 / 50%)".parse::<Rgba<u8>>(), Ok(Rgba { c: Srgb::new(0xFF_u8, 0x80, 0x00).to_rgb(), a: 0x80 }));
+    }
+
+    #[test]
+    fn test_round_trip_u8() {
+        let formats = [CssFormat::Hex, CssFormat::ShortHex, CssFormat::Rgb, CssFormat::Hsl, CssFormat::Color, CssFormat::LinearColor];
+        for r in (0..256).step_by(15) {
+            for g in (0..256).step_by(17) {
+                for b in (0..256).step_by(51) {
+                    for &a in [0u8, 0x33, 0xFF].iter() {
+                        let color = Srgba { c: Srgb::new(r as u8, g as u8, b as u8), a };
+                        for &format in formats.iter() {
+                            let s = format!("{}", color.to_css(format));
+                            assert_eq!(s.parse::<Srgba<u8>>(), Ok(color), "{}", s);
+                        }
+                        let color = Rgba { c: Rgb::new(r as u8, g as u8, b as u8), a };
+                        let s = format!("{}", color);
+                        assert_eq!(s.parse::<Rgba<u8>>(), Ok(color), "{}", s);
+                        for &format in formats[2..].iter() {
+                            let s = format!("{}", color.to_css(format));
+                            assert_eq!(s.parse::<Rgba<u8>>(), Ok(color), "{}", s);
+                        }
+                        // Hex rounds the encoded components to 8 bits, which
+                        // is exact for the sRGB value rather than the linear one
+                        let srgb = Rgba { c: color.c.to_srgb::<u8>().to_rgb(), a };
+                        for &format in formats[..2].iter() {
+                            let s = format!("{}", color.to_css(format));
+                            assert_eq!(s.parse::<Rgba<u8>>(), Ok(srgb), "{}", s);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_round_trip_integer_hsv() {
+        // Deg<u8> cannot be compared directly, as its PartialEq wraps by 360
+        for h in (0..256).step_by(15) {
+            for s in (51..256).step_by(51) {
+                for v in (51..256).step_by(51) {
+                    let hsv = Hsv::new(Deg(h as u8), s as u8, v as u8);
+                    let text = format!("{}", hsv);
+                    let back = text.parse::<Hsv<u8>>().unwrap();
+                    assert_eq!((back.h.value(), back.s, back.v), (hsv.h.value(), hsv.s, hsv.v), "{}", text);
+                }
+            }
+        }
+        for h in (0..360).step_by(25) {
+            let hsv = Hsv::new(Deg(h as u16), 0xCCCC, 0x3333);
+            let text = format!("{}", Hsva { c: hsv, a: 0x8000 });
+            assert_eq!(text.parse::<Hsv<u16>>(), Ok(hsv), "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_round_trip_other_channels() {
+        for i in (0..0x10000u32).step_by(0x101) {
+            let x = i as u16;
+            let color = Srgba { c: Srgb::new(x, !x, x / 3), a: x };
+            let s = format!("{}", color.to_css(CssFormat::Color));
+            assert_eq!(s.parse::<Srgba<u16>>(), Ok(color), "{}", s);
+        }
+        for i in 0..1000u32 {
+            let x = i as f32 / 999.0;
+            let color = Srgba { c: Srgb::new(x, 1.0 - x, x / 3.0), a: x };
+            for &format in [CssFormat::Rgb, CssFormat::Color].iter() {
+                let s = format!("{}", color.to_css(format));
+                assert_eq!(s.parse::<Srgba<f32>>(), Ok(color), "{}", s);
+            }
+
+            let x = i as f64 / 999.0;
+            let color = Srgba { c: Srgb::new(x, 1.0 - x, x / 3.0), a: x };
+            let s = format!("{}", color.to_css(CssFormat::Color));
+            assert_eq!(s.parse::<Srgba<f64>>(), Ok(color), "{}", s);
+        }
+        let hsv = Hsv::<f64>::new(Deg(210.0), 0.5, 0.8);
+        let parsed: Hsv<f64> = format!("{}", hsv).parse().unwrap();
+        assert!((parsed.h.value() - 210.0).abs() < 1e-9 && (parsed.s - 0.5).abs() < 1e-9 && (parsed.v - 0.8).abs() < 1e-9);
+    }
+}
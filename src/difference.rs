@@ -0,0 +1,257 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Color difference metrics in CIE L\*a\*b\*:
+//! http://en.wikipedia.org/wiki/Color_difference
+//!
+//! A difference of about `1.0` is commonly taken as the smallest difference
+//! an observer can notice. CIE94 and CMC are not symmetric: `self` is the
+//! reference color and `other` the sample.
+
+use std::f64::consts::PI;
+
+use {Lab, ToLab};
+
+/// The application specific weighting factors of CIE94.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Cie94Weighting {
+    GraphicArts,
+    Textiles,
+}
+
+impl Cie94Weighting {
+    /// Returns the `(kL, K1, K2)` factors.
+    #[inline]
+    pub fn factors(self) -> (f64, f64, f64) {
+        match self {
+            Cie94Weighting::GraphicArts => (1.0, 0.045, 0.015),
+            Cie94Weighting::Textiles    => (2.0, 0.048, 0.014),
+        }
+    }
+}
+
+pub trait ColorDifference {
+    /// The Euclidean distance in L\*a\*b\*, as defined by CIE76.
+    fn delta_e_76<C: ToLab>(&self, other: &C) -> f64;
+
+    /// The CIE94 difference with the given weighting.
+    fn delta_e_94<C: ToLab>(&self, other: &C, weighting: Cie94Weighting) -> f64;
+
+    /// The CIEDE2000 difference.
+    fn delta_e_2000<C: ToLab>(&self, other: &C) -> f64;
+
+    /// The CMC l:c difference. `l = 2, c = 1` is commonly used for
+    /// acceptability and `l = 1, c = 1` for perceptibility.
+    fn delta_e_cmc<C: ToLab>(&self, other: &C, l: f64, c: f64) -> f64;
+}
+
+impl<T: ToLab> ColorDifference for T {
+    fn delta_e_76<C: ToLab>(&self, other: &C) -> f64 {
+        let (x, y) = (self.to_lab::<f64>(), other.to_lab::<f64>());
+        ((x.l - y.l).powi(2) + (x.a - y.a).powi(2) + (x.b - y.b).powi(2)).sqrt()
+    }
+
+    fn delta_e_94<C: ToLab>(&self, other: &C, weighting: Cie94Weighting) -> f64 {
+        let (x, y) = (self.to_lab::<f64>(), other.to_lab::<f64>());
+        let (kl, k1, k2) = weighting.factors();
+        let c1 = chroma(&x);
+        let dl = x.l - y.l;
+        let dc = c1 - chroma(&y);
+        let dh2 = hue_difference_squared(&x, &y, dc);
+        let sc = 1.0 + k1 * c1;
+        let sh = 1.0 + k2 * c1;
+        ((dl / kl).powi(2) + (dc / sc).powi(2) + dh2 / (sh * sh)).sqrt()
+    }
+
+    fn delta_e_2000<C: ToLab>(&self, other: &C) -> f64 {
+        // Implementation notes from Sharma, Wu and Dalal, "The CIEDE2000
+        // Color-Difference Formula", Color Research & Application, 2005
+        let (x, y) = (self.to_lab::<f64>(), other.to_lab::<f64>());
+        let c_mean = (chroma(&x) + chroma(&y)) / 2.0;
+        let c_mean7 = c_mean.powi(7);
+        let g = 0.5 * (1.0 - (c_mean7 / (c_mean7 + 25f64.powi(7))).sqrt());
+
+        let a1 = (1.0 + g) * x.a;
+        let a2 = (1.0 + g) * y.a;
+        let c1 = a1.hypot(x.b);
+        let c2 = a2.hypot(y.b);
+        let h1 = hue_angle(x.b, a1);
+        let h2 = hue_angle(y.b, a2);
+
+        let dl = y.l - x.l;
+        let dc = c2 - c1;
+        let dh = if c1 * c2 == 0.0 {
+            0.0
+        } else if (h2 - h1).abs() <= 180.0 {
+            h2 - h1
+        } else if h2 - h1 > 180.0 {
+            h2 - h1 - 360.0
+        } else {
+            h2 - h1 + 360.0
+        };
+        let dh = 2.0 * (c1 * c2).sqrt() * (dh / 2.0).to_radians().sin();
+
+        let l_mean = (x.l + y.l) / 2.0;
+        let c_mean = (c1 + c2) / 2.0;
+        let h_mean = if c1 * c2 == 0.0 {
+            h1 + h2
+        } else if (h1 - h2).abs() <= 180.0 {
+            (h1 + h2) / 2.0
+        } else if h1 + h2 < 360.0 {
+            (h1 + h2 + 360.0) / 2.0
+        } else {
+            (h1 + h2 - 360.0) / 2.0
+        };
+
+        let t = 1.0 - 0.17 * (h_mean - 30.0).to_radians().cos()
+                    + 0.24 * (2.0 * h_mean).to_radians().cos()
+                    + 0.32 * (3.0 * h_mean + 6.0).to_radians().cos()
+                    - 0.20 * (4.0 * h_mean - 63.0).to_radians().cos();
+        let d_theta = 30.0 * (-((h_mean - 275.0) / 25.0).powi(2)).exp();
+        let c_mean7 = c_mean.powi(7);
+        let rc = 2.0 * (c_mean7 / (c_mean7 + 25f64.powi(7))).sqrt();
+        let sl = 1.0 + 0.015 * (l_mean - 50.0).powi(2) / (20.0 + (l_mean - 50.0).powi(2)).sqrt();
+        let sc = 1.0 + 0.045 * c_mean;
+        let sh = 1.0 + 0.015 * c_mean * t;
+        let rt = -(2.0 * d_theta).to_radians().sin() * rc;
+
+        ((dl / sl).powi(2) + (dc / sc).powi(2) + (dh / sh).powi(2) + rt * (dc / sc) * (dh / sh)).sqrt()
+    }
+
+    fn delta_e_cmc<C: ToLab>(&self, other: &C, l: f64, c: f64) -> f64 {
+        let (x, y) = (self.to_lab::<f64>(), other.to_lab::<f64>());
+        let c1 = chroma(&x);
+        let h1 = hue_angle(x.b, x.a);
+        let dl = x.l - y.l;
+        let dc = c1 - chroma(&y);
+        let dh2 = hue_difference_squared(&x, &y, dc);
+
+        let sl = if x.l < 16.0 { 0.511 } else { 0.040975 * x.l / (1.0 + 0.01765 * x.l) };
+        let sc = 0.0638 * c1 / (1.0 + 0.0131 * c1) + 0.638;
+        let c14 = c1.powi(4);
+        let f = (c14 / (c14 + 1900.0)).sqrt();
+        let t = if (164.0..=345.0).contains(&h1) {
+            0.56 + (0.2 * (h1 + 168.0).to_radians().cos()).abs()
+        } else {
+            0.36 + (0.4 * (h1 + 35.0).to_radians().cos()).abs()
+        };
+        let sh = sc * (f * t + 1.0 - f);
+
+        ((dl / (l * sl)).powi(2) + (dc / (c * sc)).powi(2) + dh2 / (sh * sh)).sqrt()
+    }
+}
+
+#[inline]
+fn chroma(lab: &Lab<f64>) -> f64 {
+    lab.a.hypot(lab.b)
+}
+
+/// The hue angle in degrees, in the range `[0, 360)`.
+#[inline]
+fn hue_angle(b: f64, a: f64) -> f64 {
+    if a == 0.0 && b == 0.0 {
+        0.0
+    } else {
+        let h = b.atan2(a) * 180.0 / PI;
+        if h < 0.0 { h + 360.0 } else { h }
+    }
+}
+
+/// The squared metric hue difference, derived from the chroma difference.
+#[inline]
+fn hue_difference_squared(x: &Lab<f64>, y: &Lab<f64>, dc: f64) -> f64 {
+    ((x.a - y.a).powi(2) + (x.b - y.b).powi(2) - dc * dc).max(0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use {Lab, Rgb, Srgb};
+    use super::{ColorDifference, Cie94Weighting};
+
+    // Test data from Sharma, Wu and Dalal:
+    // http://www2.ece.rochester.edu/~gsharma/ciede2000/
+    static SHARMA: [([f64; 3], [f64; 3], f64); 34] = [
+        ([50.0000,   2.6772, -79.7751], [50.0000,   0.0000, -82.7485],  2.0425),
+        ([50.0000,   3.1571, -77.2803], [50.0000,   0.0000, -82.7485],  2.8615),
+        ([50.0000,   2.8361, -74.0200], [50.0000,   0.0000, -82.7485],  3.4412),
+        ([50.0000,  -1.3802, -84.2814], [50.0000,   0.0000, -82.7485],  1.0000),
+        ([50.0000,  -1.1848, -84.8006], [50.0000,   0.0000, -82.7485],  1.0000),
+        ([50.0000,  -0.9009, -85.5211], [50.0000,   0.0000, -82.7485],  1.0000),
+        ([50.0000,   0.0000,   0.0000], [50.0000,  -1.0000,   2.0000],  2.3669),
+        ([50.0000,  -1.0000,   2.0000], [50.0000,   0.0000,   0.0000],  2.3669),
+        ([50.0000,   2.4900,  -0.0010], [50.0000,  -2.4900,   0.0009],  7.1792),
+        ([50.0000,   2.4900,  -0.0010], [50.0000,  -2.4900,   0.0010],  7.1792),
+        ([50.0000,   2.4900,  -0.0010], [50.0000,  -2.4900,   0.0011],  7.2195),
+        ([50.0000,   2.4900,  -0.0010], [50.0000,  -2.4900,   0.0012],  7.2195),
+        ([50.0000,  -0.0010,   2.4900], [50.0000,   0.0009,  -2.4900],  4.8045),
+        ([50.0000,  -0.0010,   2.4900], [50.0000,   0.0010,  -2.4900],  4.8045),
+        ([50.0000,  -0.0010,   2.4900], [50.0000,   0.0011,  -2.4900],  4.7461),
+        ([50.0000,   2.5000,   0.0000], [50.0000,   0.0000,  -2.5000],  4.3065),
+        ([50.0000,   2.5000,   0.0000], [73.0000,  25.0000, -18.0000], 27.1492),
+        ([50.0000,   2.5000,   0.0000], [61.0000,  -5.0000,  29.0000], 22.8977),
+        ([50.0000,   2.5000,   0.0000], [56.0000, -27.0000,  -3.0000], 31.9030),
+        ([50.0000,   2.5000,   0.0000], [58.0000,  24.0000,  15.0000], 19.4535),
+        ([50.0000,   2.5000,   0.0000], [50.0000,   3.1736,   0.5854],  1.0000),
+        ([50.0000,   2.5000,   0.0000], [50.0000,   3.2972,   0.0000],  1.0000),
+        ([50.0000,   2.5000,   0.0000], [50.0000,   1.8634,   0.5757],  1.0000),
+        ([50.0000,   2.5000,   0.0000], [50.0000,   3.2592,   0.3350],  1.0000),
+        ([60.2574, -34.0099,  36.2677], [60.4626, -34.1751,  39.4387],  1.2644),
+        ([63.0109, -31.0961,  -5.8663], [62.8187, -29.7946,  -4.0864],  1.2630),
+        ([61.2901,   3.7196,  -5.3901], [61.4292,   2.2480,  -4.9620],  1.8731),
+        ([35.0831, -44.1164,   3.7933], [35.0232, -40.0716,   1.5901],  1.8645),
+        ([22.7233,  20.0904, -46.6940], [23.0331,  14.9730, -42.5619],  2.0373),
+        ([36.4612,  47.8580,  18.3852], [36.2715,  50.5065,  21.2231],  1.4146),
+        ([90.8027,  -2.0831,   1.4410], [91.1528,  -1.6435,   0.0447],  1.4441),
+        ([90.9257,  -0.5406,  -0.9208], [88.6381,  -0.8985,  -0.7239],  1.5381),
+        ([ 6.7747,  -0.2908,  -2.4247], [ 5.8714,  -0.0985,  -2.2286],  0.6377),
+        ([ 2.0776,   0.0795,  -1.1350], [ 0.9033,  -0.0636,  -0.5514],  0.9082),
+    ];
+
+    fn lab(v: [f64; 3]) -> Lab<f64> {
+        Lab::new(v[0], v[1], v[2])
+    }
+
+    #[test]
+    fn test_delta_e_2000() {
+        for &(x, y, expected) in SHARMA.iter() {
+            let (x, y) = (lab(x), lab(y));
+            assert!((x.delta_e_2000(&y) - expected).abs() < 1e-4, "{:?} {:?}: {} != {}", x, y, x.delta_e_2000(&y), expected);
+            assert!((y.delta_e_2000(&x) - expected).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_other_metrics() {
+        let x = Lab::new(0.9, 16.3, -2.22);
+        let y = Lab::new(0.7, 14.2, -1.80);
+        assert!((x.delta_e_76(&y) - 2.151).abs() < 1e-3);
+        assert!((x.delta_e_94(&y, Cie94Weighting::GraphicArts) - 1.249).abs() < 1e-3);
+        assert!((x.delta_e_cmc(&y, 2.0, 1.0) - 1.443).abs() < 1e-3);
+        assert!((x.delta_e_2000(&y) - 1.523).abs() < 1e-3);
+        assert!(x.delta_e_94(&y, Cie94Weighting::Textiles) < x.delta_e_94(&y, Cie94Weighting::GraphicArts));
+        assert_eq!(x.delta_e_94(&x, Cie94Weighting::Textiles), 0.0);
+        assert_eq!(x.delta_e_cmc(&x, 1.0, 1.0), 0.0);
+    }
+
+    #[test]
+    fn test_rgb_difference() {
+        let red = Srgb::<u8>::new(0xFF, 0x00, 0x00);
+        assert_eq!(red.delta_e_2000(&red), 0.0);
+        assert!(red.delta_e_2000(&Srgb::<u8>::new(0xFE, 0x00, 0x00)) < 1.0);
+        assert!(red.delta_e_2000(&Srgb::<u8>::new(0xE0, 0x00, 0x00)) > 1.0);
+        let rgb = Rgb::<f32>::new(0.2, 0.4, 0.6);
+        assert!((rgb.delta_e_76(&lab([50.0, 0.0, 0.0])) - rgb.delta_e_76(&Lab::new(50.0f32, 0.0, 0.0))).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,329 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Gamut mapping, which brings colors that a working space cannot display
+//! into its gamut, as described by CSS Color Level 4:
+//! https://www.w3.org/TR/css-color-4/#gamut-mapping
+
+use angle::Angle;
+
+use {Channel, FloatChannel};
+use {Rgb, ToRgb, Srgb, Hsv, Hsl, YCbCr};
+use {Lab, Lch, Oklab, Oklch, ToOklab, ToOklch, Luv, Lchuv, Xyz, Yxy, Hsluv, Hpluv};
+use space::{RgbSpace, LinearSrgb};
+
+/// How far outside of `(0,1)` a component may be and still count as in
+/// gamut, to allow for the error of converting between spaces.
+const EPSILON: f64 = 1e-6;
+
+/// The deltaEOK below which a clipped color is indistinguishable from the
+/// color it was clipped from.
+const JND: f64 = 0.02;
+
+/// The precision of the chroma searches.
+const CHROMA_EPSILON: f64 = 1e-4;
+
+/// The strategy used to bring an out of gamut color into gamut.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GamutMapping {
+    /// Clamps each component to `(0,1)`. This is fast, but shifts the hue
+    /// and lightness of colors far out of gamut.
+    Clip,
+    /// The CSS Color 4 algorithm: reduces the Oklch chroma, keeping the
+    /// lightness and hue, until clipping the color changes it by less than a
+    /// just noticeable difference in deltaEOK. Colors whose lightness is out
+    /// of range become white or black.
+    Css,
+    /// Projects the color onto the gamut boundary at constant Oklch lightness
+    /// and hue, by reducing the chroma until the color is in gamut. Colors
+    /// whose lightness is out of range become white or black.
+    ConstantLightness,
+    /// Projects the color onto the gamut boundary at constant Oklch hue,
+    /// toward the gray with the lightness of the cusp of the gamut at that
+    /// hue, the most saturated color the space can display. Very light or
+    /// dark colors keep more of their chroma than with `ConstantLightness`,
+    /// at the cost of their lightness. Colors whose lightness is out of range
+    /// become white or black.
+    ConstantHue,
+}
+
+/// Returns whether all the components of a linear color are within `(0,1)`.
+#[inline]
+fn within<S:RgbSpace>(c: &Rgb<f64, S>) -> bool {
+    [c.r, c.g, c.b].iter().all(|&v| (-EPSILON..=1.0 + EPSILON).contains(&v))
+}
+
+#[inline]
+fn clip<S:RgbSpace>(c: &Rgb<f64, S>) -> Rgb<f64, S> {
+    Rgb::with_space(c.r.clamp(0.0, 1.0), c.g.clamp(0.0, 1.0), c.b.clamp(0.0, 1.0), S::default())
+}
+
+#[inline]
+fn to_oklch<S:RgbSpace>(c: &Rgb<f64, S>) -> Oklch<f64> {
+    c.to_space::<LinearSrgb>().to_oklch()
+}
+
+#[inline]
+fn from_oklch<S:RgbSpace>(c: &Oklch<f64>) -> Rgb<f64, S> {
+    c.to_rgb::<f64>().to_space()
+}
+
+/// The Euclidean distance in Oklab.
+#[inline]
+fn delta_e_ok(a: &Oklch<f64>, b: &Oklch<f64>) -> f64 {
+    let (a, b) = (a.to_oklab::<f64>(), b.to_oklab::<f64>());
+    ((a.l - b.l).powi(2) + (a.a - b.a).powi(2) + (a.b - b.b).powi(2)).sqrt()
+}
+
+/// Returns the Oklch lightness of the cusp of the gamut of `S` at the hue
+/// `h`. The cusp has one component at one and another at zero, so it lies on
+/// one of the six edges of the RGB cube between the primaries and the
+/// secondaries, along which the hue increases.
+fn cusp_lightness<S:RgbSpace>(h: f64) -> f64 {
+    const CORNERS: [[f64; 3]; 6] = [[1.0, 0.0, 0.0], [1.0, 1.0, 0.0], [0.0, 1.0, 0.0],
+                                    [0.0, 1.0, 1.0], [0.0, 0.0, 1.0], [1.0, 0.0, 1.0]];
+    let at = |a: &[f64; 3], b: &[f64; 3], t: f64| {
+        to_oklch(&Rgb::<f64, S>::with_space(a[0] + t * (b[0] - a[0]), a[1] + t * (b[1] - a[1]), a[2] + t * (b[2] - a[2]), S::default()))
+    };
+    // The hue from `from` to `to`, going in the increasing direction
+    let span = |from: f64, to: f64| (to - from).rem_euclid(360.0);
+    for i in 0..6 {
+        let (a, b) = (&CORNERS[i], &CORNERS[(i + 1) % 6]);
+        let start = at(a, b, 0.0).h.value();
+        let target = span(start, h);
+        if target > span(start, at(a, b, 1.0).h.value()) {
+            continue;
+        }
+        let (mut min, mut max) = (0.0, 1.0);
+        while max - min > CHROMA_EPSILON {
+            let t = (min + max) / 2.0;
+            if span(start, at(a, b, t).h.value()) < target {
+                min = t;
+            } else {
+                max = t;
+            }
+        }
+        return at(a, b, min).l;
+    }
+    // The edges go all the way around the hue circle, so this is only reached
+    // for a NaN hue
+    0.5
+}
+
+/// Maps a linear color of the space `S` into its gamut.
+fn map<S:RgbSpace>(color: &Rgb<f64, S>, method: GamutMapping) -> Rgb<f64, S> {
+    if within(color) {
+        return clip(color);
+    }
+    if method == GamutMapping::Clip {
+        return clip(color);
+    }
+    let origin = to_oklch(color);
+    if origin.l >= 1.0 {
+        return Rgb::with_space(1.0, 1.0, 1.0, S::default());
+    } else if origin.l <= 0.0 {
+        return Rgb::with_space(0.0, 0.0, 0.0, S::default());
+    }
+    let with_chroma = |c: f64| Oklch::new(origin.l, c, origin.h);
+    let (mut min, mut max) = (0.0, origin.c);
+    if method == GamutMapping::ConstantLightness {
+        while max - min > CHROMA_EPSILON {
+            let chroma = (min + max) / 2.0;
+            if within(&from_oklch::<S>(&with_chroma(chroma))) {
+                min = chroma;
+            } else {
+                max = chroma;
+            }
+        }
+        return clip(&from_oklch::<S>(&with_chroma(min)));
+    }
+    if method == GamutMapping::ConstantHue {
+        let cusp = cusp_lightness::<S>(origin.h.value());
+        // The point a fraction `t` of the way from the gray to the color
+        let toward = |t: f64| Oklch::new(cusp + t * (origin.l - cusp), t * origin.c, origin.h);
+        let (mut min, mut max) = (0.0, 1.0);
+        while max - min > CHROMA_EPSILON {
+            let t = (min + max) / 2.0;
+            if within(&from_oklch::<S>(&toward(t))) {
+                min = t;
+            } else {
+                max = t;
+            }
+        }
+        return clip(&from_oklch::<S>(&toward(min)));
+    }
+
+    let mut clipped = clip(color);
+    if delta_e_ok(&to_oklch(&clipped), &origin) < JND {
+        return clipped;
+    }
+    let mut min_in_gamut = true;
+    while max - min > CHROMA_EPSILON {
+        let chroma = (min + max) / 2.0;
+        let current = from_oklch::<S>(&with_chroma(chroma));
+        if min_in_gamut && within(&current) {
+            min = chroma;
+            continue;
+        }
+        clipped = clip(&current);
+        let e = delta_e_ok(&to_oklch(&clipped), &with_chroma(chroma));
+        if e < JND {
+            if JND - e < CHROMA_EPSILON {
+                return clipped;
+            }
+            min_in_gamut = false;
+            min = chroma;
+        } else {
+            max = chroma;
+        }
+    }
+    clipped
+}
+
+/// Colors that can be checked against, and mapped into, the gamut of an RGB
+/// working space.
+pub trait ToGamut {
+    /// Returns whether the color can be displayed in the working space `S`,
+    /// allowing for a small conversion error.
+    fn in_gamut<S:RgbSpace>(&self) -> bool;
+
+    /// Maps the color into the gamut of the working space `S` with the given
+    /// strategy, returning linear components in `(0,1)`.
+    fn map_to_gamut<S:RgbSpace>(&self, method: GamutMapping) -> Rgb<f64, S>;
+}
+
+impl<T:Channel, F:RgbSpace> ToGamut for Rgb<T, F> {
+    #[inline]
+    fn in_gamut<S:RgbSpace>(&self) -> bool {
+        within(&Rgb::<f64, F>::with_space(self.r.to_channel(), self.g.to_channel(), self.b.to_channel(), F::default())
+               .to_space::<S>())
+    }
+
+    #[inline]
+    fn map_to_gamut<S:RgbSpace>(&self, method: GamutMapping) -> Rgb<f64, S> {
+        map(&Rgb::<f64, F>::with_space(self.r.to_channel(), self.g.to_channel(), self.b.to_channel(), F::default())
+            .to_space::<S>(), method)
+    }
+}
+
+macro_rules! impl_to_gamut {
+    ($bound: ident: $($ty: ident),*) => {
+        $(
+            impl<T:$bound> ToGamut for $ty<T> {
+                #[inline]
+                fn in_gamut<S:RgbSpace>(&self) -> bool {
+                    self.to_rgb::<f64>().in_gamut::<S>()
+                }
+
+                #[inline]
+                fn map_to_gamut<S:RgbSpace>(&self, method: GamutMapping) -> Rgb<f64, S> {
+                    self.to_rgb::<f64>().map_to_gamut(method)
+                }
+            }
+        )*
+    }
+}
+
+impl_to_gamut!(Channel: Srgb, Hsv, Hsl, YCbCr);
+impl_to_gamut!(FloatChannel: Lab, Lch, Oklab, Oklch, Luv, Lchuv, Xyz, Yxy, Hsluv, Hpluv);
+
+#[cfg(test)]
+mod tests {
+    use {Rgb, Oklch, ToOklch, Lab};
+    use space::{DisplayP3, LinearSrgb, Rec2020};
+    use super::{ToGamut, GamutMapping, cusp_lightness};
+    use angle::*;
+
+    fn in_unit<S>(c: Rgb<f64, S>) -> bool {
+        [c.r, c.g, c.b].iter().all(|&v| (0.0..=1.0).contains(&v))
+    }
+
+    #[test]
+    fn test_in_gamut() {
+        assert!(Rgb::new(1.0f64, 0.5, 0.0).in_gamut::<LinearSrgb>());
+        assert!(Rgb::new(255u8, 128, 0).in_gamut::<DisplayP3>());
+        assert!(!Rgb::new(1.1f64, 0.5, 0.0).in_gamut::<LinearSrgb>());
+        // P3 green is outside of sRGB, but sRGB green is inside P3
+        let p3_green = Rgb::with_space(0.0f64, 1.0, 0.0, DisplayP3);
+        assert!(p3_green.in_gamut::<DisplayP3>());
+        assert!(!p3_green.in_gamut::<LinearSrgb>());
+        assert!(p3_green.in_gamut::<Rec2020>());
+        assert!(Rgb::new(0.0f64, 1.0, 0.0).in_gamut::<DisplayP3>());
+        assert!(Oklch::new(0.5f64, 0.05, Deg(120.0)).in_gamut::<LinearSrgb>());
+        assert!(!Oklch::new(0.5f64, 0.4, Deg(120.0)).in_gamut::<LinearSrgb>());
+    }
+
+    #[test]
+    fn test_clip() {
+        let c = Rgb::new(1.5f64, -0.2, 0.5).map_to_gamut::<LinearSrgb>(GamutMapping::Clip);
+        assert_eq!(c, Rgb::new(1.0, 0.0, 0.5));
+        // Colors in gamut are not changed
+        for &method in [GamutMapping::Clip, GamutMapping::Css, GamutMapping::ConstantLightness, GamutMapping::ConstantHue].iter() {
+            let c = Rgb::new(0.2f64, 0.4, 0.6).map_to_gamut::<LinearSrgb>(method);
+            assert!((c.r - 0.2).abs() < 1e-12 && (c.g - 0.4).abs() < 1e-12 && (c.b - 0.6).abs() < 1e-12, "{:?}", c);
+        }
+    }
+
+    #[test]
+    fn test_chroma_reduction() {
+        let origin = Oklch::new(0.7f64, 0.35, Deg(150.0));
+        for &method in [GamutMapping::Css, GamutMapping::ConstantLightness].iter() {
+            let mapped = origin.map_to_gamut::<LinearSrgb>(method);
+            assert!(in_unit(mapped), "{:?}", mapped);
+            let oklch = mapped.to_oklch::<f64>();
+            // The lightness and hue are kept up to the final clip, the chroma reduced
+            assert!((oklch.l - 0.7).abs() < 0.02, "{:?} {:?}", method, oklch);
+            assert!((oklch.h.value() - 150.0).abs() < 5.0, "{:?} {:?}", method, oklch);
+            assert!(oklch.c < 0.35);
+        }
+        // Projecting at constant lightness keeps the lightness and hue exactly
+        let oklch = origin.map_to_gamut::<LinearSrgb>(GamutMapping::ConstantLightness).to_oklch::<f64>();
+        assert!((oklch.l - 0.7).abs() < 1e-4 && (oklch.h.value() - 150.0).abs() < 1e-2, "{:?}", oklch);
+        // P3 keeps more chroma than sRGB
+        let p3 = origin.map_to_gamut::<DisplayP3>(GamutMapping::Css).to_space::<LinearSrgb>().to_oklch::<f64>();
+        assert!(p3.c > origin.map_to_gamut::<LinearSrgb>(GamutMapping::Css).to_oklch::<f64>().c);
+    }
+
+    #[test]
+    fn test_constant_hue() {
+        // The cusps of the primaries and secondaries are the colors themselves
+        let red = Rgb::new(1.0f64, 0.0, 0.0).to_oklch::<f64>();
+        assert!((cusp_lightness::<LinearSrgb>(red.h.value()) - red.l).abs() < 1e-3);
+        let cyan = Rgb::with_space(0.0f64, 1.0, 1.0, DisplayP3).to_space::<LinearSrgb>().to_oklch::<f64>();
+        assert!((cusp_lightness::<DisplayP3>(cyan.h.value()) - cyan.l).abs() < 1e-3);
+
+        // A light green far outside of sRGB
+        let origin = Oklch::new(0.95f64, 0.3, Deg(150.0));
+        let cusp = cusp_lightness::<LinearSrgb>(150.0);
+        let mapped = origin.map_to_gamut::<LinearSrgb>(GamutMapping::ConstantHue);
+        assert!(in_unit(mapped), "{:?}", mapped);
+        let oklch = mapped.to_oklch::<f64>();
+        // The hue is kept, and the color moved toward the gray of the cusp
+        assert!((oklch.h.value() - 150.0).abs() < 1e-2, "{:?}", oklch);
+        assert!(oklch.l < 0.95 && oklch.l > cusp, "{:?}", oklch);
+        assert!(((oklch.l - cusp) / (0.95 - cusp) - oklch.c / 0.3).abs() < 1e-3, "{:?}", oklch);
+        // Giving up lightness keeps more chroma
+        let constant_lightness = origin.map_to_gamut::<LinearSrgb>(GamutMapping::ConstantLightness).to_oklch::<f64>();
+        assert!(oklch.c > constant_lightness.c, "{:?} {:?}", oklch, constant_lightness);
+    }
+
+    #[test]
+    fn test_lightness_out_of_range() {
+        let white = Lab::new(110.0f64, 20.0, 0.0).map_to_gamut::<LinearSrgb>(GamutMapping::Css);
+        assert_eq!(white, Rgb::new(1.0, 1.0, 1.0));
+        let black = Oklch::new(-0.1f64, 0.1, Deg(30.0)).map_to_gamut::<DisplayP3>(GamutMapping::ConstantHue);
+        assert_eq!(black, Rgb::with_space(0.0, 0.0, 0.0, DisplayP3));
+    }
+}
@@ -0,0 +1,408 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Gradients through any number of color stops, interpolated in a selectable
+//! color space with premultiplied alpha, following CSS Color Level 4:
+//! https://www.w3.org/TR/css-color-4/#interpolation
+
+use std::cmp::Ordering;
+
+use angle::*;
+
+use {Channel, FloatChannel};
+use {AlphaColor, Rgb, Rgba, ToRgb, Srgb, ToSrgb, Hsv, ToHsv};
+use {Oklab, ToOklab, Oklch, ToOklch, Lab, ToLab};
+use HueInterpolation;
+use space::{RgbSpace, LinearSrgb};
+use oklab::ACHROMATIC;
+
+/// The color space in which a gradient is interpolated.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum InterpolationSpace {
+    /// Linear light sRGB, which blends like light does.
+    LinearRgb,
+    /// Gamma encoded sRGB, as most image editors and legacy CSS do.
+    Srgb,
+    /// Oklab, which gives perceptually even gradients. This is the CSS
+    /// default.
+    Oklab,
+    /// CIE L\*a\*b\*.
+    Lab,
+    /// HSV, interpolating the hue around the color wheel.
+    Hsv,
+    /// Oklch, interpolating the hue around the color wheel.
+    Oklch,
+}
+
+impl InterpolationSpace {
+    /// Returns the index of the hue component, for the polar spaces.
+    fn hue(self) -> Option<usize> {
+        match self {
+            InterpolationSpace::Hsv => Some(0),
+            InterpolationSpace::Oklch => Some(2),
+            _ => None,
+        }
+    }
+
+    /// Returns whether the hue of the given components is powerless, like
+    /// the hue of a gray, for the polar spaces.
+    fn powerless(self, v: [f64; 4]) -> bool {
+        match self {
+            InterpolationSpace::Hsv => v[1].abs() < 1e-9 || v[2].abs() < 1e-9,
+            InterpolationSpace::Oklch => v[1].abs() < ACHROMATIC,
+            _ => false,
+        }
+    }
+
+    /// Converts a linear RGB color to the components of this space.
+    fn components(self, c: Rgb<f64>) -> [f64; 3] {
+        match self {
+            InterpolationSpace::LinearRgb => [c.r, c.g, c.b],
+            InterpolationSpace::Srgb => [LinearSrgb::encode(c.r), LinearSrgb::encode(c.g), LinearSrgb::encode(c.b)],
+            InterpolationSpace::Oklab => { let c = c.to_oklab::<f64>(); [c.l, c.a, c.b] }
+            InterpolationSpace::Lab => { let c = c.to_lab::<f64>(); [c.l, c.a, c.b] }
+            InterpolationSpace::Hsv => { let c = c.to_hsv::<f64>(); [c.h.value(), c.s, c.v] }
+            InterpolationSpace::Oklch => { let c = c.to_oklch::<f64>(); [c.l, c.c, c.h.value()] }
+        }
+    }
+
+    /// Converts the components of this space back to linear RGB.
+    fn rgb(self, v: [f64; 3]) -> Rgb<f64> {
+        match self {
+            InterpolationSpace::LinearRgb => Rgb::new(v[0], v[1], v[2]),
+            InterpolationSpace::Srgb => Rgb::new(LinearSrgb::decode(v[0]), LinearSrgb::decode(v[1]), LinearSrgb::decode(v[2])),
+            InterpolationSpace::Oklab => Oklab::new(v[0], v[1], v[2]).to_rgb(),
+            InterpolationSpace::Lab => Lab::new(v[0], v[1], v[2]).to_rgb(),
+            InterpolationSpace::Hsv => Hsv::new(Deg(v[0]), v[1], v[2]).to_rgb(),
+            InterpolationSpace::Oklch => Oklch::new(v[0], v[1], Deg(v[2])).to_rgb(),
+        }
+    }
+
+    /// Interpolates between two colors given as components of this space
+    /// followed by the alpha. The non-hue components are premultiplied by the
+    /// alpha, and the hue is interpolated with `method`.
+    fn interpolate(self, a: [f64; 4], b: [f64; 4], t: f64, method: HueInterpolation) -> [f64; 4] {
+        let alpha = a[3] + (b[3] - a[3]) * t;
+        let mut out = [0.0, 0.0, 0.0, alpha];
+        for i in 0..3 {
+            out[i] = if self.hue() == Some(i) {
+                method.interpolate_powerless(a[i], self.powerless(a), b[i], self.powerless(b), t)
+            } else if alpha == 0.0 {
+                a[i] + (b[i] - a[i]) * t
+            } else {
+                (a[i] * a[3] + (b[i] * b[3] - a[i] * a[3]) * t) / alpha
+            };
+        }
+        out
+    }
+}
+
+/// A color that can be a gradient stop. Stops are converted to linear sRGB
+/// with straight alpha on their way to and from the interpolation space.
+pub trait GradientColor: Copy {
+    fn to_gradient_rgba(&self) -> Rgba<f64>;
+    fn from_gradient_rgba(color: Rgba<f64>) -> Self;
+}
+
+impl<T:Channel> GradientColor for Rgb<T> {
+    #[inline]
+    fn to_gradient_rgba(&self) -> Rgba<f64> {
+        Rgba { c: self.to_rgb(), a: 1.0 }
+    }
+
+    #[inline]
+    fn from_gradient_rgba(color: Rgba<f64>) -> Rgb<T> {
+        color.c.to_rgb()
+    }
+}
+
+impl<T:Channel> GradientColor for Srgb<T> {
+    #[inline]
+    fn to_gradient_rgba(&self) -> Rgba<f64> {
+        Rgba { c: self.to_rgb(), a: 1.0 }
+    }
+
+    #[inline]
+    fn from_gradient_rgba(color: Rgba<f64>) -> Srgb<T> {
+        color.c.to_srgb()
+    }
+}
+
+impl<T:FloatChannel> GradientColor for Oklab<T> {
+    #[inline]
+    fn to_gradient_rgba(&self) -> Rgba<f64> {
+        Rgba { c: self.to_rgb(), a: 1.0 }
+    }
+
+    #[inline]
+    fn from_gradient_rgba(color: Rgba<f64>) -> Oklab<T> {
+        color.c.to_oklab()
+    }
+}
+
+impl<T:FloatChannel> GradientColor for Oklch<T> {
+    #[inline]
+    fn to_gradient_rgba(&self) -> Rgba<f64> {
+        Rgba { c: self.to_rgb(), a: 1.0 }
+    }
+
+    #[inline]
+    fn from_gradient_rgba(color: Rgba<f64>) -> Oklch<T> {
+        color.c.to_oklch()
+    }
+}
+
+impl<T:FloatChannel> GradientColor for Lab<T> {
+    #[inline]
+    fn to_gradient_rgba(&self) -> Rgba<f64> {
+        Rgba { c: self.to_rgb(), a: 1.0 }
+    }
+
+    #[inline]
+    fn from_gradient_rgba(color: Rgba<f64>) -> Lab<T> {
+        color.c.to_lab()
+    }
+}
+
+impl<T:Channel, C:GradientColor> GradientColor for AlphaColor<T, C> {
+    #[inline]
+    fn to_gradient_rgba(&self) -> Rgba<f64> {
+        Rgba { c: self.c.to_gradient_rgba().c, a: self.a.to_channel() }
+    }
+
+    #[inline]
+    fn from_gradient_rgba(color: Rgba<f64>) -> AlphaColor<T, C> {
+        AlphaColor { c: C::from_gradient_rgba(Rgba { c: color.c, a: 1.0 }), a: color.a.to_channel() }
+    }
+}
+
+/// A gradient through a list of `(position, color)` stops.
+///
+/// Before the first stop and after the last one the gradient keeps the color
+/// of that stop. Two stops at the same position make a hard transition.
+#[derive(Clone, Debug)]
+pub struct Gradient<C> {
+    stops: Vec<(f64, C)>,
+    space: InterpolationSpace,
+    hue_interpolation: HueInterpolation,
+}
+
+impl<C:GradientColor> Gradient<C> {
+    /// Creates a gradient interpolated in Oklab, with hues in polar spaces
+    /// taking the shorter way around the circle. The stops are sorted by
+    /// position, keeping the given order for equal positions.
+    ///
+    /// Panics if `stops` is empty.
+    pub fn new(stops: Vec<(f64, C)>) -> Gradient<C> {
+        assert!(!stops.is_empty(), "a gradient needs at least one stop");
+        let mut stops = stops;
+        stops.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal));
+        Gradient { stops, space: InterpolationSpace::Oklab, hue_interpolation: HueInterpolation::Shorter }
+    }
+
+    /// Sets the color space the gradient is interpolated in.
+    pub fn in_space(self, space: InterpolationSpace) -> Gradient<C> {
+        Gradient { space, ..self }
+    }
+
+    /// Sets how hues are interpolated when the gradient is interpolated in a
+    /// polar space.
+    pub fn with_hue_interpolation(self, method: HueInterpolation) -> Gradient<C> {
+        Gradient { hue_interpolation: method, ..self }
+    }
+
+    /// Returns the sorted stops of the gradient.
+    #[inline]
+    pub fn stops(&self) -> &[(f64, C)] {
+        &self.stops
+    }
+
+    /// Returns the color space the gradient is interpolated in.
+    #[inline]
+    pub fn space(&self) -> InterpolationSpace {
+        self.space
+    }
+
+    /// Returns how hues are interpolated in polar spaces.
+    #[inline]
+    pub fn hue_interpolation(&self) -> HueInterpolation {
+        self.hue_interpolation
+    }
+
+    /// Returns the color of the gradient at position `t`.
+    pub fn sample(&self, t: f64) -> C {
+        let next = self.stops.iter().position(|&(p, _)| p > t);
+        let (from, to) = match next {
+            Some(0) => return self.stops[0].1,
+            None => return self.stops[self.stops.len() - 1].1,
+            Some(i) => (self.stops[i - 1], self.stops[i]),
+        };
+        let to_components = |color: C| {
+            let rgba = color.to_gradient_rgba();
+            let c = self.space.components(rgba.c);
+            [c[0], c[1], c[2], rgba.a]
+        };
+        let t = (t - from.0) / (to.0 - from.0);
+        let c = self.space.interpolate(to_components(from.1), to_components(to.1), t, self.hue_interpolation);
+        C::from_gradient_rgba(Rgba { c: self.space.rgb([c[0], c[1], c[2]]), a: c[3] })
+    }
+
+    /// Returns an iterator over `count` evenly spaced samples, from the first
+    /// stop to the last one.
+    pub fn samples(&self, count: usize) -> Samples<'_, C> {
+        Samples { gradient: self, index: 0, count }
+    }
+
+    /// Bakes the gradient into a lookup table of `size` evenly spaced
+    /// samples, from the first stop to the last one.
+    pub fn to_lut(&self, size: usize) -> Vec<C> {
+        self.samples(size).collect()
+    }
+}
+
+/// An iterator over evenly spaced samples of a gradient, created by
+/// `Gradient::samples`.
+#[derive(Clone, Debug)]
+pub struct Samples<'a, C: 'a> {
+    gradient: &'a Gradient<C>,
+    index: usize,
+    count: usize,
+}
+
+impl<'a, C:GradientColor> Iterator for Samples<'a, C> {
+    type Item = C;
+
+    fn next(&mut self) -> Option<C> {
+        if self.index >= self.count {
+            return None;
+        }
+        let stops = &self.gradient.stops;
+        let (start, end) = (stops[0].0, stops[stops.len() - 1].0);
+        let t = if self.count == 1 {
+            start
+        } else if self.index == self.count - 1 {
+            end
+        } else {
+            start + (end - start) * self.index as f64 / (self.count - 1) as f64
+        };
+        self.index += 1;
+        Some(self.gradient.sample(t))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.count - self.index.min(self.count);
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'a, C:GradientColor> ExactSizeIterator for Samples<'a, C> {}
+
+#[cfg(test)]
+mod tests {
+    use {Rgb, Rgba, Srgb, Srgba, Oklch, HueInterpolation};
+    use super::{Gradient, InterpolationSpace};
+    use angle::*;
+
+    fn assert_close(a: [f64; 4], b: [f64; 4]) {
+        for i in 0..4 {
+            assert!((a[i] - b[i]).abs() < 1e-3, "{:?} != {:?}", a, b);
+        }
+    }
+
+    fn srgba(c: Srgba<f64>) -> [f64; 4] {
+        [c.c.r, c.c.g, c.c.b, c.a]
+    }
+
+    #[test]
+    fn test_stops() {
+        let gradient = Gradient::new(vec![(1.0, Rgb::new(0.0, 0.0, 1.0)),
+                                          (0.0, Rgb::new(1.0, 0.0, 0.0)),
+                                          (0.5, Rgb::new(0.0, 1.0, 0.0)),
+                                          (0.5, Rgb::new(1.0, 1.0, 1.0))])
+            .in_space(InterpolationSpace::LinearRgb);
+        assert_eq!(gradient.sample(-1.0), Rgb::new(1.0, 0.0, 0.0));
+        assert_eq!(gradient.sample(0.0), Rgb::new(1.0, 0.0, 0.0));
+        assert_eq!(gradient.sample(0.25), Rgb::new(0.5, 0.5, 0.0));
+        // The second stop at 0.5 takes over from there
+        assert_eq!(gradient.sample(0.5), Rgb::new(1.0, 1.0, 1.0));
+        assert_eq!(gradient.sample(0.75), Rgb::new(0.5, 0.5, 1.0));
+        assert_eq!(gradient.sample(2.0), Rgb::new(0.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn test_spaces() {
+        let black = Srgba { c: Srgb::new(0.0, 0.0, 0.0), a: 1.0 };
+        let white = Srgba { c: Srgb::new(1.0, 1.0, 1.0), a: 1.0 };
+        let gradient = Gradient::new(vec![(0.0, black), (1.0, white)]);
+        let mid = |space| srgba(gradient.clone().in_space(space).sample(0.5));
+        assert_close(mid(InterpolationSpace::Srgb), [0.5, 0.5, 0.5, 1.0]);
+        assert_close(mid(InterpolationSpace::LinearRgb), [0.7354, 0.7354, 0.7354, 1.0]);
+        assert_close(mid(InterpolationSpace::Oklab), [0.3882, 0.3882, 0.3882, 1.0]);
+        assert_close(mid(InterpolationSpace::Lab), [0.4663, 0.4663, 0.4663, 1.0]);
+    }
+
+    #[test]
+    fn test_hue() {
+        // Red to blue the short way goes through magenta
+        let gradient = Gradient::new(vec![(0.0, Oklch::new(0.6f64, 0.2, Deg(10.0))),
+                                          (1.0, Oklch::new(0.6, 0.2, Deg(330.0)))])
+            .in_space(InterpolationSpace::Oklch);
+        let mid = gradient.sample(0.5);
+        assert!((mid.h.value() - 350.0).abs() < 1e-3, "{:?}", mid);
+        let gradient = Gradient::new(vec![(0.0, Rgb::new(1.0f64, 0.0, 0.0)), (1.0, Rgb::new(0.0, 0.0, 1.0))])
+            .in_space(InterpolationSpace::Hsv);
+        let mid = gradient.sample(0.5);
+        assert!((mid.r - 1.0).abs() < 1e-9 && mid.g.abs() < 1e-9 && (mid.b - 1.0).abs() < 1e-9, "{:?}", mid);
+        // The long way goes through green
+        let mid = gradient.with_hue_interpolation(HueInterpolation::Longer).sample(0.5);
+        assert!(mid.r.abs() < 1e-9 && (mid.g - 1.0).abs() < 1e-9 && mid.b.abs() < 1e-9, "{:?}", mid);
+        // A gray stop keeps the hue of the other stop
+        let gradient = Gradient::new(vec![(0.0, Oklch::new(0.6f64, 0.2, Deg(120.0))),
+                                          (1.0, Oklch::new(0.6, 0.0, Deg(0.0)))])
+            .in_space(InterpolationSpace::Oklch);
+        let mid = gradient.sample(0.5);
+        assert!((mid.h.value() - 120.0).abs() < 1e-3, "{:?}", mid);
+    }
+
+    #[test]
+    fn test_premultiplied() {
+        // Fading to transparent does not pick up the color of the transparent stop
+        let red = Rgba { c: Rgb::new(1.0f64, 0.0, 0.0), a: 1.0 };
+        let transparent = Rgba { c: Rgb::new(0.0, 0.0, 1.0), a: 0.0 };
+        let gradient = Gradient::new(vec![(0.0, red), (1.0, transparent)]).in_space(InterpolationSpace::LinearRgb);
+        let mid = gradient.sample(0.5);
+        assert_eq!(mid, Rgba { c: Rgb::new(1.0, 0.0, 0.0), a: 0.5 });
+        let half = Rgba { c: Rgb::new(0.0, 0.0, 1.0), a: 0.5 };
+        let mid = Gradient::new(vec![(0.0, red), (1.0, half)]).in_space(InterpolationSpace::LinearRgb).sample(0.5);
+        assert!((mid.c.r - 2.0 / 3.0).abs() < 1e-12 && (mid.c.b - 1.0 / 3.0).abs() < 1e-12 && mid.a == 0.75);
+    }
+
+    #[test]
+    fn test_samples() {
+        let gradient = Gradient::new(vec![(0.0, Rgb::new(0u8, 0, 0)), (1.0, Rgb::new(0xFF, 0xFF, 0xFF))])
+            .in_space(InterpolationSpace::LinearRgb);
+        let samples = gradient.samples(5);
+        assert_eq!(samples.len(), 5);
+        let lut = gradient.to_lut(256);
+        assert_eq!(lut.len(), 256);
+        assert_eq!(lut[0], Rgb::new(0, 0, 0));
+        assert_eq!(lut[255], Rgb::new(0xFF, 0xFF, 0xFF));
+        assert_eq!(gradient.to_lut(1), vec![Rgb::new(0, 0, 0)]);
+        assert!(gradient.to_lut(0).is_empty());
+        let values: Vec<u8> = gradient.samples(3).map(|c| c.r).collect();
+        assert!(values[0] < values[1] && values[1] < values[2]);
+    }
+}
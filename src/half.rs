@@ -0,0 +1,317 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! IEEE 754 half precision floats, the format of 16 bit float textures and
+//! HDR images.
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Sub, Mul, Div, Rem, Neg};
+
+use num::{Num, NumCast, ToPrimitive, Zero, One};
+
+/// A 16 bit IEEE 754 binary16 float, with 1 sign bit, 5 exponent bits and 10
+/// mantissa bits. Arithmetic is done in `f32` and rounded back to the
+/// nearest `f16`, with ties to even.
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Default)]
+pub struct f16(u16);
+
+/// Shifts `value` right by `shift` bits, rounding to the nearest value with
+/// ties to even.
+#[inline]
+fn shift_round(value: u64, shift: u32) -> u64 {
+    if shift >= 64 {
+        return 0;
+    }
+    let (quotient, remainder) = (value >> shift, value & ((1 << shift) - 1));
+    let half = 1 << (shift - 1);
+    if remainder > half || (remainder == half && quotient & 1 == 1) {
+        quotient + 1
+    } else {
+        quotient
+    }
+}
+
+/// Encodes a value as a float with a 5 bit exponent biased by 15 and
+/// `mantissa` mantissa bits, with the sign in the bit above the exponent.
+/// This is the layout of `f16` and, without the sign, of the 11 and 10 bit
+/// floats of packed pixel formats.
+///
+/// Rounds to the nearest value with ties to even, and values too large
+/// become infinite.
+pub fn encode(value: f64, mantissa: u32) -> u32 {
+    let bits = value.to_bits();
+    let sign = ((bits >> 63) as u32) << (mantissa + 5);
+    let exponent = ((bits >> 52) & 0x7FF) as i32;
+    let fraction = bits & 0xF_FFFF_FFFF_FFFF;
+    let infinity = 0x1F << mantissa;
+    if exponent == 0x7FF {
+        return sign | if fraction == 0 { infinity } else { infinity | (1 << (mantissa - 1)) };
+    }
+    // The biased exponent of the result
+    let e = exponent - 1023 + 15;
+    if e >= 0x1F {
+        return sign | infinity;
+    }
+    let fraction = if exponent == 0 { fraction } else { fraction | (1 << 52) };
+    let shift = 52 - mantissa;
+    if e <= 0 {
+        // Subnormal, in units of the smallest subnormal. A carry into the
+        // exponent gives the smallest normal value.
+        return sign | shift_round(fraction, (shift as i32 + 1 - e) as u32) as u32;
+    }
+    // A carry out of the mantissa increments the exponent, and may give
+    // infinity.
+    let rounded = shift_round(fraction, shift) as u32;
+    sign | (((e as u32) << mantissa) + (rounded - (1 << mantissa)))
+}
+
+/// Decodes a float encoded by `encode`.
+pub fn decode(bits: u32, mantissa: u32) -> f64 {
+    let sign = if bits >> (mantissa + 5) & 1 == 0 { 1.0 } else { -1.0 };
+    let exponent = ((bits >> mantissa) & 0x1F) as i32;
+    let fraction = (bits & ((1 << mantissa) - 1)) as f64;
+    let one = (1u32 << mantissa) as f64;
+    match exponent {
+        0 => sign * fraction / one * 2f64.powi(-14),
+        0x1F if fraction == 0.0 => sign * f64::INFINITY,
+        0x1F => f64::NAN,
+        _ => sign * (one + fraction) / one * 2f64.powi(exponent - 15),
+    }
+}
+
+impl f16 {
+    pub const ZERO: f16 = f16(0x0000);
+    pub const ONE: f16 = f16(0x3C00);
+    pub const INFINITY: f16 = f16(0x7C00);
+    pub const NEG_INFINITY: f16 = f16(0xFC00);
+    pub const NAN: f16 = f16(0x7E00);
+    /// The largest finite value, `65504`.
+    pub const MAX: f16 = f16(0x7BFF);
+    /// The smallest positive normal value, `2^-14`.
+    pub const MIN_POSITIVE: f16 = f16(0x0400);
+    /// The difference between `1` and the next larger value, `2^-10`.
+    pub const EPSILON: f16 = f16(0x1400);
+
+    #[inline]
+    pub fn from_bits(bits: u16) -> f16 {
+        f16(bits)
+    }
+
+    #[inline]
+    pub fn to_bits(self) -> u16 {
+        self.0
+    }
+
+    /// Converts a `f32` to the nearest `f16`, with ties to even. Values too
+    /// large for a `f16` become infinite.
+    #[inline]
+    pub fn from_f32(value: f32) -> f16 {
+        // Every f32 is exactly representable as a f64
+        f16::from_f64(value as f64)
+    }
+
+    /// Converts a `f64` to the nearest `f16`, with ties to even. Values too
+    /// large for a `f16` become infinite.
+    #[inline]
+    pub fn from_f64(value: f64) -> f16 {
+        f16(encode(value, 10) as u16)
+    }
+
+    #[inline]
+    pub fn to_f32(self) -> f32 {
+        self.to_f64() as f32
+    }
+
+    #[inline]
+    pub fn to_f64(self) -> f64 {
+        decode(self.0 as u32, 10)
+    }
+
+    #[inline]
+    pub fn is_nan(self) -> bool {
+        self.0 & 0x7C00 == 0x7C00 && self.0 & 0x3FF != 0
+    }
+
+    #[inline]
+    pub fn is_finite(self) -> bool {
+        self.0 & 0x7C00 != 0x7C00
+    }
+}
+
+impl PartialEq for f16 {
+    #[inline]
+    fn eq(&self, other: &f16) -> bool {
+        f16::to_f32(*self) == f16::to_f32(*other)
+    }
+}
+
+impl PartialOrd for f16 {
+    #[inline]
+    fn partial_cmp(&self, other: &f16) -> Option<Ordering> {
+        f16::to_f32(*self).partial_cmp(&f16::to_f32(*other))
+    }
+}
+
+impl fmt::Debug for f16 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&f16::to_f32(*self), f)
+    }
+}
+
+impl fmt::Display for f16 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&f16::to_f32(*self), f)
+    }
+}
+
+macro_rules! impl_op {
+    ($op: ident, $method: ident) => {
+        impl $op for f16 {
+            type Output = f16;
+
+            #[inline]
+            fn $method(self, rhs: f16) -> f16 {
+                f16::from_f32(self.to_f32().$method(rhs.to_f32()))
+            }
+        }
+    }
+}
+
+impl_op!(Add, add);
+impl_op!(Sub, sub);
+impl_op!(Mul, mul);
+impl_op!(Div, div);
+impl_op!(Rem, rem);
+
+impl Neg for f16 {
+    type Output = f16;
+
+    #[inline]
+    fn neg(self) -> f16 {
+        f16(self.0 ^ 0x8000)
+    }
+}
+
+impl Zero for f16 {
+    #[inline]
+    fn zero() -> f16 {
+        f16::ZERO
+    }
+
+    #[inline]
+    fn is_zero(&self) -> bool {
+        self.0 & 0x7FFF == 0
+    }
+}
+
+impl One for f16 {
+    #[inline]
+    fn one() -> f16 {
+        f16::ONE
+    }
+}
+
+impl Num for f16 {
+    type FromStrRadixErr = <f32 as Num>::FromStrRadixErr;
+
+    #[inline]
+    fn from_str_radix(s: &str, radix: u32) -> Result<f16, Self::FromStrRadixErr> {
+        <f32 as Num>::from_str_radix(s, radix).map(f16::from_f32)
+    }
+}
+
+impl ToPrimitive for f16 {
+    #[inline]
+    fn to_i64(&self) -> Option<i64> {
+        f16::to_f32(*self).to_i64()
+    }
+
+    #[inline]
+    fn to_u64(&self) -> Option<u64> {
+        f16::to_f32(*self).to_u64()
+    }
+
+    #[inline]
+    fn to_f32(&self) -> Option<f32> {
+        Some(f16::to_f32(*self))
+    }
+
+    #[inline]
+    fn to_f64(&self) -> Option<f64> {
+        Some(f16::to_f64(*self))
+    }
+}
+
+impl NumCast for f16 {
+    #[inline]
+    fn from<T: ToPrimitive>(n: T) -> Option<f16> {
+        n.to_f64().map(f16::from_f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::f16;
+
+    #[test]
+    fn test_conversions() {
+        assert_eq!(f16::from_f32(1.0).to_bits(), 0x3C00);
+        assert_eq!(f16::from_f32(-2.0).to_bits(), 0xC000);
+        assert_eq!(f16::from_f32(0.5).to_bits(), 0x3800);
+        assert_eq!(f16::from_f32(65504.0).to_bits(), 0x7BFF);
+        assert_eq!(f16::from_f32(65520.0).to_bits(), 0x7C00);
+        assert_eq!(f16::from_f32(1e-8).to_bits(), 0x0000);
+        assert_eq!(f16::from_f32(-0.0).to_bits(), 0x8000);
+        assert_eq!(f16::from_f64(2f64.powi(-24)).to_bits(), 0x0001);
+        assert_eq!(f16::from_f64(2f64.powi(-14)).to_bits(), 0x0400);
+        assert!(f16::from_f32(f32::NAN).is_nan());
+        assert_eq!(f16::from_f32(f32::NEG_INFINITY).to_bits(), 0xFC00);
+        // Ties round to even
+        assert_eq!(f16::from_f64(1.0 + 2f64.powi(-11)).to_bits(), 0x3C00);
+        assert_eq!(f16::from_f64(1.0 + 3.0 * 2f64.powi(-11)).to_bits(), 0x3C02);
+        assert_eq!(f16::from_f64(2f64.powi(-25)).to_bits(), 0x0000);
+        assert_eq!(f16::from_f64(3.0 * 2f64.powi(-25)).to_bits(), 0x0002);
+        // The largest subnormal rounds up to the smallest normal
+        assert_eq!(f16::from_f64(2f64.powi(-14) - 2f64.powi(-26)).to_bits(), 0x0400);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for bits in 0..=0xFFFF_u16 {
+            let value = f16::from_bits(bits);
+            if value.is_nan() {
+                assert!(f16::from_f32(value.to_f32()).is_nan());
+            } else {
+                assert_eq!(f16::from_f32(value.to_f32()).to_bits(), bits);
+                assert_eq!(f16::from_f64(value.to_f64()).to_bits(), bits);
+            }
+        }
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let (a, b) = (f16::from_f32(0.25), f16::from_f32(0.5));
+        assert_eq!(a + b, f16::from_f32(0.75));
+        assert_eq!(b - a, a);
+        assert_eq!(a * b, f16::from_f32(0.125));
+        assert_eq!(a / b, b);
+        assert_eq!(-a, f16::from_f32(-0.25));
+        assert!(a < b);
+        assert_eq!(f16::from_f32(0.0), f16::from_f32(-0.0));
+        assert!(f16::NAN != f16::NAN);
+    }
+}
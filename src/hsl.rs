@@ -0,0 +1,259 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! http://en.wikipedia.org/wiki/HSL_and_HSV
+//!
+//! As in CSS, `Hsl` is a cylindrical form of gamma encoded sRGB. Conversions
+//! from and to linear `Rgb` go through `Srgb`.
+
+use num;
+use angle::*;
+
+use {Color, FloatColor};
+use {Channel, FloatChannel};
+use {Rgb, ToRgb};
+use {HueInterpolation, MixHue};
+use {Hsv, ToHsv};
+use {Srgb, ToSrgb};
+use AlphaColor;
+
+#[inline]
+fn cast<T: num::NumCast, U: num::NumCast>(n: T) -> U {
+    num::traits::cast(n).unwrap()
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Hsl<T: Channel> { pub h: Deg<T>, pub s: T, pub l: T }
+
+impl<T: Channel> Hsl<T> {
+    pub fn new(h: Deg<T>, s: T, l: T) -> Hsl<T> {
+        Hsl { h, s, l }
+    }
+}
+
+impl<T: Channel> Color<T> for Hsl<T> {
+    /// Clamps the components of the color to the range `(lo,hi)`.
+    #[inline]
+    fn clamp_s(self, lo: T, hi: T) -> Hsl<T> {
+        Hsl::new(self.h, // Should the hue component be clamped?
+                 self.s.clamp(lo, hi),
+                 self.l.clamp(lo, hi))
+    }
+
+    /// Clamps the components of the color component-wise between `lo` and `hi`.
+    #[inline]
+    fn clamp_c(self, lo: Hsl<T>, hi: Hsl<T>) -> Hsl<T> {
+        Hsl::new(self.h,
+                 self.s.clamp(lo.s, hi.s),
+                 self.l.clamp(lo.l, hi.l))
+    }
+
+    /// Inverts the color.
+    #[inline]
+    fn inverse(self) -> Hsl<T> {
+        Hsl::new((self.h + Deg(cast(180))).wrap(),
+                 self.s,
+                 self.l.invert_channel())
+    }
+
+    #[inline]
+    fn mix(self, other: Self, value: T) -> Self {
+        self.mix_hue(other, value, HueInterpolation::Shorter)
+    }
+}
+
+impl<T: FloatChannel> FloatColor<T> for Hsl<T> {
+    /// Normalizes the components of the color. Modulo `360` is applied to the
+    /// `h` component, and `s` and `l` are clamped to the range `(0,1)`.
+    #[inline]
+    fn saturate(self) -> Hsl<T> {
+        Hsl::new(self.h.wrap(),
+                 self.s.saturate(),
+                 self.l.saturate())
+    }
+}
+
+pub trait ToHsl {
+    fn to_hsl<U:Channel>(&self) -> Hsl<U>;
+}
+
+impl<T:Channel> ToHsl for Hsl<T> {
+    #[inline]
+    fn to_hsl<U:Channel>(&self) -> Hsl<U> {
+        Hsl::new(Deg(cast(self.h.value())),
+                 self.s.to_channel(),
+                 self.l.to_channel())
+    }
+}
+
+impl<T:Channel> ToHsl for Srgb<T> {
+    fn to_hsl<U:Channel>(&self) -> Hsl<U> {
+        // Algorithm taken from CSS Color Module Level 4:
+        // https://www.w3.org/TR/css-color-4/#rgb-to-hsl
+        let rgb = self.to_srgb::<f64>();
+        let mx = rgb.r.max(rgb.g).max(rgb.b);
+        let mn = rgb.r.min(rgb.g).min(rgb.b);
+        let chr = mx - mn;
+        let l = (mx + mn) / 2.0;
+
+        if chr > 0.0 && l > 0.0 && l < 1.0 {
+            let h = if      rgb.r == mx       { (rgb.g - rgb.b) / chr + if rgb.g < rgb.b { 6.0 } else { 0.0 } }
+                    else if rgb.g == mx       { (rgb.b - rgb.r) / chr + 2.0 }
+                    else    /* rgb.b == mx */ { (rgb.r - rgb.g) / chr + 4.0 }
+                    * 60.0;
+            let s = (mx - l) / l.min(1.0 - l);
+
+            Hsl::new(Deg(cast(h)), Channel::from(s), Channel::from(l))
+        } else {
+            Hsl::new(Deg(cast(0)), Channel::from(0.0f64), Channel::from(l))
+        }
+    }
+}
+
+impl<T:Channel> ToHsl for Rgb<T> {
+    #[inline]
+    fn to_hsl<U:Channel>(&self) -> Hsl<U> {
+        self.to_srgb::<f64>().to_hsl()
+    }
+}
+
+impl<T:Channel> ToHsl for Hsv<T> {
+    fn to_hsl<U:Channel>(&self) -> Hsl<U> {
+        let hsl = self.to_srgb::<f64>().to_hsl::<f64>();
+        // Neutral colors lose their hue on the way through sRGB
+        let h = if hsl.s > 0.0 { hsl.h.value() } else { cast(self.h.value()) };
+        Hsl::new(Deg(cast(h)), Channel::from(hsl.s), Channel::from(hsl.l))
+    }
+}
+
+impl<T, C: ToHsl> ToHsl for AlphaColor<T, C> {
+    #[inline]
+    fn to_hsl<U:Channel>(&self) -> Hsl<U> {
+        self.c.to_hsl()
+    }
+}
+
+impl<T:Channel> ToHsv for Hsl<T> {
+    fn to_hsv<U:Channel>(&self) -> Hsv<U> {
+        let hsv = self.to_rgb::<f64>().to_hsv::<f64>();
+        // Neutral colors lose their hue on the way through linear RGB
+        let h = if hsv.s > 0.0 { hsv.h.value() } else { cast(self.h.value()) };
+        Hsv::new(Deg(cast(h)), Channel::from(hsv.s), Channel::from(hsv.v))
+    }
+}
+
+impl<T:Channel> ToSrgb for Hsl<T> {
+    fn to_srgb<U:Channel>(&self) -> Srgb<U> {
+        // Algorithm taken from CSS Color Module Level 4:
+        // https://www.w3.org/TR/css-color-4/#hsl-to-rgb
+        let h: f64 = cast::<T, f64>(self.h.value()) % 360.0;
+        let h = if h < 0.0 { h + 360.0 } else { h };
+        let s: f64 = self.s.to_channel_f64();
+        let l: f64 = self.l.to_channel_f64();
+        let a = s * l.min(1.0 - l);
+        let f = |n: f64| {
+            let k = (n + h / 30.0) % 12.0;
+            l - a * (k - 3.0).min(9.0 - k).clamp(-1.0, 1.0)
+        };
+        Srgb::new(Channel::from(f(0.0)),
+                  Channel::from(f(8.0)),
+                  Channel::from(f(4.0)))
+    }
+}
+
+impl<T:Channel> ToRgb for Hsl<T> {
+    #[inline]
+    fn to_rgb<U:Channel>(&self) -> Rgb<U> {
+        self.to_srgb::<f64>().to_rgb()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {Hsl, ToHsl};
+    use {Hsv, ToHsv};
+    use {Rgb, ToRgb};
+    use {Srgb, ToSrgb};
+    use {Hsla, ToRgba};
+    use {Color, FloatColor};
+    use angle::*;
+
+    fn assert_srgb_eq(hsl: Hsl<f64>, hex: u32) {
+        let expected = Srgb::new((hex >> 16) as u8, (hex >> 8) as u8, hex as u8);
+        assert_eq!(hsl.to_srgb::<u8>(), expected, "{:?}", hsl);
+        let css = format!("hsl({} {}% {}%)", hsl.h.value(), hsl.s * 100.0, hsl.l * 100.0);
+        assert_eq!(css.parse::<Srgb<u8>>(), Ok(expected), "{}", css);
+    }
+
+    #[test]
+    fn test_hsl_to_srgb() {
+        // Reference values from the CSS Color Module Level 4 HSL tables
+        assert_srgb_eq(Hsl::new(Deg(0.0), 1.0, 0.5), 0xFF0000);
+        assert_srgb_eq(Hsl::new(Deg(30.0), 1.0, 0.5), 0xFF8000);
+        assert_srgb_eq(Hsl::new(Deg(60.0), 1.0, 0.25), 0x808000);
+        assert_srgb_eq(Hsl::new(Deg(120.0), 1.0, 0.25), 0x008000);
+        assert_srgb_eq(Hsl::new(Deg(180.0), 0.6, 0.5), 0x33CCCC);
+        assert_srgb_eq(Hsl::new(Deg(210.0), 1.0, 0.75), 0x80BFFF);
+        assert_srgb_eq(Hsl::new(Deg(270.0), 0.8, 0.3), 0x4D0F8A);
+        assert_srgb_eq(Hsl::new(Deg(300.0), 0.5, 0.5), 0xBF40BF);
+        assert_srgb_eq(Hsl::new(Deg(330.0), 0.2, 0.9), 0xEBE0E6);
+        assert_srgb_eq(Hsl::new(Deg(0.0), 0.0, 0.5), 0x808080);
+        assert_srgb_eq(Hsl::new(Deg(-90.0), 1.0, 0.5), 0x8000FF);
+        assert_srgb_eq(Hsl::new(Deg(390.0), 1.0, 0.5), 0xFF8000);
+    }
+
+    #[test]
+    fn test_srgb_to_hsl() {
+        assert_eq!(Srgb::<u8>::new(0xFF, 0xFF, 0xFF).to_hsl::<f32>(), Hsl::<f32>::new(Deg(0.0), 0.0, 1.0));
+        assert_eq!(Srgb::<u8>::new(0x00, 0x00, 0x00).to_hsl::<f32>(), Hsl::<f32>::new(Deg(0.0), 0.0, 0.0));
+        assert_eq!(Srgb::<f32>::new(1.0, 0.0, 0.0).to_hsl::<f32>(), Hsl::<f32>::new(Deg(0.0), 1.0, 0.5));
+        assert_eq!(Srgb::<f32>::new(0.0, 0.5, 0.0).to_hsl::<f32>(), Hsl::<f32>::new(Deg(120.0), 1.0, 0.25));
+        assert_eq!(Srgb::<f32>::new(0.5, 0.0, 1.0).to_hsl::<f32>(), Hsl::<f32>::new(Deg(270.0), 1.0, 0.5));
+        assert_eq!(Srgb::<f32>::new(1.0, 0.0, 0.5).to_hsl::<f32>(), Hsl::<f32>::new(Deg(330.0), 1.0, 0.5));
+    }
+
+    #[test]
+    fn test_rgb_to_hsl() {
+        // Linear colors are encoded first, so linear mid gray is lighter than 50%
+        let gray = Rgb::<f64>::new(0.5, 0.5, 0.5).to_hsl::<f64>();
+        assert!((gray.l - 0.7354).abs() < 1e-4, "{:?}", gray);
+        let hsl = Hsl::<f64>::new(Deg(210.0), 0.8, 0.3);
+        let back = hsl.to_rgb::<f64>().to_hsl::<f64>();
+        assert!((back.h.value() - 210.0).abs() < 1e-9 && (back.s - 0.8).abs() < 1e-9 && (back.l - 0.3).abs() < 1e-9, "{:?}", back);
+        assert_eq!(Hsl::<f64>::new(Deg(0.0), 0.0, 0.5).to_rgb::<f64>(), Srgb::new(0.5, 0.5, 0.5).to_rgb());
+    }
+
+    #[test]
+    fn test_hsl_hsv() {
+        // Hsv is defined over linear RGB, so the conversions go through it
+        let hsl = Hsl::<f64>::new(Deg(120.0), 1.0, 0.25);
+        assert_eq!(hsl.to_hsv::<f64>(), hsl.to_rgb::<f64>().to_hsv());
+        let hsv = hsl.to_hsv::<f64>().to_hsl::<f64>();
+        assert!((hsv.h.value() - 120.0).abs() < 1e-9 && (hsv.s - 1.0).abs() < 1e-9 && (hsv.l - 0.25).abs() < 1e-9, "{:?}", hsv);
+        assert_eq!(Hsl::<f32>::new(Deg(0.0), 0.0, 1.0).to_hsv::<f32>(), Hsv::<f32>::new(Deg(0.0), 0.0, 1.0));
+        assert_eq!(Hsv::<f32>::new(Deg(60.0), 0.0, 0.0).to_hsl::<f32>(), Hsl::<f32>::new(Deg(60.0), 0.0, 0.0));
+        assert_eq!(Hsl::<f32>::new(Deg(60.0), 0.0, 0.5).to_hsv::<f32>().h, Deg(60.0));
+    }
+
+    #[test]
+    fn test_hsl_color() {
+        let hsl = Hsl::<f32>::new(Deg(400.0), 1.5, -0.5).saturate();
+        assert_eq!(hsl, Hsl::new(Deg(40.0), 1.0, 0.0));
+        assert_eq!(Hsl::<f32>::new(Deg(0.0), 1.0, 0.25).inverse(), Hsl::new(Deg(180.0), 1.0, 0.75));
+
+        let hsla = Hsla { c: Hsl::<f32>::new(Deg(0.0), 1.0, 0.5), a: 0.5 };
+        assert_eq!(hsla.to_rgba::<f32>(), rgba!(1.0f32, 0.0, 0.0, 0.5));
+    }
+}
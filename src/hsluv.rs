@@ -0,0 +1,445 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! HSLuv and HPLuv, human friendly reparameterizations of LCh(uv):
+//! http://www.hsluv.org/
+//!
+//! Both keep the lightness of LCh(uv), so colors with the same `l` look
+//! equally light regardless of hue. HSLuv stretches the chroma so that
+//! every `s` from `0` to `100` is inside the sRGB gamut, while HPLuv only
+//! uses the chroma that is available for every hue at that lightness. All
+//! components except the hue range from `0` to `100`.
+
+use num;
+use num::traits;
+use angle::*;
+
+use {Color, FloatColor};
+use {Channel, FloatChannel};
+use {Rgb, ToRgb};
+use {Srgb, ToSrgb};
+use {Xyz, ToXyz};
+use {Lchuv, ToLchuv, Luv, ToLuv};
+use AlphaColor;
+use {HueInterpolation, MixHue};
+use lab::{EPSILON, KAPPA};
+use xyz::XYZ_TO_SRGB;
+
+fn cast<T: num::NumCast, U: num::NumCast>(n: T) -> U {
+    traits::cast(n).unwrap()
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Hsluv<T: Channel> { pub h: Deg<T>, pub s: T, pub l: T }
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Hpluv<T: Channel> { pub h: Deg<T>, pub p: T, pub l: T }
+
+impl<T: Channel> Hsluv<T> {
+    #[inline]
+    pub fn new(h: Deg<T>, s: T, l: T) -> Hsluv<T> {
+        Hsluv { h, s, l }
+    }
+}
+
+impl<T: Channel> Hpluv<T> {
+    #[inline]
+    pub fn new(h: Deg<T>, p: T, l: T) -> Hpluv<T> {
+        Hpluv { h, p, l }
+    }
+}
+
+/// Returns the `(slope, intercept)` of the six lines in the u\*v\* plane that
+/// bound the sRGB gamut at lightness `l`.
+fn gamut_bounds(l: f64) -> [(f64, f64); 6] {
+    let m = XYZ_TO_SRGB;
+    let sub1 = (l + 16.0).powi(3) / 1560896.0;
+    let sub2 = if sub1 > EPSILON { sub1 } else { l / KAPPA };
+    let mut bounds = [(0.0, 0.0); 6];
+    for (channel, row) in m.iter().enumerate() {
+        let (m1, m2, m3) = (row[0], row[1], row[2]);
+        for t in 0..2 {
+            let t = t as f64;
+            let top1 = (284517.0 * m1 - 94839.0 * m3) * sub2;
+            let top2 = (838422.0 * m3 + 769860.0 * m2 + 731718.0 * m1) * l * sub2 - 769860.0 * t * l;
+            let bottom = (632260.0 * m3 - 126452.0 * m2) * sub2 + 126452.0 * t;
+            bounds[channel * 2 + t as usize] = (top1 / bottom, top2 / bottom);
+        }
+    }
+    bounds
+}
+
+/// Returns the largest LCh(uv) chroma inside the sRGB gamut for the given
+/// lightness and hue.
+pub fn max_chroma_for_lh(l: f64, h: Deg<f64>) -> f64 {
+    let (sin, cos) = h.sin_cos();
+    gamut_bounds(l).iter()
+        .map(|&(slope, intercept)| intercept / (sin - slope * cos))
+        .filter(|&length| length >= 0.0)
+        .fold(f64::INFINITY, f64::min)
+}
+
+/// Returns the largest LCh(uv) chroma inside the sRGB gamut for every hue at
+/// the given lightness.
+pub fn max_safe_chroma_for_l(l: f64) -> f64 {
+    gamut_bounds(l).iter()
+        .map(|&(slope, intercept)| intercept.abs() / (slope * slope + 1.0).sqrt())
+        .fold(f64::INFINITY, f64::min)
+}
+
+impl<T:FloatChannel> Color<T> for Hsluv<T> {
+    /// Clamps the components of the color to the range `(lo,hi)`.
+    #[inline]
+    fn clamp_s(self, lo: T, hi: T) -> Hsluv<T> {
+        Hsluv::new(self.h,
+                   Channel::clamp(self.s, lo, hi),
+                   Channel::clamp(self.l, lo, hi))
+    }
+
+    /// Clamps the components of the color component-wise between `lo` and `hi`.
+    #[inline]
+    fn clamp_c(self, lo: Hsluv<T>, hi: Hsluv<T>) -> Hsluv<T> {
+        Hsluv::new(self.h,
+                   Channel::clamp(self.s, lo.s, hi.s),
+                   Channel::clamp(self.l, lo.l, hi.l))
+    }
+
+    /// Inverts the lightness and rotates the hue by 180 degrees.
+    #[inline]
+    fn inverse(self) -> Hsluv<T> {
+        Hsluv::new((self.h + Deg(cast(180))).wrap(),
+                   self.s,
+                   cast::<f64, T>(100.0) - self.l)
+    }
+
+    /// Mixes the colors component-wise, with the hue interpolated along the
+    /// shorter arc, as CSS `color-mix()` does in polar spaces.
+    #[inline]
+    fn mix(self, other: Self, value: T) -> Self {
+        self.mix_hue(other, value, HueInterpolation::Shorter)
+    }
+}
+
+impl<T:FloatChannel> FloatColor<T> for Hsluv<T> {
+    /// Normalizes the components of the color. Modulo `360` is applied to the
+    /// `h` component, and `s` and `l` are clamped to the range `(0,100)`.
+    #[inline]
+    fn saturate(self) -> Hsluv<T> {
+        Hsluv::new(self.h.wrap(),
+                   Channel::clamp(self.s, cast(0.0), cast(100.0)),
+                   Channel::clamp(self.l, cast(0.0), cast(100.0)))
+    }
+}
+
+impl<T:FloatChannel> Color<T> for Hpluv<T> {
+    /// Clamps the components of the color to the range `(lo,hi)`.
+    #[inline]
+    fn clamp_s(self, lo: T, hi: T) -> Hpluv<T> {
+        Hpluv::new(self.h,
+                   Channel::clamp(self.p, lo, hi),
+                   Channel::clamp(self.l, lo, hi))
+    }
+
+    /// Clamps the components of the color component-wise between `lo` and `hi`.
+    #[inline]
+    fn clamp_c(self, lo: Hpluv<T>, hi: Hpluv<T>) -> Hpluv<T> {
+        Hpluv::new(self.h,
+                   Channel::clamp(self.p, lo.p, hi.p),
+                   Channel::clamp(self.l, lo.l, hi.l))
+    }
+
+    /// Inverts the lightness and rotates the hue by 180 degrees.
+    #[inline]
+    fn inverse(self) -> Hpluv<T> {
+        Hpluv::new((self.h + Deg(cast(180))).wrap(),
+                   self.p,
+                   cast::<f64, T>(100.0) - self.l)
+    }
+
+    /// Mixes the colors component-wise, with the hue interpolated along the
+    /// shorter arc, as CSS `color-mix()` does in polar spaces.
+    #[inline]
+    fn mix(self, other: Self, value: T) -> Self {
+        self.mix_hue(other, value, HueInterpolation::Shorter)
+    }
+}
+
+impl<T:FloatChannel> FloatColor<T> for Hpluv<T> {
+    /// Normalizes the components of the color. Modulo `360` is applied to the
+    /// `h` component, and `p` and `l` are clamped to the range `(0,100)`.
+    #[inline]
+    fn saturate(self) -> Hpluv<T> {
+        Hpluv::new(self.h.wrap(),
+                   Channel::clamp(self.p, cast(0.0), cast(100.0)),
+                   Channel::clamp(self.l, cast(0.0), cast(100.0)))
+    }
+}
+
+pub trait ToHsluv {
+    fn to_hsluv<U:FloatChannel>(&self) -> Hsluv<U>;
+}
+
+impl<T:FloatChannel> ToHsluv for Hsluv<T> {
+    #[inline]
+    fn to_hsluv<U:FloatChannel>(&self) -> Hsluv<U> {
+        Hsluv::new(Deg(cast(self.h.value())), cast(self.s), cast(self.l))
+    }
+}
+
+impl<T:FloatChannel> ToHsluv for Lchuv<T> {
+    fn to_hsluv<U:FloatChannel>(&self) -> Hsluv<U> {
+        let l: f64 = cast(self.l);
+        let h: f64 = cast(self.h.value());
+        let s = if !(1e-8..=99.9999999).contains(&l) {
+            0.0
+        } else {
+            cast::<T, f64>(self.c) / max_chroma_for_lh(l, Deg(h)) * 100.0
+        };
+        Hsluv::new(Deg(cast(h)), cast(s), cast(l))
+    }
+}
+
+impl<T:FloatChannel> ToHsluv for Luv<T> {
+    #[inline]
+    fn to_hsluv<U:FloatChannel>(&self) -> Hsluv<U> {
+        self.to_lchuv::<f64>().to_hsluv()
+    }
+}
+
+impl<T:FloatChannel> ToHsluv for Xyz<T> {
+    #[inline]
+    fn to_hsluv<U:FloatChannel>(&self) -> Hsluv<U> {
+        self.to_lchuv::<f64>().to_hsluv()
+    }
+}
+
+impl<T:Channel> ToHsluv for Rgb<T> {
+    #[inline]
+    fn to_hsluv<U:FloatChannel>(&self) -> Hsluv<U> {
+        self.to_lchuv::<f64>().to_hsluv()
+    }
+}
+
+impl<T:Channel> ToHsluv for Srgb<T> {
+    #[inline]
+    fn to_hsluv<U:FloatChannel>(&self) -> Hsluv<U> {
+        self.to_lchuv::<f64>().to_hsluv()
+    }
+}
+
+impl<T, C: ToHsluv> ToHsluv for AlphaColor<T, C> {
+    #[inline]
+    fn to_hsluv<U:FloatChannel>(&self) -> Hsluv<U> {
+        self.c.to_hsluv()
+    }
+}
+
+pub trait ToHpluv {
+    fn to_hpluv<U:FloatChannel>(&self) -> Hpluv<U>;
+}
+
+impl<T:FloatChannel> ToHpluv for Hpluv<T> {
+    #[inline]
+    fn to_hpluv<U:FloatChannel>(&self) -> Hpluv<U> {
+        Hpluv::new(Deg(cast(self.h.value())), cast(self.p), cast(self.l))
+    }
+}
+
+impl<T:FloatChannel> ToHpluv for Lchuv<T> {
+    fn to_hpluv<U:FloatChannel>(&self) -> Hpluv<U> {
+        let l: f64 = cast(self.l);
+        let p = if !(1e-8..=99.9999999).contains(&l) {
+            0.0
+        } else {
+            cast::<T, f64>(self.c) / max_safe_chroma_for_l(l) * 100.0
+        };
+        Hpluv::new(Deg(cast(self.h.value())), cast(p), cast(l))
+    }
+}
+
+impl<T:FloatChannel> ToHpluv for Luv<T> {
+    #[inline]
+    fn to_hpluv<U:FloatChannel>(&self) -> Hpluv<U> {
+        self.to_lchuv::<f64>().to_hpluv()
+    }
+}
+
+impl<T:FloatChannel> ToHpluv for Xyz<T> {
+    #[inline]
+    fn to_hpluv<U:FloatChannel>(&self) -> Hpluv<U> {
+        self.to_lchuv::<f64>().to_hpluv()
+    }
+}
+
+impl<T:Channel> ToHpluv for Rgb<T> {
+    #[inline]
+    fn to_hpluv<U:FloatChannel>(&self) -> Hpluv<U> {
+        self.to_lchuv::<f64>().to_hpluv()
+    }
+}
+
+impl<T:Channel> ToHpluv for Srgb<T> {
+    #[inline]
+    fn to_hpluv<U:FloatChannel>(&self) -> Hpluv<U> {
+        self.to_lchuv::<f64>().to_hpluv()
+    }
+}
+
+impl<T, C: ToHpluv> ToHpluv for AlphaColor<T, C> {
+    #[inline]
+    fn to_hpluv<U:FloatChannel>(&self) -> Hpluv<U> {
+        self.c.to_hpluv()
+    }
+}
+
+impl<T:FloatChannel> ToLchuv for Hsluv<T> {
+    fn to_lchuv<U:FloatChannel>(&self) -> Lchuv<U> {
+        let l: f64 = cast(self.l);
+        let h: f64 = cast(self.h.value());
+        let c = if !(1e-8..=99.9999999).contains(&l) {
+            0.0
+        } else {
+            max_chroma_for_lh(l, Deg(h)) / 100.0 * cast::<T, f64>(self.s)
+        };
+        Lchuv::new(cast(l), cast(c), Deg(cast(h)))
+    }
+}
+
+impl<T:FloatChannel> ToLchuv for Hpluv<T> {
+    fn to_lchuv<U:FloatChannel>(&self) -> Lchuv<U> {
+        let l: f64 = cast(self.l);
+        let c = if !(1e-8..=99.9999999).contains(&l) {
+            0.0
+        } else {
+            max_safe_chroma_for_l(l) / 100.0 * cast::<T, f64>(self.p)
+        };
+        Lchuv::new(cast(l), cast(c), Deg(cast(self.h.value())))
+    }
+}
+
+impl<T:FloatChannel> ToLuv for Hsluv<T> {
+    #[inline]
+    fn to_luv<U:FloatChannel>(&self) -> Luv<U> {
+        self.to_lchuv::<f64>().to_luv()
+    }
+}
+
+impl<T:FloatChannel> ToLuv for Hpluv<T> {
+    #[inline]
+    fn to_luv<U:FloatChannel>(&self) -> Luv<U> {
+        self.to_lchuv::<f64>().to_luv()
+    }
+}
+
+impl<T:FloatChannel> ToXyz for Hsluv<T> {
+    #[inline]
+    fn to_xyz<U:FloatChannel>(&self) -> Xyz<U> {
+        self.to_luv::<f64>().to_xyz()
+    }
+}
+
+impl<T:FloatChannel> ToXyz for Hpluv<T> {
+    #[inline]
+    fn to_xyz<U:FloatChannel>(&self) -> Xyz<U> {
+        self.to_luv::<f64>().to_xyz()
+    }
+}
+
+impl<T:FloatChannel> ToRgb for Hsluv<T> {
+    #[inline]
+    fn to_rgb<U:Channel>(&self) -> Rgb<U> {
+        self.to_xyz::<f64>().to_rgb()
+    }
+}
+
+impl<T:FloatChannel> ToRgb for Hpluv<T> {
+    #[inline]
+    fn to_rgb<U:Channel>(&self) -> Rgb<U> {
+        self.to_xyz::<f64>().to_rgb()
+    }
+}
+
+impl<T:FloatChannel> ToSrgb for Hsluv<T> {
+    #[inline]
+    fn to_srgb<U:Channel>(&self) -> Srgb<U> {
+        self.to_rgb::<f64>().to_srgb()
+    }
+}
+
+impl<T:FloatChannel> ToSrgb for Hpluv<T> {
+    #[inline]
+    fn to_srgb<U:Channel>(&self) -> Srgb<U> {
+        self.to_rgb::<f64>().to_srgb()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {Hsluv, ToHsluv, Hpluv, ToHpluv};
+    use {Srgb, ToSrgb, Lchuv, ToLchuv};
+    use super::{max_chroma_for_lh, max_safe_chroma_for_l};
+    use angle::*;
+
+    fn assert_hsluv_eq(a: Hsluv<f64>, b: Hsluv<f64>) {
+        assert!((a.h.value() - b.h.value()).abs() < 1e-2, "{:?} != {:?}", a, b);
+        assert!((a.s - b.s).abs() < 1e-2, "{:?} != {:?}", a, b);
+        assert!((a.l - b.l).abs() < 1e-2, "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn test_srgb_to_hsluv() {
+        // Reference values from the HSLuv test snapshot
+        assert_hsluv_eq(Srgb::<u8>::new(0xFF, 0x00, 0x00).to_hsluv(), Hsluv::new(Deg(12.1771), 100.0, 53.2371));
+        assert_hsluv_eq(Srgb::<u8>::new(0x00, 0xFF, 0x00).to_hsluv(), Hsluv::new(Deg(127.7150), 100.0, 87.7355));
+        assert_hsluv_eq(Srgb::<u8>::new(0x00, 0x00, 0xFF).to_hsluv(), Hsluv::new(Deg(265.8743), 100.0, 32.3009));
+        assert_hsluv_eq(Srgb::<u8>::new(0xFF, 0xFF, 0xFF).to_hsluv(), Hsluv::new(Deg(0.0), 0.0, 100.0));
+        assert_hsluv_eq(Srgb::<u8>::new(0x00, 0x00, 0x00).to_hsluv(), Hsluv::new(Deg(0.0), 0.0, 0.0));
+    }
+
+    #[test]
+    fn test_hsluv_to_srgb() {
+        let srgb = Hsluv::<f64>::new(Deg(12.1771), 100.0, 53.2371).to_srgb::<f64>();
+        assert!((srgb.r - 1.0).abs() < 1e-3 && srgb.g.abs() < 1e-3 && srgb.b.abs() < 1e-3);
+        // Every saturation at every hue stays inside the gamut
+        for h in 0..36 {
+            for l in 1..10 {
+                let srgb = Hsluv::<f64>::new(Deg(h as f64 * 10.0), 100.0, l as f64 * 10.0).to_srgb::<f64>();
+                for &c in [srgb.r, srgb.g, srgb.b].iter() {
+                    assert!(c > -1e-6 && c < 1.0 + 1e-6, "{:?}", srgb);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_hpluv() {
+        let hpluv = Srgb::<u8>::new(0xFF, 0x00, 0x00).to_hpluv::<f64>();
+        assert!((hpluv.p - 426.7467).abs() < 1e-1);
+        assert!((hpluv.l - 53.2371).abs() < 1e-2);
+        let srgb = Hpluv::<f64>::new(Deg(200.0), 100.0, 50.0).to_srgb::<f64>();
+        for &c in [srgb.r, srgb.g, srgb.b].iter() {
+            assert!(c > -1e-6 && c < 1.0 + 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_max_chroma() {
+        let red = Srgb::<u8>::new(0xFF, 0x00, 0x00).to_lchuv::<f64>();
+        assert!((max_chroma_for_lh(red.l, red.h) - red.c).abs() < 1e-2);
+        assert!(max_safe_chroma_for_l(50.0) <= max_chroma_for_lh(50.0, Deg(0.0)));
+        let lchuv = Lchuv::<f64>::new(50.0, max_safe_chroma_for_l(50.0), Deg(123.0));
+        assert!((lchuv.to_hpluv::<f64>().p - 100.0).abs() < 1e-9);
+    }
+}
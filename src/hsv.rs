@@ -14,12 +14,12 @@
 // limitations under the License.
 
 use num::{self, zero};
-use num::traits::{Float, Zero};
 use angle::*;
 
 use {Color, FloatColor};
 use {Channel, FloatChannel};
 use {Rgb, ToRgb};
+use {HueInterpolation, MixHue};
 
 #[inline]
 fn cast<T: num::NumCast, U: num::NumCast>(n: T) -> U {
@@ -31,7 +31,7 @@ pub struct Hsv<T: Channel> { pub h: Deg<T>, pub s: T, pub v: T }
 
 impl<T: Channel> Hsv<T> {
     pub fn new(h: Deg<T>, s: T, v: T) -> Hsv<T> {
-        Hsv { h: h, s: s, v: v }
+        Hsv { h, s, v }
     }
 }
 
@@ -62,7 +62,7 @@ impl<T: Channel> Color<T> for Hsv<T> {
     
     #[inline]
     fn mix(self, other: Self, value: T) -> Self {
-        self.to_rgb().mix(other.to_rgb(),value).to_hsv() // TODO: can we mix the hsv directly?
+        self.mix_hue(other, value, HueInterpolation::Shorter)
     }
 }
 
@@ -81,20 +81,6 @@ pub trait ToHsv {
     fn to_hsv<U:Channel>(&self) -> Hsv<U>;
 }
 
-impl ToHsv for u32 {
-    #[inline]
-    fn to_hsv<U:Channel>(&self) -> Hsv<U> {
-        panic!("Not yet implemented")
-    }
-}
-
-impl ToHsv for u64 {
-    #[inline]
-    fn to_hsv<U:Channel>(&self) -> Hsv<U> {
-        panic!("Not yet implemented")
-    }
-}
-
 impl<T:Channel> ToHsv for Hsv<T> {
     #[inline]
     fn to_hsv<U:Channel>(&self) -> Hsv<U> {
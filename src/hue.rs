@@ -0,0 +1,254 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Interpolation of colors with a hue component, with the hue interpolation
+//! methods of CSS Color Level 4:
+//! https://www.w3.org/TR/css-color-4/#hue-interpolation
+
+use num;
+use angle::*;
+
+use {Channel, FloatChannel};
+use {Hsv, Hsl, Lch, Oklch, Lchuv, Hsluv, Hpluv};
+use oklab::ACHROMATIC;
+
+#[inline]
+fn cast<T: num::NumCast, U: num::NumCast>(n: T) -> U {
+    num::traits::cast(n).unwrap()
+}
+
+/// Which way around the color wheel a hue is interpolated.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum HueInterpolation {
+    /// Takes the shorter arc between the hues. This is the CSS default.
+    Shorter,
+    /// Takes the longer arc between the hues.
+    Longer,
+    /// Goes through increasing hue angles.
+    Increasing,
+    /// Goes through decreasing hue angles.
+    Decreasing,
+    /// Interpolates the angles as given, without wrapping them first, so
+    /// that a difference of more than 360 degrees goes around more than once.
+    Specified,
+}
+
+impl HueInterpolation {
+    /// Interpolates between two hues in degrees, with `t` in `(0,1)`. The
+    /// result is wrapped to `[0,360)`, except for `Specified`.
+    pub fn interpolate(self, from: f64, to: f64, t: f64) -> f64 {
+        if self == HueInterpolation::Specified {
+            return from + (to - from) * t;
+        }
+        let (mut from, mut to) = (from.rem_euclid(360.0), to.rem_euclid(360.0));
+        let d = to - from;
+        match self {
+            HueInterpolation::Shorter => {
+                if d > 180.0 { from += 360.0 } else if d < -180.0 { to += 360.0 }
+            }
+            HueInterpolation::Longer => {
+                if d > 0.0 && d < 180.0 { from += 360.0 } else if d > -180.0 && d <= 0.0 { to += 360.0 }
+            }
+            HueInterpolation::Increasing => {
+                if d < 0.0 { to += 360.0 }
+            }
+            HueInterpolation::Decreasing => {
+                if d > 0.0 { from += 360.0 }
+            }
+            HueInterpolation::Specified => {}
+        }
+        (from + (to - from) * t).rem_euclid(360.0)
+    }
+
+    /// Interpolates between two hues of colors where the hue may be
+    /// powerless, like the hue of a gray. A powerless hue takes the value of
+    /// the other hue, so that it does not affect the result.
+    pub fn interpolate_powerless(self, from: f64, from_powerless: bool, to: f64, to_powerless: bool, t: f64) -> f64 {
+        match (from_powerless, to_powerless) {
+            (true, false) => if self == HueInterpolation::Specified { to } else { to.rem_euclid(360.0) },
+            (false, true) => if self == HueInterpolation::Specified { from } else { from.rem_euclid(360.0) },
+            _ => self.interpolate(from, to, t),
+        }
+    }
+}
+
+/// Returns `value` interpolated from `from` to `to` as `f64`.
+#[inline]
+fn lerp<T:Channel>(from: T, to: T, value: f64) -> f64 {
+    let (from, to): (f64, f64) = (cast(from), cast(to));
+    from + (to - from) * value
+}
+
+/// Converts a hue in degrees to the channel type. An integer channel holds
+/// whole degrees up to its maximum, so the hue is rounded, and as a `u8` hue
+/// stops at 255 degrees, a hue past that is clamped rather than overflowing.
+#[inline]
+pub(crate) fn from_degrees<T:Channel>(h: f64) -> Deg<T> {
+    let whole = cast::<T, f64>(cast(0.5)) == 0.0;
+    let h = if whole { h.round() } else { h };
+    match num::traits::cast(h) {
+        Some(h) => Deg(h),
+        None if h < 0.0 => Deg(cast(0)),
+        None => Deg(T::max()),
+    }
+}
+
+/// Mixing of colors directly in their polar color space. `Color::mix` for
+/// the polar types is `mix_hue` with `HueInterpolation::Shorter`, the
+/// default of CSS `color-mix()`.
+pub trait MixHue<T>: Sized {
+    /// Mixes the colors component-wise, with the hue interpolated with
+    /// `method`. A powerless hue, such as the hue of a gray, takes the hue of
+    /// the other color.
+    fn mix_hue(self, other: Self, value: T, method: HueInterpolation) -> Self;
+}
+
+impl<T:Channel> MixHue<T> for Hsv<T> {
+    fn mix_hue(self, other: Hsv<T>, value: T, method: HueInterpolation) -> Hsv<T> {
+        let t: f64 = value.to_channel_f64();
+        let powerless = |c: &Hsv<T>| c.s == cast(0) || c.v == cast(0);
+        let h = method.interpolate_powerless(cast(self.h.value()), powerless(&self),
+                                             cast(other.h.value()), powerless(&other), t);
+        Hsv::new(from_degrees(h), self.s.mix(other.s, value), self.v.mix(other.v, value))
+    }
+}
+
+impl<T:Channel> MixHue<T> for Hsl<T> {
+    fn mix_hue(self, other: Hsl<T>, value: T, method: HueInterpolation) -> Hsl<T> {
+        let t: f64 = value.to_channel_f64();
+        let powerless = |c: &Hsl<T>| c.s == cast(0) || c.l == cast(0) || c.l == T::max();
+        let h = method.interpolate_powerless(cast(self.h.value()), powerless(&self),
+                                             cast(other.h.value()), powerless(&other), t);
+        Hsl::new(from_degrees(h), self.s.mix(other.s, value), self.l.mix(other.l, value))
+    }
+}
+
+macro_rules! impl_mix_hue_lch {
+    ($ty: ident, $achromatic: expr) => {
+        impl<T:FloatChannel> MixHue<T> for $ty<T> {
+            fn mix_hue(self, other: $ty<T>, value: T, method: HueInterpolation) -> $ty<T> {
+                let t: f64 = cast(value);
+                let powerless = |c: &$ty<T>| cast::<T, f64>(c.c).abs() < $achromatic;
+                let h = method.interpolate_powerless(cast(self.h.value()), powerless(&self),
+                                                     cast(other.h.value()), powerless(&other), t);
+                $ty::new(cast(lerp(self.l, other.l, t)), cast(lerp(self.c, other.c, t)), Deg(cast(h)))
+            }
+        }
+    }
+}
+
+impl_mix_hue_lch!(Lch, 1e-4);
+impl_mix_hue_lch!(Lchuv, 1e-4);
+impl_mix_hue_lch!(Oklch, ACHROMATIC);
+
+impl<T:FloatChannel> MixHue<T> for Hsluv<T> {
+    fn mix_hue(self, other: Hsluv<T>, value: T, method: HueInterpolation) -> Hsluv<T> {
+        let t: f64 = cast(value);
+        let powerless = |c: &Hsluv<T>| cast::<T, f64>(c.s) < 1e-8;
+        let h = method.interpolate_powerless(cast(self.h.value()), powerless(&self),
+                                             cast(other.h.value()), powerless(&other), t);
+        Hsluv::new(Deg(cast(h)), cast(lerp(self.s, other.s, t)), cast(lerp(self.l, other.l, t)))
+    }
+}
+
+impl<T:FloatChannel> MixHue<T> for Hpluv<T> {
+    fn mix_hue(self, other: Hpluv<T>, value: T, method: HueInterpolation) -> Hpluv<T> {
+        let t: f64 = cast(value);
+        let powerless = |c: &Hpluv<T>| cast::<T, f64>(c.p) < 1e-8;
+        let h = method.interpolate_powerless(cast(self.h.value()), powerless(&self),
+                                             cast(other.h.value()), powerless(&other), t);
+        Hpluv::new(Deg(cast(h)), cast(lerp(self.p, other.p, t)), cast(lerp(self.l, other.l, t)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {Color, Hsv, Hsl, Oklch, Lch};
+    use super::{HueInterpolation, MixHue};
+    use super::HueInterpolation::*;
+    use angle::*;
+
+    fn assert_hue(method: HueInterpolation, from: f64, to: f64, expected: f64) {
+        let h = method.interpolate(from, to, 0.5);
+        assert!((h - expected).abs() < 1e-9, "{:?} {} {}: {} != {}", method, from, to, h, expected);
+    }
+
+    #[test]
+    fn test_methods() {
+        assert_hue(Shorter, 10.0, 350.0, 0.0);
+        assert_hue(Shorter, 350.0, 10.0, 0.0);
+        assert_hue(Shorter, 30.0, 90.0, 60.0);
+        assert_hue(Longer, 10.0, 350.0, 180.0);
+        assert_hue(Longer, 30.0, 90.0, 240.0);
+        assert_hue(Longer, 90.0, 30.0, 240.0);
+        assert_hue(Increasing, 350.0, 10.0, 0.0);
+        assert_hue(Increasing, 10.0, 350.0, 180.0);
+        assert_hue(Decreasing, 350.0, 10.0, 180.0);
+        assert_hue(Decreasing, 10.0, 350.0, 0.0);
+        assert_hue(Specified, 0.0, 720.0, 360.0);
+        assert_hue(Shorter, -30.0, 390.0, 0.0);
+        assert_eq!(Shorter.interpolate(10.0, 350.0, 0.25), 5.0);
+        assert_eq!(Increasing.interpolate(350.0, 10.0, 0.25), 355.0);
+    }
+
+    #[test]
+    fn test_powerless() {
+        // A gray keeps the hue of the other color instead of going through red
+        let gray = Hsv::new(Deg(0.0f64), 0.0, 0.5);
+        let blue = Hsv::new(Deg(240.0), 1.0, 1.0);
+        assert_eq!(gray.mix_hue(blue, 0.5, Shorter), Hsv::new(Deg(240.0), 0.5, 0.75));
+        assert_eq!(blue.mix_hue(gray, 0.5, Longer), Hsv::new(Deg(240.0), 0.5, 0.75));
+        let white = Oklch::new(1.0f64, 0.0, Deg(0.0));
+        let red = Oklch::new(0.6, 0.2, Deg(30.0));
+        let mid = white.mix_hue(red, 0.5, Shorter);
+        assert_eq!(mid.h, Deg(30.0));
+        assert!((mid.l - 0.8).abs() < 1e-12 && (mid.c - 0.1).abs() < 1e-12);
+        let black = Hsl::new(Deg(0.0f64), 0.0, 0.0);
+        assert_eq!(black.mix_hue(Hsl::new(Deg(120.0), 1.0, 0.5), 0.5, Shorter).h, Deg(120.0));
+    }
+
+    #[test]
+    fn test_mix() {
+        let red = Hsv::new(Deg(0.0f64), 1.0, 1.0);
+        let blue = Hsv::new(Deg(240.0), 1.0, 1.0);
+        assert_eq!(red.mix(blue, 0.5), Hsv::new(Deg(300.0), 1.0, 1.0));
+        assert_eq!(red.mix_hue(blue, 0.5, Longer), Hsv::new(Deg(120.0), 1.0, 1.0));
+        let a = Lch::new(50.0f64, 40.0, Deg(350.0));
+        let b = Lch::new(70.0, 60.0, Deg(20.0));
+        let mid = a.mix_hue(b, 0.5, Shorter);
+        assert!((mid.l - 60.0).abs() < 1e-12 && (mid.c - 50.0).abs() < 1e-12 && (mid.h.value() - 5.0).abs() < 1e-12);
+        let mid = a.mix_hue(b, 0.5, Decreasing);
+        assert!((mid.h.value() - 185.0).abs() < 1e-12);
+        let mid = a.mix(b, 0.5);
+        assert!((mid.c - 50.0).abs() < 1e-12 && (mid.h.value() - 5.0).abs() < 1e-12);
+        let mid = Oklch::new(0.5f64, 0.1, Deg(350.0)).mix(Oklch::new(0.7, 0.1, Deg(30.0)), 0.5);
+        assert!((mid.c - 0.1).abs() < 1e-12 && (mid.h.value() - 10.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_integer_hue() {
+        // A u8 hue only goes up to 255 degrees, so 310 is clamped. Deg<u8>
+        // cannot be compared directly, as its PartialEq wraps by 360 degrees.
+        let a = Hsv::new(Deg(250u8), 255, 255);
+        let b = Hsv::new(Deg(10u8), 255, 255);
+        let mid = a.mix(b, 128);
+        assert_eq!((mid.h.value(), mid.s, mid.v), (255, 255, 255));
+        let a = Hsv::new(Deg(200u8), 255, 255);
+        let b = Hsv::new(Deg(220u8), 255, 255);
+        assert_eq!(a.mix(b, 128).h.value(), 210);
+        let a = Hsl::new(Deg(250u8), 255, 128);
+        assert_eq!(a.mix_hue(Hsl::new(Deg(10u8), 255, 128), 128, Shorter).h.value(), 255);
+    }
+}
@@ -0,0 +1,411 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The CIE 1976 L\*a\*b\* color space and its cylindrical LCh(ab) form:
+//! http://en.wikipedia.org/wiki/Lab_color_space
+//!
+//! `L` ranges from `0` to `100`, while `a` and `b` are unbounded but stay
+//! within roughly `-128` to `128` for real surface colors. Colors are
+//! relative to a reference white, which is D65 unless stated otherwise. Use
+//! `Lab::adapt` to re-express a color relative to another white.
+
+use num;
+use num::traits;
+use angle::*;
+
+use {Color, FloatColor};
+use {Channel, FloatChannel};
+use {Rgb, ToRgb};
+use {Srgb, ToSrgb};
+use {Hsv, Hsl, AlphaColor};
+use {Xyz, ToXyz, Chromaticity};
+use {HueInterpolation, MixHue};
+
+fn cast<T: num::NumCast, U: num::NumCast>(n: T) -> U {
+    traits::cast(n).unwrap()
+}
+
+/// The CIE constant `(6/29)^3`: relative luminances up to it are on the
+/// linear segment of the lightness curve. Shared with L\*u\*v\*.
+pub(crate) const EPSILON: f64 = 216.0 / 24389.0;
+/// The CIE constant `(29/3)^3`, the slope of that linear segment.
+pub(crate) const KAPPA: f64 = 24389.0 / 27.0;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Lab<T> { pub l: T, pub a: T, pub b: T }
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Lch<T: Channel> { pub l: T, pub c: T, pub h: Deg<T> }
+
+impl<T> Lab<T> {
+    #[inline]
+    pub fn new(l: T, a: T, b: T) -> Lab<T> {
+        Lab { l, a, b }
+    }
+}
+
+impl<T: Channel> Lch<T> {
+    #[inline]
+    pub fn new(l: T, c: T, h: Deg<T>) -> Lch<T> {
+        Lch { l, c, h }
+    }
+}
+
+impl<T:FloatChannel> Lab<T> {
+    /// Converts a color to L\*a\*b\* relative to the given reference white.
+    /// No chromatic adaptation is done, so colors seen under another white
+    /// should go through `Xyz::adapt` first.
+    pub fn from_xyz<C: ToXyz>(color: &C, white: Chromaticity) -> Lab<T> {
+        let xyz = color.to_xyz::<f64>();
+        let w = white.to_xyz::<f64>();
+        let f = |t: f64| if t > EPSILON { t.cbrt() } else { (KAPPA * t + 16.0) / 116.0 };
+        let fx = f(xyz.x / w.x);
+        let fy = f(xyz.y / w.y);
+        let fz = f(xyz.z / w.z);
+        Lab::new(cast(116.0 * fy - 16.0),
+                 cast(500.0 * (fx - fy)),
+                 cast(200.0 * (fy - fz)))
+    }
+
+    /// Converts the color to XYZ, interpreting it relative to the given
+    /// reference white.
+    pub fn to_xyz_with<U:FloatChannel>(&self, white: Chromaticity) -> Xyz<U> {
+        let l: f64 = cast(self.l);
+        let fy = (l + 16.0) / 116.0;
+        let fx = fy + cast::<T, f64>(self.a) / 500.0;
+        let fz = fy - cast::<T, f64>(self.b) / 200.0;
+        let finv = |f: f64| if f * f * f > EPSILON { f * f * f } else { (116.0 * f - 16.0) / KAPPA };
+        let y = if l > KAPPA * EPSILON { fy * fy * fy } else { l / KAPPA };
+        let w = white.to_xyz::<f64>();
+        Xyz::new(cast(finv(fx) * w.x),
+                 cast(y * w.y),
+                 cast(finv(fz) * w.z))
+    }
+}
+
+impl<T:FloatChannel> Color<T> for Lab<T> {
+    /// Clamps the components of the color to the range `(lo,hi)`.
+    #[inline]
+    fn clamp_s(self, lo: T, hi: T) -> Lab<T> {
+        Lab::new(Channel::clamp(self.l, lo, hi),
+                 Channel::clamp(self.a, lo, hi),
+                 Channel::clamp(self.b, lo, hi))
+    }
+
+    /// Clamps the components of the color component-wise between `lo` and `hi`.
+    #[inline]
+    fn clamp_c(self, lo: Lab<T>, hi: Lab<T>) -> Lab<T> {
+        Lab::new(Channel::clamp(self.l, lo.l, hi.l),
+                 Channel::clamp(self.a, lo.a, hi.a),
+                 Channel::clamp(self.b, lo.b, hi.b))
+    }
+
+    /// Inverts the lightness and reflects the color through the neutral axis.
+    #[inline]
+    fn inverse(self) -> Lab<T> {
+        Lab::new(cast::<f64, T>(100.0) - self.l, -self.a, -self.b)
+    }
+
+    /// Mixes the colors linearly in L\*a\*b\*.
+    #[inline]
+    fn mix(self, other: Self, value: T) -> Self {
+        Lab::new(self.l + (other.l - self.l) * value,
+                 self.a + (other.a - self.a) * value,
+                 self.b + (other.b - self.b) * value)
+    }
+}
+
+impl<T:FloatChannel> FloatColor<T> for Lab<T> {
+    /// Clamps the lightness of the color to the range `(0,100)`.
+    #[inline]
+    fn saturate(self) -> Lab<T> {
+        Lab::new(Channel::clamp(self.l, cast(0.0), cast(100.0)), self.a, self.b)
+    }
+}
+
+impl<T:FloatChannel> Color<T> for Lch<T> {
+    /// Clamps the components of the color to the range `(lo,hi)`.
+    #[inline]
+    fn clamp_s(self, lo: T, hi: T) -> Lch<T> {
+        Lch::new(Channel::clamp(self.l, lo, hi),
+                 Channel::clamp(self.c, lo, hi),
+                 self.h)
+    }
+
+    /// Clamps the components of the color component-wise between `lo` and `hi`.
+    #[inline]
+    fn clamp_c(self, lo: Lch<T>, hi: Lch<T>) -> Lch<T> {
+        Lch::new(Channel::clamp(self.l, lo.l, hi.l),
+                 Channel::clamp(self.c, lo.c, hi.c),
+                 self.h)
+    }
+
+    /// Inverts the lightness and rotates the hue by 180 degrees.
+    #[inline]
+    fn inverse(self) -> Lch<T> {
+        Lch::new(cast::<f64, T>(100.0) - self.l,
+                 self.c,
+                 (self.h + Deg(cast(180))).wrap())
+    }
+
+    /// Mixes the colors component-wise, with the hue interpolated along the
+    /// shorter arc, as CSS `color-mix()` does in polar spaces.
+    #[inline]
+    fn mix(self, other: Self, value: T) -> Self {
+        self.mix_hue(other, value, HueInterpolation::Shorter)
+    }
+}
+
+impl<T:FloatChannel> FloatColor<T> for Lch<T> {
+    /// Normalizes the components of the color. Modulo `360` is applied to the
+    /// `h` component, `l` is clamped to `(0,100)` and `c` is kept positive.
+    #[inline]
+    fn saturate(self) -> Lch<T> {
+        Lch::new(Channel::clamp(self.l, cast(0.0), cast(100.0)),
+                 self.c.max(cast(0.0)),
+                 self.h.wrap())
+    }
+}
+
+pub trait ToLab {
+    fn to_lab<U:FloatChannel>(&self) -> Lab<U>;
+}
+
+impl<T:FloatChannel> ToLab for Lab<T> {
+    #[inline]
+    fn to_lab<U:FloatChannel>(&self) -> Lab<U> {
+        Lab::new(cast(self.l), cast(self.a), cast(self.b))
+    }
+}
+
+impl<T:FloatChannel> ToLab for Lch<T> {
+    #[inline]
+    fn to_lab<U:FloatChannel>(&self) -> Lab<U> {
+        let c: f64 = cast(self.c);
+        let (sin, cos) = Deg(cast::<T, f64>(self.h.value())).sin_cos();
+        Lab::new(cast(self.l), cast(c * cos), cast(c * sin))
+    }
+}
+
+impl<T:FloatChannel> ToLab for Xyz<T> {
+    #[inline]
+    fn to_lab<U:FloatChannel>(&self) -> Lab<U> {
+        Lab::from_xyz(self, Chromaticity::D65)
+    }
+}
+
+impl<T:Channel> ToLab for Rgb<T> {
+    #[inline]
+    fn to_lab<U:FloatChannel>(&self) -> Lab<U> {
+        Lab::from_xyz(self, Chromaticity::D65)
+    }
+}
+
+impl<T:Channel> ToLab for Srgb<T> {
+    #[inline]
+    fn to_lab<U:FloatChannel>(&self) -> Lab<U> {
+        Lab::from_xyz(self, Chromaticity::D65)
+    }
+}
+
+impl<T:Channel> ToLab for Hsv<T> {
+    #[inline]
+    fn to_lab<U:FloatChannel>(&self) -> Lab<U> {
+        Lab::from_xyz(self, Chromaticity::D65)
+    }
+}
+
+impl<T:Channel> ToLab for Hsl<T> {
+    #[inline]
+    fn to_lab<U:FloatChannel>(&self) -> Lab<U> {
+        Lab::from_xyz(self, Chromaticity::D65)
+    }
+}
+
+impl<T, C: ToLab> ToLab for AlphaColor<T, C> {
+    #[inline]
+    fn to_lab<U:FloatChannel>(&self) -> Lab<U> {
+        self.c.to_lab()
+    }
+}
+
+pub trait ToLch {
+    fn to_lch<U:FloatChannel>(&self) -> Lch<U>;
+}
+
+impl<T:FloatChannel> ToLch for Lch<T> {
+    #[inline]
+    fn to_lch<U:FloatChannel>(&self) -> Lch<U> {
+        Lch::new(cast(self.l), cast(self.c), Deg(cast(self.h.value())))
+    }
+}
+
+impl<T:FloatChannel> ToLch for Lab<T> {
+    /// Converts the color to LCh(ab). The hue is zero when both a\* and b\*
+    /// are.
+    fn to_lch<U:FloatChannel>(&self) -> Lch<U> {
+        let a: f64 = cast(self.a);
+        let b: f64 = cast(self.b);
+        let c = a.hypot(b);
+        let h = if c > 0.0 { b.atan2(a).to_degrees() } else { 0.0 };
+        let h = if h < 0.0 { h + 360.0 } else { h };
+        Lch::new(cast(self.l), cast(c), Deg(cast(h)))
+    }
+}
+
+impl<T:FloatChannel> ToLch for Xyz<T> {
+    #[inline]
+    fn to_lch<U:FloatChannel>(&self) -> Lch<U> {
+        self.to_lab::<f64>().to_lch()
+    }
+}
+
+impl<T:Channel> ToLch for Rgb<T> {
+    #[inline]
+    fn to_lch<U:FloatChannel>(&self) -> Lch<U> {
+        self.to_lab::<f64>().to_lch()
+    }
+}
+
+impl<T:Channel> ToLch for Srgb<T> {
+    #[inline]
+    fn to_lch<U:FloatChannel>(&self) -> Lch<U> {
+        self.to_lab::<f64>().to_lch()
+    }
+}
+
+impl<T:Channel> ToLch for Hsv<T> {
+    #[inline]
+    fn to_lch<U:FloatChannel>(&self) -> Lch<U> {
+        self.to_lab::<f64>().to_lch()
+    }
+}
+
+impl<T, C: ToLch> ToLch for AlphaColor<T, C> {
+    #[inline]
+    fn to_lch<U:FloatChannel>(&self) -> Lch<U> {
+        self.c.to_lch()
+    }
+}
+
+impl<T:FloatChannel> ToXyz for Lab<T> {
+    #[inline]
+    fn to_xyz<U:FloatChannel>(&self) -> Xyz<U> {
+        self.to_xyz_with(Chromaticity::D65)
+    }
+}
+
+impl<T:FloatChannel> ToXyz for Lch<T> {
+    #[inline]
+    fn to_xyz<U:FloatChannel>(&self) -> Xyz<U> {
+        self.to_lab::<f64>().to_xyz()
+    }
+}
+
+impl<T:FloatChannel> ToRgb for Lab<T> {
+    #[inline]
+    fn to_rgb<U:Channel>(&self) -> Rgb<U> {
+        self.to_xyz::<f64>().to_rgb()
+    }
+}
+
+impl<T:FloatChannel> ToRgb for Lch<T> {
+    #[inline]
+    fn to_rgb<U:Channel>(&self) -> Rgb<U> {
+        self.to_xyz::<f64>().to_rgb()
+    }
+}
+
+impl<T:FloatChannel> ToSrgb for Lab<T> {
+    #[inline]
+    fn to_srgb<U:Channel>(&self) -> Srgb<U> {
+        self.to_rgb::<f64>().to_srgb()
+    }
+}
+
+impl<T:FloatChannel> ToSrgb for Lch<T> {
+    #[inline]
+    fn to_srgb<U:Channel>(&self) -> Srgb<U> {
+        self.to_rgb::<f64>().to_srgb()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {Lab, ToLab, Lch, ToLch};
+    use {Srgb, ToSrgb, Xyz, Chromaticity};
+    use {Laba, Srgba};
+    use Color;
+    use angle::*;
+
+    fn assert_lab_eq(a: Lab<f64>, b: Lab<f64>) {
+        assert!((a.l - b.l).abs() < 1e-2, "{:?} != {:?}", a, b);
+        assert!((a.a - b.a).abs() < 1e-2, "{:?} != {:?}", a, b);
+        assert!((a.b - b.b).abs() < 1e-2, "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn test_srgb_to_lab() {
+        assert_lab_eq(Srgb::<u8>::new(0xFF, 0xFF, 0xFF).to_lab(), Lab::new(100.0, 0.0, 0.0));
+        assert_lab_eq(Srgb::<u8>::new(0x00, 0x00, 0x00).to_lab(), Lab::new(0.0, 0.0, 0.0));
+        assert_lab_eq(Srgb::<u8>::new(0xFF, 0x00, 0x00).to_lab(), Lab::new(53.2408, 80.0925, 67.2032));
+        assert_lab_eq(Srgb::<u8>::new(0x00, 0xFF, 0x00).to_lab(), Lab::new(87.7347, -86.1827, 83.1793));
+        assert_lab_eq(Srgb::<u8>::new(0x00, 0x00, 0xFF).to_lab(), Lab::new(32.2970, 79.1875, -107.8602));
+        assert_lab_eq(Srgb::<u8>::new(0x80, 0x80, 0x80).to_lab(), Lab::new(53.5850, 0.0, 0.0));
+    }
+
+    #[test]
+    fn test_lab_to_srgb() {
+        let srgb = Lab::<f64>::new(53.2408, 80.0925, 67.2032).to_srgb::<f64>();
+        assert!((srgb.r - 1.0).abs() < 1e-4 && srgb.g.abs() < 1e-4 && srgb.b.abs() < 1e-4);
+        let srgb = Lab::<f64>::new(100.0, 0.0, 0.0).to_srgb::<f64>();
+        assert!((srgb.r - 1.0).abs() < 1e-9 && (srgb.g - 1.0).abs() < 1e-9 && (srgb.b - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_reference_white() {
+        let white = Chromaticity::D50.to_xyz::<f64>();
+        assert_lab_eq(Lab::from_xyz(&white, Chromaticity::D50), Lab::new(100.0, 0.0, 0.0));
+        let xyz = Lab::<f64>::new(50.0, 20.0, -30.0).to_xyz_with::<f64>(Chromaticity::D50);
+        assert_lab_eq(Lab::from_xyz(&xyz, Chromaticity::D50), Lab::new(50.0, 20.0, -30.0));
+        assert_lab_eq(Xyz::new(0.0, 0.0, 0.0).to_lab(), Lab::new(0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn test_lch() {
+        let lch = Srgb::<u8>::new(0xFF, 0x00, 0x00).to_lch::<f64>();
+        assert!((lch.l - 53.2408).abs() < 1e-2);
+        assert!((lch.c - 104.5518).abs() < 1e-2);
+        assert!((lch.h.value() - 39.9990).abs() < 1e-2);
+        assert_lab_eq(lch.to_lab(), Lab::new(53.2408, 80.0925, 67.2032));
+
+        let lch = Lab::<f64>::new(50.0, 0.0, -20.0).to_lch::<f64>();
+        assert_eq!(lch, Lch::new(50.0, 20.0, Deg(270.0)));
+        assert_eq!(Lab::<f64>::new(50.0, 0.0, 0.0).to_lch::<f64>(), Lch::new(50.0, 0.0, Deg(0.0)));
+    }
+
+    #[test]
+    fn test_lab_mix() {
+        let black = Lab::<f64>::new(0.0, 0.0, 0.0);
+        let red = Lab::<f64>::new(53.2408, 80.0925, 67.2032);
+        assert_lab_eq(black.mix(red, 0.5), Lab::new(26.6204, 40.04625, 33.6016));
+        assert_eq!(black.inverse(), Lab::new(100.0, 0.0, 0.0));
+
+        let laba = Laba { c: red, a: 0.5 };
+        let srgba: Srgba<f64> = Srgba { c: laba.to_srgb(), a: laba.a };
+        assert!((srgba.c.r - 1.0).abs() < 1e-4);
+        assert_lab_eq(srgba.to_lab(), red);
+    }
+}
@@ -15,19 +15,57 @@
 extern crate num;
 extern crate angle;
 
+pub use adaptation::{WhitePoint, Illuminant, Observer, ChromaticAdaptation};
 pub use alpha::AlphaColor;
-pub use alpha::{Rgba, Hsva, Srgba, YCbCra, ToRgba};
+pub use alpha::{Rgba, Hsva, Hsla, Srgba, YCbCra, Laba, Lcha, Oklaba, Oklcha, Luva, Lchuva, Hsluva, Hpluva, ToRgba, ToSrgba, ToYCbCra};
+pub use blend::{Blend, BlendMode};
 pub use channel::{Channel, FloatChannel};
+pub use composite::PorterDuff;
+pub use css::{ParseColorError, ParseColorErrorKind, Css, CssFormat, ToCss};
+pub use difference::{ColorDifference, Cie94Weighting};
+pub use gamut::{GamutMapping, ToGamut};
+pub use gradient::{Gradient, GradientColor, InterpolationSpace};
+pub use half::f16;
+pub use hsl::{Hsl, ToHsl};
+pub use hsluv::{Hsluv, ToHsluv, Hpluv, ToHpluv};
 pub use hsv::{Hsv, ToHsv};
+pub use hue::{HueInterpolation, MixHue};
+pub use lab::{Lab, ToLab, Lch, ToLch};
+pub use luv::{Luv, ToLuv, Lchuv, ToLchuv};
+pub use oklab::{Oklab, ToOklab, Oklch, ToOklch};
+pub use packed::{Layout, Rgb565, Rgba5551, Rgba4444, Rgb10A2, R11G11B10F, Rgb9E5};
+pub use prealpha::{PreAlpha, PreRgba};
 pub use rgb::{Rgb, Rg, ToRgb, consts};
-pub use srgb::Srgb;
-pub use ycbcr::YCbCr;
+pub use space::RgbSpace;
+pub use srgb::{Srgb, ToSrgb};
+pub use xyz::{Xyz, ToXyz, Yxy, ToYxy, Chromaticity, RgbPrimaries};
+pub use ycbcr::{YCbCr, ToYCbCr, YCbCrMatrix, YCbCrRange};
 
 #[macro_use] mod rgb;
 #[macro_use] mod alpha;
+mod adaptation;
+mod blend;
+pub mod bulk;
 mod channel;
+mod composite;
+mod css;
+mod difference;
+mod gamut;
+mod gradient;
+mod half;
+mod hsl;
+mod hsluv;
 mod hsv;
+mod hue;
+mod lab;
+mod luv;
+mod matrix;
+mod oklab;
+mod packed;
+mod prealpha;
+pub mod space;
 mod srgb;
+mod xyz;
 mod ycbcr;
 
 pub trait Color<T>: Copy {
@@ -0,0 +1,380 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The CIE 1976 L\*u\*v\* color space and its cylindrical LCh(uv) form:
+//! http://en.wikipedia.org/wiki/CIELUV
+//!
+//! `L` ranges from `0` to `100`. Colors are relative to a reference white,
+//! which is D65 unless stated otherwise.
+
+use num;
+use num::traits;
+use angle::*;
+
+use {Color, FloatColor};
+use {Channel, FloatChannel};
+use {Rgb, ToRgb};
+use {Srgb, ToSrgb};
+use {Hsv, Hsl, AlphaColor};
+use {Xyz, ToXyz, Chromaticity};
+use {HueInterpolation, MixHue};
+use lab::{EPSILON, KAPPA};
+
+fn cast<T: num::NumCast, U: num::NumCast>(n: T) -> U {
+    traits::cast(n).unwrap()
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Luv<T> { pub l: T, pub u: T, pub v: T }
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Lchuv<T: Channel> { pub l: T, pub c: T, pub h: Deg<T> }
+
+impl<T> Luv<T> {
+    #[inline]
+    pub fn new(l: T, u: T, v: T) -> Luv<T> {
+        Luv { l, u, v }
+    }
+}
+
+impl<T: Channel> Lchuv<T> {
+    #[inline]
+    pub fn new(l: T, c: T, h: Deg<T>) -> Lchuv<T> {
+        Lchuv { l, c, h }
+    }
+}
+
+/// Returns the `(u', v')` chromaticity coordinates of a tristimulus value.
+fn uv_prime(x: f64, y: f64, z: f64) -> (f64, f64) {
+    let d = x + 15.0 * y + 3.0 * z;
+    if d == 0.0 { (0.0, 0.0) } else { (4.0 * x / d, 9.0 * y / d) }
+}
+
+impl<T:FloatChannel> Luv<T> {
+    /// Converts a color to L\*u\*v\*, with u\* and v\* measured from the
+    /// chromaticity of the given reference white.
+    pub fn from_xyz<C: ToXyz>(color: &C, white: Chromaticity) -> Luv<T> {
+        let xyz = color.to_xyz::<f64>();
+        let w = white.to_xyz::<f64>();
+        let yr = xyz.y / w.y;
+        let l = if yr > EPSILON { 116.0 * yr.cbrt() - 16.0 } else { KAPPA * yr };
+        if l == 0.0 {
+            return Luv::new(cast(0.0), cast(0.0), cast(0.0));
+        }
+        let (up, vp) = uv_prime(xyz.x, xyz.y, xyz.z);
+        let (upn, vpn) = uv_prime(w.x, w.y, w.z);
+        Luv::new(cast(l),
+                 cast(13.0 * l * (up - upn)),
+                 cast(13.0 * l * (vp - vpn)))
+    }
+
+    /// Converts the color back to XYZ, given the reference white that u\*
+    /// and v\* are measured from.
+    pub fn to_xyz_with<U:FloatChannel>(&self, white: Chromaticity) -> Xyz<U> {
+        let l: f64 = cast(self.l);
+        if l <= 0.0 {
+            return Xyz::new(cast(0.0), cast(0.0), cast(0.0));
+        }
+        let w = white.to_xyz::<f64>();
+        let (upn, vpn) = uv_prime(w.x, w.y, w.z);
+        let up = cast::<T, f64>(self.u) / (13.0 * l) + upn;
+        let vp = cast::<T, f64>(self.v) / (13.0 * l) + vpn;
+        let y = if l > KAPPA * EPSILON { w.y * ((l + 16.0) / 116.0).powi(3) } else { w.y * l / KAPPA };
+        Xyz::new(cast(y * 9.0 * up / (4.0 * vp)),
+                 cast(y),
+                 cast(y * (12.0 - 3.0 * up - 20.0 * vp) / (4.0 * vp)))
+    }
+}
+
+impl<T:FloatChannel> Color<T> for Luv<T> {
+    /// Clamps the components of the color to the range `(lo,hi)`.
+    #[inline]
+    fn clamp_s(self, lo: T, hi: T) -> Luv<T> {
+        Luv::new(Channel::clamp(self.l, lo, hi),
+                 Channel::clamp(self.u, lo, hi),
+                 Channel::clamp(self.v, lo, hi))
+    }
+
+    /// Clamps the components of the color component-wise between `lo` and `hi`.
+    #[inline]
+    fn clamp_c(self, lo: Luv<T>, hi: Luv<T>) -> Luv<T> {
+        Luv::new(Channel::clamp(self.l, lo.l, hi.l),
+                 Channel::clamp(self.u, lo.u, hi.u),
+                 Channel::clamp(self.v, lo.v, hi.v))
+    }
+
+    /// Inverts the lightness and reflects the color through the neutral axis.
+    #[inline]
+    fn inverse(self) -> Luv<T> {
+        Luv::new(cast::<f64, T>(100.0) - self.l, -self.u, -self.v)
+    }
+
+    /// Mixes the colors linearly in L\*u\*v\*.
+    #[inline]
+    fn mix(self, other: Self, value: T) -> Self {
+        Luv::new(self.l + (other.l - self.l) * value,
+                 self.u + (other.u - self.u) * value,
+                 self.v + (other.v - self.v) * value)
+    }
+}
+
+impl<T:FloatChannel> FloatColor<T> for Luv<T> {
+    /// Clamps the lightness of the color to the range `(0,100)`.
+    #[inline]
+    fn saturate(self) -> Luv<T> {
+        Luv::new(Channel::clamp(self.l, cast(0.0), cast(100.0)), self.u, self.v)
+    }
+}
+
+impl<T:FloatChannel> Color<T> for Lchuv<T> {
+    /// Clamps the components of the color to the range `(lo,hi)`.
+    #[inline]
+    fn clamp_s(self, lo: T, hi: T) -> Lchuv<T> {
+        Lchuv::new(Channel::clamp(self.l, lo, hi),
+                   Channel::clamp(self.c, lo, hi),
+                   self.h)
+    }
+
+    /// Clamps the components of the color component-wise between `lo` and `hi`.
+    #[inline]
+    fn clamp_c(self, lo: Lchuv<T>, hi: Lchuv<T>) -> Lchuv<T> {
+        Lchuv::new(Channel::clamp(self.l, lo.l, hi.l),
+                   Channel::clamp(self.c, lo.c, hi.c),
+                   self.h)
+    }
+
+    /// Inverts the lightness and rotates the hue by 180 degrees.
+    #[inline]
+    fn inverse(self) -> Lchuv<T> {
+        Lchuv::new(cast::<f64, T>(100.0) - self.l,
+                   self.c,
+                   (self.h + Deg(cast(180))).wrap())
+    }
+
+    /// Mixes the colors component-wise, with the hue interpolated along the
+    /// shorter arc, as CSS `color-mix()` does in polar spaces.
+    #[inline]
+    fn mix(self, other: Self, value: T) -> Self {
+        self.mix_hue(other, value, HueInterpolation::Shorter)
+    }
+}
+
+impl<T:FloatChannel> FloatColor<T> for Lchuv<T> {
+    /// Normalizes the components of the color. Modulo `360` is applied to the
+    /// `h` component, `l` is clamped to `(0,100)` and `c` is kept positive.
+    #[inline]
+    fn saturate(self) -> Lchuv<T> {
+        Lchuv::new(Channel::clamp(self.l, cast(0.0), cast(100.0)),
+                   self.c.max(cast(0.0)),
+                   self.h.wrap())
+    }
+}
+
+pub trait ToLuv {
+    fn to_luv<U:FloatChannel>(&self) -> Luv<U>;
+}
+
+impl<T:FloatChannel> ToLuv for Luv<T> {
+    #[inline]
+    fn to_luv<U:FloatChannel>(&self) -> Luv<U> {
+        Luv::new(cast(self.l), cast(self.u), cast(self.v))
+    }
+}
+
+impl<T:FloatChannel> ToLuv for Lchuv<T> {
+    #[inline]
+    fn to_luv<U:FloatChannel>(&self) -> Luv<U> {
+        let c: f64 = cast(self.c);
+        let (sin, cos) = Deg(cast::<T, f64>(self.h.value())).sin_cos();
+        Luv::new(cast(self.l), cast(c * cos), cast(c * sin))
+    }
+}
+
+impl<T:FloatChannel> ToLuv for Xyz<T> {
+    #[inline]
+    fn to_luv<U:FloatChannel>(&self) -> Luv<U> {
+        Luv::from_xyz(self, Chromaticity::D65)
+    }
+}
+
+impl<T:Channel> ToLuv for Rgb<T> {
+    #[inline]
+    fn to_luv<U:FloatChannel>(&self) -> Luv<U> {
+        Luv::from_xyz(self, Chromaticity::D65)
+    }
+}
+
+impl<T:Channel> ToLuv for Srgb<T> {
+    #[inline]
+    fn to_luv<U:FloatChannel>(&self) -> Luv<U> {
+        Luv::from_xyz(self, Chromaticity::D65)
+    }
+}
+
+impl<T:Channel> ToLuv for Hsv<T> {
+    #[inline]
+    fn to_luv<U:FloatChannel>(&self) -> Luv<U> {
+        Luv::from_xyz(self, Chromaticity::D65)
+    }
+}
+
+impl<T:Channel> ToLuv for Hsl<T> {
+    #[inline]
+    fn to_luv<U:FloatChannel>(&self) -> Luv<U> {
+        Luv::from_xyz(self, Chromaticity::D65)
+    }
+}
+
+impl<T, C: ToLuv> ToLuv for AlphaColor<T, C> {
+    #[inline]
+    fn to_luv<U:FloatChannel>(&self) -> Luv<U> {
+        self.c.to_luv()
+    }
+}
+
+pub trait ToLchuv {
+    fn to_lchuv<U:FloatChannel>(&self) -> Lchuv<U>;
+}
+
+impl<T:FloatChannel> ToLchuv for Lchuv<T> {
+    #[inline]
+    fn to_lchuv<U:FloatChannel>(&self) -> Lchuv<U> {
+        Lchuv::new(cast(self.l), cast(self.c), Deg(cast(self.h.value())))
+    }
+}
+
+impl<T:FloatChannel> ToLchuv for Luv<T> {
+    /// Converts the color to LCh(uv). Chromas below `1e-8`, which grays pick
+    /// up from rounding in the u'v' conversion, get a hue of zero.
+    fn to_lchuv<U:FloatChannel>(&self) -> Lchuv<U> {
+        let u: f64 = cast(self.u);
+        let v: f64 = cast(self.v);
+        let c = u.hypot(v);
+        let h = if c > 1e-8 { v.atan2(u).to_degrees() } else { 0.0 };
+        let h = if h < 0.0 { h + 360.0 } else { h };
+        Lchuv::new(cast(self.l), cast(c), Deg(cast(h)))
+    }
+}
+
+impl<T:FloatChannel> ToLchuv for Xyz<T> {
+    #[inline]
+    fn to_lchuv<U:FloatChannel>(&self) -> Lchuv<U> {
+        self.to_luv::<f64>().to_lchuv()
+    }
+}
+
+impl<T:Channel> ToLchuv for Rgb<T> {
+    #[inline]
+    fn to_lchuv<U:FloatChannel>(&self) -> Lchuv<U> {
+        self.to_luv::<f64>().to_lchuv()
+    }
+}
+
+impl<T:Channel> ToLchuv for Srgb<T> {
+    #[inline]
+    fn to_lchuv<U:FloatChannel>(&self) -> Lchuv<U> {
+        self.to_luv::<f64>().to_lchuv()
+    }
+}
+
+impl<T, C: ToLchuv> ToLchuv for AlphaColor<T, C> {
+    #[inline]
+    fn to_lchuv<U:FloatChannel>(&self) -> Lchuv<U> {
+        self.c.to_lchuv()
+    }
+}
+
+impl<T:FloatChannel> ToXyz for Luv<T> {
+    #[inline]
+    fn to_xyz<U:FloatChannel>(&self) -> Xyz<U> {
+        self.to_xyz_with(Chromaticity::D65)
+    }
+}
+
+impl<T:FloatChannel> ToXyz for Lchuv<T> {
+    #[inline]
+    fn to_xyz<U:FloatChannel>(&self) -> Xyz<U> {
+        self.to_luv::<f64>().to_xyz()
+    }
+}
+
+impl<T:FloatChannel> ToRgb for Luv<T> {
+    #[inline]
+    fn to_rgb<U:Channel>(&self) -> Rgb<U> {
+        self.to_xyz::<f64>().to_rgb()
+    }
+}
+
+impl<T:FloatChannel> ToRgb for Lchuv<T> {
+    #[inline]
+    fn to_rgb<U:Channel>(&self) -> Rgb<U> {
+        self.to_xyz::<f64>().to_rgb()
+    }
+}
+
+impl<T:FloatChannel> ToSrgb for Luv<T> {
+    #[inline]
+    fn to_srgb<U:Channel>(&self) -> Srgb<U> {
+        self.to_rgb::<f64>().to_srgb()
+    }
+}
+
+impl<T:FloatChannel> ToSrgb for Lchuv<T> {
+    #[inline]
+    fn to_srgb<U:Channel>(&self) -> Srgb<U> {
+        self.to_rgb::<f64>().to_srgb()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {Luv, ToLuv, Lchuv, ToLchuv};
+    use {Srgb, ToSrgb, Xyz, Chromaticity};
+    use angle::*;
+
+    fn assert_luv_eq(a: Luv<f64>, b: Luv<f64>) {
+        assert!((a.l - b.l).abs() < 1e-2, "{:?} != {:?}", a, b);
+        assert!((a.u - b.u).abs() < 1e-2, "{:?} != {:?}", a, b);
+        assert!((a.v - b.v).abs() < 1e-2, "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn test_srgb_to_luv() {
+        assert_luv_eq(Srgb::<u8>::new(0xFF, 0xFF, 0xFF).to_luv(), Luv::new(100.0, 0.0, 0.0));
+        assert_luv_eq(Srgb::<u8>::new(0x00, 0x00, 0x00).to_luv(), Luv::new(0.0, 0.0, 0.0));
+        assert_luv_eq(Srgb::<u8>::new(0xFF, 0x00, 0x00).to_luv(), Luv::new(53.2371, 175.0098, 37.7651));
+        assert_luv_eq(Srgb::<u8>::new(0x00, 0xFF, 0x00).to_luv(), Luv::new(87.7355, -83.0671, 107.4181));
+        assert_luv_eq(Srgb::<u8>::new(0x00, 0x00, 0xFF).to_luv(), Luv::new(32.3009, -9.4024, -130.3511));
+    }
+
+    #[test]
+    fn test_luv_round_trip() {
+        let srgb = Srgb::<u8>::new(0xFF, 0x00, 0x00).to_luv::<f64>().to_srgb::<f64>();
+        assert!((srgb.r - 1.0).abs() < 1e-9 && srgb.g.abs() < 1e-9 && srgb.b.abs() < 1e-9);
+        let xyz = Luv::<f64>::new(40.0, -20.0, 30.0).to_xyz_with::<f64>(Chromaticity::D50);
+        assert_luv_eq(Luv::from_xyz(&xyz, Chromaticity::D50), Luv::new(40.0, -20.0, 30.0));
+        assert_luv_eq(Xyz::new(0.0, 0.0, 0.0).to_luv(), Luv::new(0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn test_lchuv() {
+        let lch = Srgb::<u8>::new(0xFF, 0x00, 0x00).to_lchuv::<f64>();
+        assert!((lch.l - 53.2371).abs() < 1e-2);
+        assert!((lch.c - 179.0381).abs() < 1e-2);
+        assert!((lch.h.value() - 12.1771).abs() < 1e-2);
+        assert_luv_eq(lch.to_luv(), Luv::new(53.2371, 175.0098, 37.7651));
+        assert_eq!(Luv::<f64>::new(50.0, 0.0, 0.0).to_lchuv::<f64>(), Lchuv::new(50.0, 0.0, Deg(0.0)));
+    }
+}
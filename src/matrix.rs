@@ -0,0 +1,65 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Minimal 3x3 matrix helpers used by the color space conversions. They are
+//! `const` so that the matrices of fixed spaces are derived at compile time.
+
+/// A row major 3x3 matrix.
+pub type Matrix3 = [[f64; 3]; 3];
+
+#[inline]
+pub const fn mul_vec(m: &Matrix3, v: [f64; 3]) -> [f64; 3] {
+    [m[0][0] * v[0] + m[0][1] * v[1] + m[0][2] * v[2],
+     m[1][0] * v[0] + m[1][1] * v[1] + m[1][2] * v[2],
+     m[2][0] * v[0] + m[2][1] * v[1] + m[2][2] * v[2]]
+}
+
+pub const fn mul(a: &Matrix3, b: &Matrix3) -> Matrix3 {
+    let mut m = [[0.0; 3]; 3];
+    let mut i = 0;
+    while i < 3 {
+        let mut j = 0;
+        while j < 3 {
+            m[i][j] = a[i][0] * b[0][j] + a[i][1] * b[1][j] + a[i][2] * b[2][j];
+            j += 1;
+        }
+        i += 1;
+    }
+    m
+}
+
+pub const fn invert(m: &Matrix3) -> Matrix3 {
+    let c00 = m[1][1] * m[2][2] - m[1][2] * m[2][1];
+    let c01 = m[1][2] * m[2][0] - m[1][0] * m[2][2];
+    let c02 = m[1][0] * m[2][1] - m[1][1] * m[2][0];
+    let det = m[0][0] * c00 + m[0][1] * c01 + m[0][2] * c02;
+    let inv = 1.0 / det;
+    [[c00 * inv,
+      (m[0][2] * m[2][1] - m[0][1] * m[2][2]) * inv,
+      (m[0][1] * m[1][2] - m[0][2] * m[1][1]) * inv],
+     [c01 * inv,
+      (m[0][0] * m[2][2] - m[0][2] * m[2][0]) * inv,
+      (m[0][2] * m[1][0] - m[0][0] * m[1][2]) * inv],
+     [c02 * inv,
+      (m[0][1] * m[2][0] - m[0][0] * m[2][1]) * inv,
+      (m[0][0] * m[1][1] - m[0][1] * m[1][0]) * inv]]
+}
+
+#[inline]
+pub const fn diagonal(v: [f64; 3]) -> Matrix3 {
+    [[v[0], 0.0, 0.0],
+     [0.0, v[1], 0.0],
+     [0.0, 0.0, v[2]]]
+}
@@ -0,0 +1,407 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Björn Ottosson's Oklab perceptual color space and its cylindrical Oklch
+//! form: https://bottosson.github.io/posts/oklab/
+//!
+//! `L` ranges from `0` to `1`, while `a` and `b` stay within roughly `-0.4`
+//! to `0.4`. Mixing colors in Oklab gives smooth gradients without the hue
+//! shifts and muddy midpoints of mixing in RGB.
+
+use num;
+use num::traits;
+use angle::*;
+
+use {Color, FloatColor};
+use {Channel, FloatChannel};
+use {Rgb, ToRgb};
+use {Srgb, ToSrgb};
+use {Hsv, Hsl, AlphaColor};
+use {Xyz, ToXyz, Lab, ToLab};
+use {HueInterpolation, MixHue};
+use matrix::{self, Matrix3};
+
+fn cast<T: num::NumCast, U: num::NumCast>(n: T) -> U {
+    traits::cast(n).unwrap()
+}
+
+/// Converts linear sRGB to the LMS cone responses.
+const RGB_TO_LMS: Matrix3 = [[0.4122214708, 0.5363325363, 0.0514459929],
+                             [0.2119034982, 0.6806995451, 0.1073969566],
+                             [0.0883024619, 0.2817188376, 0.6299787005]];
+
+/// Converts the non-linear cone responses to Oklab.
+const LMS_TO_OKLAB: Matrix3 = [[0.2104542553,  0.7936177850, -0.0040720468],
+                               [1.9779984951, -2.4285922050,  0.4505937099],
+                               [0.0259040371,  0.7827717662, -0.8086757660]];
+
+const OKLAB_TO_LMS: Matrix3 = [[1.0,  0.3963377774,  0.2158037573],
+                               [1.0, -0.1055613458, -0.0638541728],
+                               [1.0, -0.0894841775, -1.2914855480]];
+
+const LMS_TO_RGB: Matrix3 = [[ 4.0767416621, -3.3077115913,  0.2309699292],
+                             [-1.2684380046,  2.6097574011, -0.3413193965],
+                             [-0.0041960863, -0.7034186147,  1.7076147010]];
+
+/// Chroma below which the hue of an Oklch color is considered powerless.
+pub(crate) const ACHROMATIC: f64 = 1e-6;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Oklab<T> { pub l: T, pub a: T, pub b: T }
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Oklch<T: Channel> { pub l: T, pub c: T, pub h: Deg<T> }
+
+impl<T> Oklab<T> {
+    #[inline]
+    pub fn new(l: T, a: T, b: T) -> Oklab<T> {
+        Oklab { l, a, b }
+    }
+}
+
+impl<T: Channel> Oklch<T> {
+    #[inline]
+    pub fn new(l: T, c: T, h: Deg<T>) -> Oklch<T> {
+        Oklch { l, c, h }
+    }
+}
+
+impl<T:FloatChannel> Color<T> for Oklab<T> {
+    /// Clamps the components of the color to the range `(lo,hi)`.
+    #[inline]
+    fn clamp_s(self, lo: T, hi: T) -> Oklab<T> {
+        Oklab::new(Channel::clamp(self.l, lo, hi),
+                   Channel::clamp(self.a, lo, hi),
+                   Channel::clamp(self.b, lo, hi))
+    }
+
+    /// Clamps the components of the color component-wise between `lo` and `hi`.
+    #[inline]
+    fn clamp_c(self, lo: Oklab<T>, hi: Oklab<T>) -> Oklab<T> {
+        Oklab::new(Channel::clamp(self.l, lo.l, hi.l),
+                   Channel::clamp(self.a, lo.a, hi.a),
+                   Channel::clamp(self.b, lo.b, hi.b))
+    }
+
+    /// Inverts the lightness and reflects the color through the neutral axis.
+    #[inline]
+    fn inverse(self) -> Oklab<T> {
+        Oklab::new(self.l.invert_channel(), -self.a, -self.b)
+    }
+
+    /// Mixes the colors linearly in Oklab.
+    #[inline]
+    fn mix(self, other: Self, value: T) -> Self {
+        Oklab::new(self.l + (other.l - self.l) * value,
+                   self.a + (other.a - self.a) * value,
+                   self.b + (other.b - self.b) * value)
+    }
+}
+
+impl<T:FloatChannel> FloatColor<T> for Oklab<T> {
+    /// Clamps the lightness of the color to the range `(0,1)`.
+    #[inline]
+    fn saturate(self) -> Oklab<T> {
+        Oklab::new(self.l.saturate(), self.a, self.b)
+    }
+}
+
+impl<T:FloatChannel> Color<T> for Oklch<T> {
+    /// Clamps the components of the color to the range `(lo,hi)`.
+    #[inline]
+    fn clamp_s(self, lo: T, hi: T) -> Oklch<T> {
+        Oklch::new(Channel::clamp(self.l, lo, hi),
+                   Channel::clamp(self.c, lo, hi),
+                   self.h)
+    }
+
+    /// Clamps the components of the color component-wise between `lo` and `hi`.
+    #[inline]
+    fn clamp_c(self, lo: Oklch<T>, hi: Oklch<T>) -> Oklch<T> {
+        Oklch::new(Channel::clamp(self.l, lo.l, hi.l),
+                   Channel::clamp(self.c, lo.c, hi.c),
+                   self.h)
+    }
+
+    /// Inverts the lightness and rotates the hue by 180 degrees.
+    #[inline]
+    fn inverse(self) -> Oklch<T> {
+        Oklch::new(self.l.invert_channel(),
+                   self.c,
+                   (self.h + Deg(cast(180))).wrap())
+    }
+
+    /// Mixes the colors component-wise, with the hue interpolated along the
+    /// shorter arc, as CSS `color-mix()` does in polar spaces.
+    #[inline]
+    fn mix(self, other: Self, value: T) -> Self {
+        self.mix_hue(other, value, HueInterpolation::Shorter)
+    }
+}
+
+impl<T:FloatChannel> FloatColor<T> for Oklch<T> {
+    /// Normalizes the components of the color. Modulo `360` is applied to the
+    /// `h` component, `l` is clamped to `(0,1)` and `c` is kept positive.
+    #[inline]
+    fn saturate(self) -> Oklch<T> {
+        Oklch::new(self.l.saturate(),
+                   self.c.max(cast(0.0)),
+                   self.h.wrap())
+    }
+}
+
+pub trait ToOklab {
+    fn to_oklab<U:FloatChannel>(&self) -> Oklab<U>;
+}
+
+impl<T:FloatChannel> ToOklab for Oklab<T> {
+    #[inline]
+    fn to_oklab<U:FloatChannel>(&self) -> Oklab<U> {
+        Oklab::new(cast(self.l), cast(self.a), cast(self.b))
+    }
+}
+
+impl<T:FloatChannel> ToOklab for Oklch<T> {
+    #[inline]
+    fn to_oklab<U:FloatChannel>(&self) -> Oklab<U> {
+        let c: f64 = cast(self.c);
+        let (sin, cos) = Deg(cast::<T, f64>(self.h.value())).sin_cos();
+        Oklab::new(cast(self.l), cast(c * cos), cast(c * sin))
+    }
+}
+
+impl<T:Channel> ToOklab for Rgb<T> {
+    fn to_oklab<U:FloatChannel>(&self) -> Oklab<U> {
+        let rgb = self.to_rgb::<f64>();
+        let lms = matrix::mul_vec(&RGB_TO_LMS, [rgb.r, rgb.g, rgb.b]);
+        let lms = [lms[0].cbrt(), lms[1].cbrt(), lms[2].cbrt()];
+        let lab = matrix::mul_vec(&LMS_TO_OKLAB, lms);
+        Oklab::new(cast(lab[0]), cast(lab[1]), cast(lab[2]))
+    }
+}
+
+impl<T:Channel> ToOklab for Srgb<T> {
+    #[inline]
+    fn to_oklab<U:FloatChannel>(&self) -> Oklab<U> {
+        self.to_rgb::<f64>().to_oklab()
+    }
+}
+
+impl<T:Channel> ToOklab for Hsv<T> {
+    #[inline]
+    fn to_oklab<U:FloatChannel>(&self) -> Oklab<U> {
+        self.to_rgb::<f64>().to_oklab()
+    }
+}
+
+impl<T:Channel> ToOklab for Hsl<T> {
+    #[inline]
+    fn to_oklab<U:FloatChannel>(&self) -> Oklab<U> {
+        self.to_rgb::<f64>().to_oklab()
+    }
+}
+
+impl<T:FloatChannel> ToOklab for Xyz<T> {
+    #[inline]
+    fn to_oklab<U:FloatChannel>(&self) -> Oklab<U> {
+        self.to_rgb::<f64>().to_oklab()
+    }
+}
+
+impl<T:FloatChannel> ToOklab for Lab<T> {
+    #[inline]
+    fn to_oklab<U:FloatChannel>(&self) -> Oklab<U> {
+        self.to_rgb::<f64>().to_oklab()
+    }
+}
+
+impl<T, C: ToOklab> ToOklab for AlphaColor<T, C> {
+    #[inline]
+    fn to_oklab<U:FloatChannel>(&self) -> Oklab<U> {
+        self.c.to_oklab()
+    }
+}
+
+pub trait ToOklch {
+    fn to_oklch<U:FloatChannel>(&self) -> Oklch<U>;
+}
+
+impl<T:FloatChannel> ToOklch for Oklch<T> {
+    #[inline]
+    fn to_oklch<U:FloatChannel>(&self) -> Oklch<U> {
+        Oklch::new(cast(self.l), cast(self.c), Deg(cast(self.h.value())))
+    }
+}
+
+impl<T:FloatChannel> ToOklch for Oklab<T> {
+    /// Converts the color to Oklch. The hue is zero for chromas below
+    /// `ACHROMATIC`.
+    fn to_oklch<U:FloatChannel>(&self) -> Oklch<U> {
+        let a: f64 = cast(self.a);
+        let b: f64 = cast(self.b);
+        let c = a.hypot(b);
+        let h = if c > ACHROMATIC { b.atan2(a).to_degrees() } else { 0.0 };
+        let h = if h < 0.0 { h + 360.0 } else { h };
+        Oklch::new(cast(self.l), cast(c), Deg(cast(h)))
+    }
+}
+
+impl<T:Channel> ToOklch for Rgb<T> {
+    #[inline]
+    fn to_oklch<U:FloatChannel>(&self) -> Oklch<U> {
+        self.to_oklab::<f64>().to_oklch()
+    }
+}
+
+impl<T:Channel> ToOklch for Srgb<T> {
+    #[inline]
+    fn to_oklch<U:FloatChannel>(&self) -> Oklch<U> {
+        self.to_oklab::<f64>().to_oklch()
+    }
+}
+
+impl<T:Channel> ToOklch for Hsv<T> {
+    #[inline]
+    fn to_oklch<U:FloatChannel>(&self) -> Oklch<U> {
+        self.to_oklab::<f64>().to_oklch()
+    }
+}
+
+impl<T, C: ToOklch> ToOklch for AlphaColor<T, C> {
+    #[inline]
+    fn to_oklch<U:FloatChannel>(&self) -> Oklch<U> {
+        self.c.to_oklch()
+    }
+}
+
+impl<T:FloatChannel> ToRgb for Oklab<T> {
+    fn to_rgb<U:Channel>(&self) -> Rgb<U> {
+        let lab = [cast(self.l), cast(self.a), cast(self.b)];
+        let lms = matrix::mul_vec(&OKLAB_TO_LMS, lab);
+        let lms = [lms[0] * lms[0] * lms[0],
+                   lms[1] * lms[1] * lms[1],
+                   lms[2] * lms[2] * lms[2]];
+        let rgb = matrix::mul_vec(&LMS_TO_RGB, lms);
+        Rgb::new(Channel::from(rgb[0]), Channel::from(rgb[1]), Channel::from(rgb[2]))
+    }
+}
+
+impl<T:FloatChannel> ToRgb for Oklch<T> {
+    #[inline]
+    fn to_rgb<U:Channel>(&self) -> Rgb<U> {
+        self.to_oklab::<f64>().to_rgb()
+    }
+}
+
+impl<T:FloatChannel> ToSrgb for Oklab<T> {
+    #[inline]
+    fn to_srgb<U:Channel>(&self) -> Srgb<U> {
+        self.to_rgb::<f64>().to_srgb()
+    }
+}
+
+impl<T:FloatChannel> ToSrgb for Oklch<T> {
+    #[inline]
+    fn to_srgb<U:Channel>(&self) -> Srgb<U> {
+        self.to_rgb::<f64>().to_srgb()
+    }
+}
+
+impl<T:FloatChannel> ToXyz for Oklab<T> {
+    #[inline]
+    fn to_xyz<U:FloatChannel>(&self) -> Xyz<U> {
+        self.to_rgb::<f64>().to_xyz()
+    }
+}
+
+impl<T:FloatChannel> ToXyz for Oklch<T> {
+    #[inline]
+    fn to_xyz<U:FloatChannel>(&self) -> Xyz<U> {
+        self.to_rgb::<f64>().to_xyz()
+    }
+}
+
+impl<T:FloatChannel> ToLab for Oklab<T> {
+    #[inline]
+    fn to_lab<U:FloatChannel>(&self) -> Lab<U> {
+        self.to_rgb::<f64>().to_lab()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {Oklab, ToOklab, Oklch, ToOklch};
+    use {Rgb, ToRgb, Srgb, ToSrgb};
+    use {Oklaba, Oklcha, Srgba, ToRgba};
+    use Color;
+    use angle::*;
+
+    fn assert_oklab_eq(a: Oklab<f64>, b: Oklab<f64>) {
+        assert!((a.l - b.l).abs() < 1e-4, "{:?} != {:?}", a, b);
+        assert!((a.a - b.a).abs() < 1e-4, "{:?} != {:?}", a, b);
+        assert!((a.b - b.b).abs() < 1e-4, "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn test_srgb_to_oklab() {
+        assert_oklab_eq(Srgb::<u8>::new(0xFF, 0xFF, 0xFF).to_oklab(), Oklab::new(1.0, 0.0, 0.0));
+        assert_oklab_eq(Srgb::<u8>::new(0x00, 0x00, 0x00).to_oklab(), Oklab::new(0.0, 0.0, 0.0));
+        assert_oklab_eq(Srgb::<u8>::new(0xFF, 0x00, 0x00).to_oklab(), Oklab::new(0.62796, 0.22486, 0.12585));
+        assert_oklab_eq(Srgb::<u8>::new(0x00, 0xFF, 0x00).to_oklab(), Oklab::new(0.86644, -0.23389, 0.17950));
+        assert_oklab_eq(Srgb::<u8>::new(0x00, 0x00, 0xFF).to_oklab(), Oklab::new(0.45201, -0.03246, -0.31153));
+    }
+
+    #[test]
+    fn test_oklab_round_trip() {
+        let colors = [Rgb::new(0.2f64, 0.4, 0.6), Rgb::new(1.0, 0.5, 0.0), Rgb::new(0.05, 0.0, 0.9)];
+        for c in colors.iter() {
+            let back = c.to_oklab::<f64>().to_rgb::<f64>();
+            assert!((back.r - c.r).abs() < 1e-6 && (back.g - c.g).abs() < 1e-6 && (back.b - c.b).abs() < 1e-6);
+            let back = c.to_oklch::<f64>().to_rgb::<f64>();
+            assert!((back.r - c.r).abs() < 1e-6 && (back.g - c.g).abs() < 1e-6 && (back.b - c.b).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_oklch() {
+        let lch = Srgb::<u8>::new(0xFF, 0x00, 0x00).to_oklch::<f64>();
+        assert!((lch.l - 0.62796).abs() < 1e-4);
+        assert!((lch.c - 0.25768).abs() < 1e-4);
+        assert!((lch.h.value() - 29.2339).abs() < 1e-2);
+        assert_eq!(Srgb::<u8>::new(0xFF, 0xFF, 0xFF).to_oklch::<f64>().h, Deg(0.0));
+        assert_eq!(Oklab::<f64>::new(0.5, 0.0, -0.1).to_oklch::<f64>(), Oklch::new(0.5, 0.1, Deg(270.0)));
+    }
+
+    #[test]
+    fn test_oklab_mix() {
+        let white = Srgb::<f64>::new(1.0, 1.0, 1.0).to_oklab::<f64>();
+        let black = Srgb::<f64>::new(0.0, 0.0, 0.0).to_oklab::<f64>();
+        let grey = white.mix(black, 0.5).to_srgb::<f64>();
+        // Mixing in Oklab gives a perceptually even midpoint
+        assert!((grey.r - 0.3882).abs() < 1e-3);
+
+        let red = Srgba { c: Srgb::new(1.0f64, 0.0, 0.0), a: 1.0 };
+        let blue = Srgba { c: Srgb::new(0.0f64, 0.0, 1.0), a: 0.0 };
+        let a = Oklaba { c: red.to_oklab::<f64>(), a: red.a };
+        let b = Oklaba { c: blue.to_oklab::<f64>(), a: blue.a };
+        let mid = a.mix(b, 0.5);
+        assert_eq!(mid.a, 0.5);
+        assert_oklab_eq(mid.c, Oklab::new(0.539985, 0.0962, -0.09284));
+
+        let a = Oklcha { c: red.to_oklch::<f64>(), a: 1.0 };
+        let b = Oklcha { c: blue.to_oklch::<f64>(), a: 1.0 };
+        assert_eq!(a.mix(b, 0.0).to_rgba::<u8>().c.b, 0);
+    }
+}
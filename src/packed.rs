@@ -0,0 +1,402 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Packed pixel formats, as used by GPUs and embedded displays. The bit
+//! layouts follow the Vulkan formats named in the documentation of each type,
+//! with bit 0 being the least significant bit.
+//!
+//! Packing rounds to the nearest representable value, so unpacking to a
+//! channel type with at least as many bits and packing again gives back the
+//! same pixel.
+//!
+//! Colors with 8 or 16 bits per channel are packed into a `u32` or `u64` with
+//! `Rgba::to_packed` and `Rgba::to_packed64`, in a selectable `Layout`.
+
+use Channel;
+use {Rgb, ToRgb, Rgba, ToRgba};
+use half;
+use space::RgbSpace;
+
+/// Extracts the `bits` wide unsigned normalized field at `shift`.
+#[inline]
+fn unorm(packed: u32, shift: u32, bits: u32) -> f64 {
+    let max = (1u32 << bits) - 1;
+    ((packed >> shift) & max) as f64 / max as f64
+}
+
+/// Converts a value in `(0,1)` to a `bits` wide unsigned normalized field,
+/// rounding to the nearest value and saturating. `NaN` becomes zero.
+#[inline]
+fn quantize(value: f64, bits: u32) -> u32 {
+    let max = ((1u32 << bits) - 1) as f64;
+    if value > 0.0 { (value.min(1.0) * max).round() as u32 } else { 0 }
+}
+
+/// Encodes an unsigned float with a 5 bit exponent and `mantissa` mantissa
+/// bits. Negative values become zero.
+#[inline]
+fn ufloat(value: f64, mantissa: u32) -> u32 {
+    // The absolute value clears the sign of a negative NaN, which has no bit
+    // of its own and would spill into the next field
+    if value > 0.0 || value.is_nan() { half::encode(value.abs(), mantissa) } else { 0 }
+}
+
+macro_rules! impl_packed {
+    ($ty: ident, $from: ident, $color: ident) => {
+        impl $ty {
+            #[inline]
+            pub fn $from<T:Channel>(color: &$color<T>) -> $ty {
+                $ty::pack(color.to_rgba::<f64>())
+            }
+        }
+
+        impl ToRgb for $ty {
+            #[inline]
+            fn to_rgb<U:Channel>(&self) -> Rgb<U> {
+                self.to_rgba::<U>().c
+            }
+        }
+
+        impl ToRgba for $ty {
+            #[inline]
+            fn to_rgba<U:Channel>(&self) -> Rgba<U> {
+                let c = self.unpack();
+                Rgba { c: Rgb::new(Channel::from(c[0]), Channel::from(c[1]), Channel::from(c[2])),
+                       a: Channel::from(c[3]) }
+            }
+        }
+    }
+}
+
+/// 16 bit RGB with 5 bits of red and blue and 6 of green
+/// (`R5G6B5_UNORM_PACK16`): red in bits 11-15, green in bits 5-10 and blue in
+/// bits 0-4.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub struct Rgb565(pub u16);
+
+impl Rgb565 {
+    fn pack(c: Rgba<f64>) -> Rgb565 {
+        Rgb565((quantize(c.c.r, 5) << 11 | quantize(c.c.g, 6) << 5 | quantize(c.c.b, 5)) as u16)
+    }
+
+    fn unpack(self) -> [f64; 4] {
+        let p = self.0 as u32;
+        [unorm(p, 11, 5), unorm(p, 5, 6), unorm(p, 0, 5), 1.0]
+    }
+}
+
+impl_packed!(Rgb565, from_rgb, Rgb);
+
+/// 16 bit RGBA with 5 bits per color component and 1 bit of alpha
+/// (`R5G5B5A1_UNORM_PACK16`): red in bits 11-15, green in bits 6-10, blue in
+/// bits 1-5 and alpha in bit 0.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub struct Rgba5551(pub u16);
+
+impl Rgba5551 {
+    fn pack(c: Rgba<f64>) -> Rgba5551 {
+        Rgba5551((quantize(c.c.r, 5) << 11 | quantize(c.c.g, 5) << 6 | quantize(c.c.b, 5) << 1
+                  | quantize(c.a, 1)) as u16)
+    }
+
+    fn unpack(self) -> [f64; 4] {
+        let p = self.0 as u32;
+        [unorm(p, 11, 5), unorm(p, 6, 5), unorm(p, 1, 5), unorm(p, 0, 1)]
+    }
+}
+
+impl_packed!(Rgba5551, from_rgba, Rgba);
+
+/// 16 bit RGBA with 4 bits per component (`R4G4B4A4_UNORM_PACK16`): red in
+/// bits 12-15, green in bits 8-11, blue in bits 4-7 and alpha in bits 0-3.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub struct Rgba4444(pub u16);
+
+impl Rgba4444 {
+    fn pack(c: Rgba<f64>) -> Rgba4444 {
+        Rgba4444((quantize(c.c.r, 4) << 12 | quantize(c.c.g, 4) << 8 | quantize(c.c.b, 4) << 4
+                  | quantize(c.a, 4)) as u16)
+    }
+
+    fn unpack(self) -> [f64; 4] {
+        let p = self.0 as u32;
+        [unorm(p, 12, 4), unorm(p, 8, 4), unorm(p, 4, 4), unorm(p, 0, 4)]
+    }
+}
+
+impl_packed!(Rgba4444, from_rgba, Rgba);
+
+/// 32 bit RGBA with 10 bits per color component and 2 bits of alpha
+/// (`A2B10G10R10_UNORM_PACK32`, or `R10G10B10A2_UNORM` in Direct3D): red in
+/// bits 0-9, green in bits 10-19, blue in bits 20-29 and alpha in bits 30-31.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub struct Rgb10A2(pub u32);
+
+impl Rgb10A2 {
+    fn pack(c: Rgba<f64>) -> Rgb10A2 {
+        Rgb10A2(quantize(c.c.r, 10) | quantize(c.c.g, 10) << 10 | quantize(c.c.b, 10) << 20
+                | quantize(c.a, 2) << 30)
+    }
+
+    fn unpack(self) -> [f64; 4] {
+        let p = self.0;
+        [unorm(p, 0, 10), unorm(p, 10, 10), unorm(p, 20, 10), unorm(p, 30, 2)]
+    }
+}
+
+impl_packed!(Rgb10A2, from_rgba, Rgba);
+
+/// 32 bit RGB of unsigned floats for HDR render targets
+/// (`B10G11R11_UFLOAT_PACK32`): red and green are 11 bit floats with a 6 bit
+/// mantissa, in bits 0-10 and 11-21, and blue a 10 bit float with a 5 bit
+/// mantissa, in bits 22-31. All have a 5 bit exponent biased by 15.
+///
+/// The largest finite value is `65024`. Larger values become infinite and
+/// negative values zero.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub struct R11G11B10F(pub u32);
+
+impl R11G11B10F {
+    fn pack(c: Rgba<f64>) -> R11G11B10F {
+        R11G11B10F(ufloat(c.c.r, 6) | ufloat(c.c.g, 6) << 11 | ufloat(c.c.b, 5) << 22)
+    }
+
+    fn unpack(self) -> [f64; 4] {
+        let p = self.0;
+        [half::decode(p & 0x7FF, 6), half::decode((p >> 11) & 0x7FF, 6), half::decode(p >> 22, 5), 1.0]
+    }
+}
+
+impl_packed!(R11G11B10F, from_rgb, Rgb);
+
+/// 32 bit RGB with 9 bit mantissas and a shared 5 bit exponent biased by 15
+/// (`E5B9G9R9_UFLOAT_PACK32`): red in bits 0-8, green in bits 9-17, blue in
+/// bits 18-26 and the exponent in bits 27-31. The mantissas have no implicit
+/// leading one.
+///
+/// The largest value is `65408`. Larger values saturate and negative values
+/// and `NaN` become zero.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub struct Rgb9E5(pub u32);
+
+impl Rgb9E5 {
+    fn pack(c: Rgba<f64>) -> Rgb9E5 {
+        // As specified by EXT_texture_shared_exponent
+        const MAX: f64 = 511.0 / 512.0 * 65536.0;
+        let clamp = |v: f64| if v > 0.0 { v.min(MAX) } else { 0.0 };
+        let (r, g, b) = (clamp(c.c.r), clamp(c.c.g), clamp(c.c.b));
+        let max = r.max(g).max(b);
+        let mut exponent = if max > 0.0 { max.log2().floor().max(-16.0) as i32 + 16 } else { 0 };
+        if (max / 2f64.powi(exponent - 24) + 0.5).floor() == 512.0 {
+            exponent += 1;
+        }
+        let scale = 2f64.powi(exponent - 24);
+        let mantissa = |v: f64| (v / scale + 0.5).floor() as u32;
+        Rgb9E5(mantissa(r) | mantissa(g) << 9 | mantissa(b) << 18 | (exponent as u32) << 27)
+    }
+
+    fn unpack(self) -> [f64; 4] {
+        let p = self.0;
+        let scale = 2f64.powi((p >> 27) as i32 - 24);
+        [(p & 0x1FF) as f64 * scale, ((p >> 9) & 0x1FF) as f64 * scale, ((p >> 18) & 0x1FF) as f64 * scale, 1.0]
+    }
+}
+
+impl_packed!(Rgb9E5, from_rgb, Rgb);
+
+/// The order of the channels of a color packed into an integer, from the
+/// most significant to the least significant bits. `X` is an unused channel,
+/// which is zero when packing and ignored when unpacking.
+///
+/// In a `u32` each channel takes 8 bits, so `Xrgb` is the common `0x00RRGGBB`
+/// and `Argb` is `0xAARRGGBB`. In a `u64` each channel takes 16 bits.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Layout {
+    Rgba,
+    Argb,
+    Bgra,
+    Abgr,
+    Rgbx,
+    Xrgb,
+}
+
+impl Layout {
+    /// Returns the positions of the red, green, blue and alpha channels,
+    /// counting from the most significant channel.
+    fn positions(self) -> ([u32; 3], Option<u32>) {
+        match self {
+            Layout::Rgba => ([0, 1, 2], Some(3)),
+            Layout::Argb => ([1, 2, 3], Some(0)),
+            Layout::Bgra => ([2, 1, 0], Some(3)),
+            Layout::Abgr => ([3, 2, 1], Some(0)),
+            Layout::Rgbx => ([0, 1, 2], None),
+            Layout::Xrgb => ([1, 2, 3], None),
+        }
+    }
+
+    /// Returns the channels packed into `packed` with `bits` bits each, with
+    /// the alpha at its maximum for the layouts without alpha.
+    #[inline]
+    fn unpack(self, packed: u64, bits: u32) -> [u64; 4] {
+        let (rgb, alpha) = self.positions();
+        let mask = (1 << bits) - 1;
+        let channel = |position: u32| (packed >> ((3 - position) * bits)) & mask;
+        [channel(rgb[0]), channel(rgb[1]), channel(rgb[2]), alpha.map_or(mask, channel)]
+    }
+
+    /// Packs the channels with `bits` bits each.
+    #[inline]
+    fn pack(self, channels: [u64; 4], bits: u32) -> u64 {
+        let (rgb, alpha) = self.positions();
+        let shift = |position: u32| (3 - position) * bits;
+        let packed = channels[0] << shift(rgb[0]) | channels[1] << shift(rgb[1]) | channels[2] << shift(rgb[2]);
+        alpha.map_or(packed, |position| packed | channels[3] << shift(position))
+    }
+}
+
+impl<T:Channel, S:RgbSpace> Rgba<T, S> {
+    /// Unpacks a color with 8 bits per channel from a `u32`.
+    pub fn from_packed(packed: u32, layout: Layout) -> Rgba<T, S> {
+        let c = layout.unpack(packed as u64, 8);
+        Rgba {
+            c: Rgb::with_space(Channel::from(c[0] as u8), Channel::from(c[1] as u8), Channel::from(c[2] as u8),
+                               S::default()),
+            a: Channel::from(c[3] as u8),
+        }
+    }
+
+    /// Packs the color into a `u32` with 8 bits per channel.
+    pub fn to_packed(&self, layout: Layout) -> u32 {
+        layout.pack([self.c.r.to_channel_u8() as u64, self.c.g.to_channel_u8() as u64,
+                     self.c.b.to_channel_u8() as u64, self.a.to_channel_u8() as u64], 8) as u32
+    }
+
+    /// Unpacks a color with 16 bits per channel from a `u64`.
+    pub fn from_packed64(packed: u64, layout: Layout) -> Rgba<T, S> {
+        let c = layout.unpack(packed, 16);
+        Rgba {
+            c: Rgb::with_space(Channel::from(c[0] as u16), Channel::from(c[1] as u16), Channel::from(c[2] as u16),
+                               S::default()),
+            a: Channel::from(c[3] as u16),
+        }
+    }
+
+    /// Packs the color into a `u64` with 16 bits per channel.
+    pub fn to_packed64(&self, layout: Layout) -> u64 {
+        layout.pack([self.c.r.to_channel_u16() as u64, self.c.g.to_channel_u16() as u64,
+                     self.c.b.to_channel_u16() as u64, self.a.to_channel_u16() as u64], 16)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {Rgb, ToRgb, Rgba, ToRgba};
+    use super::*;
+
+    #[test]
+    fn test_layouts() {
+        assert_eq!(Rgb565::from_rgb(&Rgb::new(1.0f32, 0.0, 0.0)), Rgb565(0xF800));
+        assert_eq!(Rgb565::from_rgb(&Rgb::new(0u8, 0xFF, 0)), Rgb565(0x07E0));
+        assert_eq!(Rgba5551::from_rgba(&rgba!(0u8, 0, 0xFF, 0xFF)), Rgba5551(0x003F));
+        assert_eq!(Rgba4444::from_rgba(&rgba!(0x11u8, 0x22, 0x33, 0x44)), Rgba4444(0x1234));
+        assert_eq!(Rgb10A2::from_rgba(&rgba!(1.0f32, 0.0, 0.0, 1.0)), Rgb10A2(0xC000_03FF));
+        assert_eq!(Rgb10A2(0x3FF0_0000).to_rgba::<u16>(), rgba!(0, 0, 0xFFFF, 0));
+        assert_eq!(R11G11B10F::from_rgb(&Rgb::new(1.0f32, 1.0, 1.0)), R11G11B10F(0x1E0 << 22 | 0x3C0 << 11 | 0x3C0));
+        assert_eq!(Rgb9E5::from_rgb(&Rgb::new(1.0f32, 0.5, 0.0)), Rgb9E5(16 << 27 | 0x80 << 9 | 0x100));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        // Every pixel survives unpacking to 8 or 16 bits and packing again
+        for p in 0..=0xFFFF_u16 {
+            assert_eq!(Rgb565::from_rgb(&Rgb565(p).to_rgb::<u8>()), Rgb565(p));
+            assert_eq!(Rgba5551::from_rgba(&Rgba5551(p).to_rgba::<u8>()), Rgba5551(p));
+            assert_eq!(Rgba4444::from_rgba(&Rgba4444(p).to_rgba::<u8>()), Rgba4444(p));
+        }
+        for p in (0..=0xFFFF_FFFF_u32).step_by(0x1_0001) {
+            assert_eq!(Rgb10A2::from_rgba(&Rgb10A2(p).to_rgba::<u16>()), Rgb10A2(p));
+            // Skip the infinite and NaN encodings
+            let f = R11G11B10F(p);
+            if p & 0x7C0 != 0x7C0 && p & (0x7C0 << 11) != 0x7C0 << 11 && p & (0x3E0 << 22) != 0x3E0 << 22 {
+                assert_eq!(R11G11B10F::from_rgb(&f.to_rgb::<f32>()), f);
+            }
+            // Denormalized encodings of the shared exponent are not unique
+            let e = Rgb9E5(p);
+            let c = e.to_rgb::<f64>();
+            assert_eq!(Rgb9E5::from_rgb(&c).to_rgb::<f64>(), c);
+        }
+        for i in 0..256 {
+            let c = Rgb::new(i as u8, 255 - i as u8, (i * 7) as u8);
+            assert_eq!(Rgb9E5::from_rgb(&c).to_rgb::<u8>(), c);
+            // The 5 bit mantissa of blue is only accurate to 1/64
+            let (f, c) = (R11G11B10F::from_rgb(&c).to_rgb::<f64>(), c.to_rgb::<f64>());
+            assert!((f.r - c.r).abs() <= c.r / 128.0 && (f.b - c.b).abs() <= c.b / 64.0, "{:?} {:?}", f, c);
+        }
+    }
+
+    #[test]
+    fn test_hdr() {
+        let c = Rgb::new(1000.0f32, 0.25, -1.0);
+        let f = R11G11B10F::from_rgb(&c).to_rgb::<f32>();
+        assert_eq!(f, Rgb::new(1000.0, 0.25, 0.0));
+        assert_eq!(R11G11B10F::from_rgb(&Rgb::new(1e6f32, 0.0, 0.0)).to_rgb::<f32>().r, f32::INFINITY);
+        let e = Rgb9E5::from_rgb(&c).to_rgb::<f32>();
+        // The small components lose precision to the shared exponent
+        assert_eq!(e.r, 1000.0);
+        assert!(e.g < 2.0 && e.b == 0.0);
+        assert_eq!(Rgb9E5::from_rgb(&Rgb::new(1e9f32, 0.0, 0.0)).to_rgb::<f32>().r, 65408.0);
+        assert_eq!(R11G11B10F::from_rgb(&c).to_rgba::<f32>().a, 1.0);
+    }
+
+    #[test]
+    fn test_nan() {
+        // NaN stays in its own field whatever its sign
+        for &nan in [f32::NAN, -f32::NAN].iter() {
+            assert_eq!(R11G11B10F::from_rgb(&Rgb::new(nan, 0.0, 0.0)).0, 0x7E0);
+            assert_eq!(R11G11B10F::from_rgb(&Rgb::new(0.0, nan, 0.0)).0, 0x7E0 << 11);
+            assert_eq!(R11G11B10F::from_rgb(&Rgb::new(0.0, 0.0, nan)).0, 0x3F0 << 22);
+            assert!(R11G11B10F::from_rgb(&Rgb::new(nan, nan, nan)).to_rgb::<f32>().g.is_nan());
+        }
+    }
+
+    #[test]
+    fn test_layout() {
+        let color = rgba!(0x11u8, 0x22, 0x33, 0x44);
+        assert_eq!(color.to_packed(Layout::Rgba), 0x1122_3344);
+        assert_eq!(color.to_packed(Layout::Argb), 0x4411_2233);
+        assert_eq!(color.to_packed(Layout::Bgra), 0x3322_1144);
+        assert_eq!(color.to_packed(Layout::Abgr), 0x4433_2211);
+        assert_eq!(color.to_packed(Layout::Rgbx), 0x1122_3300);
+        assert_eq!(color.to_packed(Layout::Xrgb), 0x0011_2233);
+        for &layout in [Layout::Rgba, Layout::Argb, Layout::Bgra, Layout::Abgr].iter() {
+            assert_eq!(Rgba::from_packed(color.to_packed(layout), layout), color);
+        }
+        // Layouts without alpha unpack as opaque
+        assert_eq!(Rgba::<u8>::from_packed(0xFF11_2233, Layout::Xrgb), rgba!(0x11, 0x22, 0x33, 0xFF));
+        assert_eq!(Rgba::<f32>::from_packed(0xFF00_00FF, Layout::Rgbx), rgba!(1.0, 0.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn test_layout64() {
+        let color = rgba!(0x1111u16, 0x2222, 0x3333, 0x4444);
+        assert_eq!(color.to_packed64(Layout::Argb), 0x4444_1111_2222_3333);
+        assert_eq!(color.to_packed64(Layout::Abgr), 0x4444_3333_2222_1111);
+        assert_eq!(color.to_packed64(Layout::Rgbx), 0x1111_2222_3333_0000);
+        for &layout in [Layout::Rgba, Layout::Argb, Layout::Bgra, Layout::Abgr].iter() {
+            assert_eq!(Rgba::from_packed64(color.to_packed64(layout), layout), color);
+        }
+        assert_eq!(Rgba::<u8>::from_packed64(0x0000_FFFF_8080_0000, Layout::Xrgb), rgba!(0xFF, 0x80, 0x00, 0xFF));
+    }
+}
@@ -0,0 +1,301 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Colors with premultiplied alpha, where the color components have already
+//! been multiplied by the alpha. This is the representation GPUs and most
+//! compositors expect, and the one in which filtering and interpolation are
+//! correct.
+
+use std::ops::{Mul, Add, Sub, Index, IndexMut};
+use std::slice;
+
+use num;
+use num::traits;
+use num::Saturating;
+
+use Channel;
+use {Rgb, Rgba};
+use space::{RgbSpace, LinearSrgb};
+
+fn cast<T: num::NumCast, U: num::NumCast>(n: T) -> U {
+    traits::cast(n).unwrap()
+}
+
+/// A color whose components are premultiplied by its alpha. Unlike
+/// `AlphaColor`, which has straight alpha, component-wise arithmetic on
+/// `PreAlpha` gives correctly weighted results.
+///
+/// The layout is the same as `AlphaColor`, so a `PreRgba` can be viewed as a
+/// `[T; 4]` slice in `r, g, b, a` order.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[repr(C)]
+pub struct PreAlpha<T, C> { pub c: C, pub a: T }
+
+pub type PreRgba<T, S = LinearSrgb> = PreAlpha<T, Rgb<T, S>>;
+
+/// Returns `value * factor`, with both in the range of the channel. Integer
+/// channels are rounded to the nearest value.
+#[inline]
+fn scale<T:Channel>(value: T, factor: T) -> T {
+    let max: f64 = cast(T::max());
+    if max > 1.0 {
+        let m: i128 = cast(T::max());
+        let (value, factor): (i128, i128) = (cast(value), cast(factor));
+        cast((2 * value * factor + m) / (2 * m))
+    } else {
+        value * factor
+    }
+}
+
+/// Returns `value / factor`, saturating to the range of the channel. Integer
+/// channels are rounded to the nearest value, and dividing by zero gives zero.
+#[inline]
+fn unscale<T:Channel>(value: T, factor: T) -> T {
+    if factor == cast(0) {
+        return cast(0);
+    }
+    let max: f64 = cast(T::max());
+    if max > 1.0 {
+        let m: i128 = cast(T::max());
+        let (value, factor): (i128, i128) = (cast(value), cast(factor));
+        cast(((2 * value * m + factor) / (2 * factor)).min(m))
+    } else {
+        value / factor
+    }
+}
+
+impl<T:Channel, S:RgbSpace> Rgba<T, S> {
+    /// Multiplies the color components by the alpha.
+    pub fn premultiply(&self) -> PreRgba<T, S> {
+        PreAlpha {
+            c: Rgb::with_space(scale(self.c.r, self.a),
+                               scale(self.c.g, self.a),
+                               scale(self.c.b, self.a),
+                               S::default()),
+            a: self.a,
+        }
+    }
+}
+
+impl<T:Channel, S:RgbSpace> PreRgba<T, S> {
+    /// Divides the color components by the alpha, giving back a color with
+    /// straight alpha. A fully transparent color becomes transparent black.
+    pub fn unpremultiply(&self) -> Rgba<T, S> {
+        Rgba {
+            c: Rgb::with_space(unscale(self.c.r, self.a),
+                               unscale(self.c.g, self.a),
+                               unscale(self.c.b, self.a),
+                               S::default()),
+            a: self.a,
+        }
+    }
+
+    /// Linearly interpolates between this color and `other`, weighting the
+    /// colors by their alpha.
+    #[inline]
+    pub fn mix(self, other: PreRgba<T, S>, value: T) -> PreRgba<T, S> {
+        PreAlpha {
+            c: Rgb::with_space(self.c.r.mix(other.c.r, value),
+                               self.c.g.mix(other.c.g, value),
+                               self.c.b.mix(other.c.b, value),
+                               S::default()),
+            a: self.a.mix(other.a, value),
+        }
+    }
+
+    /// Draws this color over `backdrop` with source-over compositing, which
+    /// for premultiplied colors is `src + backdrop * (1 - src.a)`.
+    pub fn over(&self, backdrop: &PreRgba<T, S>) -> PreRgba<T, S> {
+        let inv = self.a.invert_channel();
+        PreAlpha {
+            c: Rgb::with_space(self.c.r + scale(backdrop.c.r, inv),
+                               self.c.g + scale(backdrop.c.g, inv),
+                               self.c.b + scale(backdrop.c.b, inv),
+                               S::default()),
+            a: self.a + scale(backdrop.a, inv),
+        }
+    }
+
+    #[inline]
+    pub fn rgba(&self) -> PreRgba<T, S> {
+        PreAlpha { c: self.c.rgb(), a: self.a }
+    }
+
+    #[inline]
+    pub fn rbga(&self) -> PreRgba<T, S> {
+        PreAlpha { c: self.c.rbg(), a: self.a }
+    }
+
+    #[inline]
+    pub fn grba(&self) -> PreRgba<T, S> {
+        PreAlpha { c: self.c.grb(), a: self.a }
+    }
+
+    #[inline]
+    pub fn gbra(&self) -> PreRgba<T, S> {
+        PreAlpha { c: self.c.gbr(), a: self.a }
+    }
+
+    #[inline]
+    pub fn brga(&self) -> PreRgba<T, S> {
+        PreAlpha { c: self.c.brg(), a: self.a }
+    }
+
+    #[inline]
+    pub fn bgra(&self) -> PreRgba<T, S> {
+        PreAlpha { c: self.c.bgr(), a: self.a }
+    }
+}
+
+impl<T:Channel, C: Mul<Output=C>> Mul for PreAlpha<T,C> {
+    type Output = PreAlpha<T,C>;
+
+    #[inline]
+    fn mul(self, rhs: PreAlpha<T,C>) -> PreAlpha<T,C> {
+        PreAlpha{ c: self.c.mul(rhs.c),
+             a: self.a.normalized_mul(rhs.a) }
+    }
+}
+
+impl<T:Channel + Mul<T,Output=T>, C: Mul<T,Output=C>> Mul<T> for PreAlpha<T,C> {
+    type Output = PreAlpha<T,C>;
+
+    #[inline]
+    fn mul(self, rhs: T) -> PreAlpha<T,C> {
+        PreAlpha{ c: self.c * rhs,
+             a: self.a * rhs }
+    }
+}
+
+impl<T:Channel + Add<T,Output=T>, C: Add<Output=C>> Add for PreAlpha<T,C>{
+    type Output = PreAlpha<T,C>;
+
+    #[inline]
+    fn add(self, rhs: PreAlpha<T,C>) -> PreAlpha<T,C> {
+        PreAlpha{ c: self.c + rhs.c,
+             a: self.a + rhs.a }
+    }
+}
+
+impl<T:Channel + Sub<T,Output=T>, C: Sub<Output=C>> Sub for PreAlpha<T,C>{
+    type Output = PreAlpha<T,C>;
+
+    #[inline]
+    fn sub(self, rhs: PreAlpha<T,C>) -> PreAlpha<T,C> {
+        PreAlpha{ c: self.c - rhs.c,
+             a: self.a - rhs.a }
+    }
+}
+
+impl<T:Channel + Saturating, C: Saturating> Saturating for PreAlpha<T,C>{
+    fn saturating_add(self, v: PreAlpha<T,C>) -> PreAlpha<T,C> {
+        PreAlpha{ c: self.c.saturating_add(v.c),
+              a: self.a.saturating_add(v.a) }
+    }
+
+    fn saturating_sub(self, v: PreAlpha<T,C>) -> PreAlpha<T,C> {
+        PreAlpha{ c: self.c.saturating_sub(v.c),
+              a: self.a.saturating_sub(v.a) }
+    }
+}
+
+impl<T, C: AsRef<[T]>> Index<usize> for PreAlpha<T,C> {
+    type Output = T;
+    fn index(&self, index: usize) -> &T {
+        self.as_ref().index(index)
+    }
+}
+
+impl<T, C: AsRef<[T]> + AsMut<[T]>> IndexMut<usize> for PreAlpha<T,C> {
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        self.as_mut().index_mut(index)
+    }
+}
+
+impl<T, C: AsRef<[T]>> AsRef<[T]> for PreAlpha<T,C> {
+    fn as_ref(&self) -> &[T] {
+        unsafe{ slice::from_raw_parts(&self.c.as_ref()[0], 4) }
+    }
+}
+
+impl<T, C: AsMut<[T]>> AsMut<[T]> for PreAlpha<T,C> {
+    fn as_mut(&mut self) -> &mut [T] {
+        unsafe{ slice::from_raw_parts_mut(&mut self.c.as_mut()[0], 4) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use PorterDuff;
+    use super::PreAlpha;
+
+    #[test]
+    fn test_premultiply() {
+        assert_eq!(rgba!(1.0f32, 0.5, 0.0, 0.5).premultiply(), PreAlpha { c: rgb!(0.5, 0.25, 0.0), a: 0.5 });
+        assert_eq!(rgba!(255u8, 128, 0, 128).premultiply(), PreAlpha { c: rgb!(128, 64, 0), a: 128 });
+        assert_eq!(rgba!(255u8, 128, 0, 0).premultiply(), PreAlpha { c: rgb!(0, 0, 0), a: 0 });
+        assert_eq!(PreAlpha { c: rgb!(0.5f32, 0.25, 0.0), a: 0.5 }.unpremultiply(), rgba!(1.0, 0.5, 0.0, 0.5));
+        assert_eq!(PreAlpha { c: rgb!(10u8, 20, 30), a: 0 }.unpremultiply(), rgba!(0, 0, 0, 0));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        // Opaque colors and colors with enough alpha survive the round trip
+        for c in 0..256 {
+            let color = rgba!(c as u8, 255 - c as u8, 0x80, 0xFF);
+            assert_eq!(color.premultiply().unpremultiply(), color);
+            let color = rgba!(c as u16 * 0x101, 0, 0xFFFF, 0xFFFF);
+            assert_eq!(color.premultiply().unpremultiply(), color);
+        }
+        for a in 1..256 {
+            let color = rgba!(0xFFu8, 0, 0, a as u8);
+            assert_eq!(color.premultiply().unpremultiply(), color);
+        }
+    }
+
+    #[test]
+    fn test_mix() {
+        // Mixing with transparent black only fades the color
+        let red = rgba!(1.0f32, 0.0, 0.0, 1.0).premultiply();
+        let transparent = rgba!(0.0f32, 0.0, 0.0, 0.0).premultiply();
+        assert_eq!(red.mix(transparent, 0.5).unpremultiply(), rgba!(1.0, 0.0, 0.0, 0.5));
+        let sum = red * 0.25 + red * 0.75;
+        assert_eq!(sum, red);
+    }
+
+    #[test]
+    fn test_over() {
+        let src = rgba!(1.0f64, 0.0, 0.0, 0.5);
+        let dst = rgba!(0.0f64, 0.0, 1.0, 0.5);
+        assert_eq!(src.premultiply().over(&dst.premultiply()).unpremultiply(),
+                   src.composite(&dst, PorterDuff::SourceOver));
+        // Rounding the premultiplied colors may be off by one
+        let src = rgba!(200u8, 100, 0, 100);
+        let dst = rgba!(0u8, 50, 250, 255);
+        let (a, b) = (src.premultiply().over(&dst.premultiply()).unpremultiply(), src.over(&dst));
+        for i in 0..4 {
+            assert!((a[i] as i32 - b[i] as i32).abs() <= 1, "{:?} != {:?}", a, b);
+        }
+    }
+
+    #[test]
+    fn test_layout() {
+        let mut color = PreAlpha { c: rgb!(1u8, 2, 3), a: 4 };
+        assert_eq!(color.as_ref(), &[1, 2, 3, 4]);
+        assert_eq!(color[3], 4);
+        color[0] = 5;
+        assert_eq!(color.bgra().as_ref(), &[3, 2, 5, 4]);
+    }
+}
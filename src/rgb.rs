@@ -14,19 +14,32 @@
 // limitations under the License.
 
 use num;
-use num::traits::{self, Float, Zero, Saturating};
+use num::traits::{self, Zero, Saturating};
 use std::ops::{Mul, Div, Add, Sub, Index, IndexMut};
+use std::marker::PhantomData;
 use std::slice;
 
 use angle::*;
 
+use std::cmp::Ordering;
+
 use AlphaColor;
 use {Color, FloatColor};
 use {Channel, FloatChannel};
 use {Hsv, ToHsv};
-
+use {Srgb, ToOklab};
+use space::{RgbSpace, LinearSrgb};
+
+/// An RGB color with linear components in the working space `S`, which
+/// defaults to linear sRGB. Colors in different spaces are distinct types and
+/// have to be converted explicitly with `to_space` before being combined.
+///
+/// The space is a private zero sized tag, so colors are built with `new`,
+/// `with_space` or the `rgb!` macro rather than a struct literal, and
+/// patterns need a trailing `..`, as in `let Rgb { r, g, b, .. } = c;`.
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
-pub struct Rgb<T> { pub r: T, pub g: T, pub b: T }
+#[repr(C)]
+pub struct Rgb<T, S = LinearSrgb> { pub r: T, pub g: T, pub b: T, space: PhantomData<S> }
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub struct Rg<T> { pub r: T, pub g: T }
@@ -35,10 +48,39 @@ fn cast<T: num::NumCast, U: num::NumCast>(n: T) -> U {
     traits::cast(n).unwrap()
 }
 
+impl<T> Rgb<T> {
+    #[inline]
+    pub const fn new(r: T, g: T, b: T) -> Rgb<T> {
+        Rgb { r, g, b, space: PhantomData }
+    }
+}
+
 impl<T:Channel> Rgb<T> {
+    /// Returns the named color closest to this one, along with its name. The
+    /// colors are compared by their Euclidean distance in Oklab.
+    pub fn nearest_named(&self) -> (&'static str, Srgb<u8>) {
+        let target = self.to_oklab::<f64>();
+        let distance = |c: &Srgb<u8>| {
+            let c = c.to_oklab::<f64>();
+            (c.l - target.l).powi(2) + (c.a - target.a).powi(2) + (c.b - target.b).powi(2)
+        };
+        consts::iter()
+            .min_by(|&(_, a), &(_, b)| distance(&a).partial_cmp(&distance(&b)).unwrap_or(Ordering::Equal))
+            .unwrap()
+    }
+}
+
+impl<T:Channel, S> Rgb<T, S> {
+    /// Creates a color in the working space given by the `space` tag, such as
+    /// `Rgb::with_space(1.0, 0.5, 0.0, space::DisplayP3)`.
+    #[inline]
+    pub fn with_space(r: T, g: T, b: T, _space: S) -> Rgb<T, S> {
+        Rgb::from_components(r, g, b)
+    }
+
     #[inline]
-    pub fn new(r: T, g: T, b: T) -> Rgb<T> {
-        Rgb { r: r, g: g, b: b }
+    fn from_components(r: T, g: T, b: T) -> Rgb<T, S> {
+        Rgb { r, g, b, space: PhantomData }
     }
     
     #[inline]
@@ -72,89 +114,89 @@ impl<T:Channel> Rgb<T> {
     }
     
     #[inline]
-    pub fn rgb(&self) -> Rgb<T> {
-        Rgb{r: self.r, g: self.g, b: self.b}
+    pub fn rgb(&self) -> Rgb<T, S> {
+        Rgb::from_components(self.r, self.g, self.b)
     }
     
     #[inline]
-    pub fn rbg(&self) -> Rgb<T> {
-        Rgb{r: self.r, g: self.b, b: self.g}
+    pub fn rbg(&self) -> Rgb<T, S> {
+        Rgb::from_components(self.r, self.b, self.g)
     }
     
     #[inline]
-    pub fn bgr(&self) -> Rgb<T> {
-        Rgb{r: self.b, g: self.g, b: self.r}
+    pub fn bgr(&self) -> Rgb<T, S> {
+        Rgb::from_components(self.b, self.g, self.r)
     }
     
     #[inline]
-    pub fn brg(&self) -> Rgb<T> {
-        Rgb{r: self.b, g: self.r, b: self.g}
+    pub fn brg(&self) -> Rgb<T, S> {
+        Rgb::from_components(self.b, self.r, self.g)
     }
     
     #[inline]
-    pub fn grb(&self) -> Rgb<T> {
-        Rgb{r: self.g, g: self.r, b: self.b}
+    pub fn grb(&self) -> Rgb<T, S> {
+        Rgb::from_components(self.g, self.r, self.b)
     }
     
     #[inline]
-    pub fn gbr(&self) -> Rgb<T> {
-        Rgb{r: self.g, g: self.b, b: self.r}
+    pub fn gbr(&self) -> Rgb<T, S> {
+        Rgb::from_components(self.g, self.b, self.r)
     }
 }
 
 #[macro_export]
 macro_rules! rgb{
     ( $r: expr, $g: expr, $b: expr ) => {
-        Rgb{ r: $r, g: $g, b: $b } 
+        $crate::Rgb::new($r, $g, $b)
     };
     ( $rg: expr, $b: expr ) => {
-        Rgb{ r: $rg.r, g: $rg.g, b: $b } 
+        $crate::Rgb::new($rg.r, $rg.g, $b)
     };
     ( $r: expr, $gb: expr ) => {
-        Rgb{ r: $r, g: $gb.r, b: $gb.g } 
+        $crate::Rgb::new($r, $gb.r, $gb.g)
     };
 }
 
-impl<T:Channel> Color<T> for Rgb<T> {
+impl<T:Channel, S:RgbSpace> Color<T> for Rgb<T, S> {
     /// Clamps the components of the color to the range `(lo,hi)`.
     #[inline]
-    fn clamp_s(self, lo: T, hi: T) -> Rgb<T> {
-        Rgb::new(self.r.clamp(lo, hi),
-                 self.g.clamp(lo, hi),
-                 self.b.clamp(lo, hi))
+    fn clamp_s(self, lo: T, hi: T) -> Rgb<T, S> {
+        Rgb::from_components(self.r.clamp(lo, hi),
+                             self.g.clamp(lo, hi),
+                             self.b.clamp(lo, hi))
     }
 
     /// Clamps the components of the color component-wise between `lo` and `hi`.
     #[inline]
-    fn clamp_c(self, lo: Rgb<T>, hi: Rgb<T>) -> Rgb<T> {
-        Rgb::new(self.r.clamp(lo.r, hi.r),
-                 self.g.clamp(lo.g, hi.g),
-                 self.b.clamp(lo.b, hi.b))
+    fn clamp_c(self, lo: Rgb<T, S>, hi: Rgb<T, S>) -> Rgb<T, S> {
+        Rgb::from_components(self.r.clamp(lo.r, hi.r),
+                             self.g.clamp(lo.g, hi.g),
+                             self.b.clamp(lo.b, hi.b))
     }
 
     /// Inverts the color.
     #[inline]
-    fn inverse(self) -> Rgb<T> {
-        Rgb::new(self.r.invert_channel(),
-                 self.g.invert_channel(),
-                 self.b.invert_channel())
+    fn inverse(self) -> Rgb<T, S> {
+        Rgb::from_components(self.r.invert_channel(),
+                             self.g.invert_channel(),
+                             self.b.invert_channel())
     }
     
     #[inline]
     fn mix(self, other: Self, value: T) -> Self {
-        rgb!(self.r.mix(other.r, value),
-             self.g.mix(other.g, value),
-             self.b.mix(other.b, value)) 
+        Rgb::from_components(self.r.mix(other.r, value),
+                             self.g.mix(other.g, value),
+                             self.b.mix(other.b, value))
     }
 }
 
-impl<T:FloatChannel> FloatColor<T> for Rgb<T> {
+impl<T:FloatChannel, S:RgbSpace> FloatColor<T> for Rgb<T, S> {
     /// Clamps the components of the color to the range `(0,1)`.
     #[inline]
-    fn saturate(self) -> Rgb<T> {
-        Rgb::new(self.r.saturate(),
-                 self.g.saturate(),
-                 self.b.saturate())
+    fn saturate(self) -> Rgb<T, S> {
+        Rgb::from_components(self.r.saturate(),
+                             self.g.saturate(),
+                             self.b.saturate())
     }
 }
 
@@ -162,19 +204,6 @@ pub trait ToRgb {
     fn to_rgb<U:Channel>(&self) -> Rgb<U>;
 }
 
-impl ToRgb for u32 {
-    #[inline]
-    fn to_rgb<U:Channel>(&self) -> Rgb<U> {
-        let r: u8 = cast((*self >> 16) & 0xff);
-        let g: u8 = cast((*self >> 8) & 0xff);
-        let b: u8 = cast((*self >> 0) & 0xff); 
-        let r: U = Channel::from(r);
-        let g: U = Channel::from(g);
-        let b: U = Channel::from(b); 
-        rgb!(r, g, b)
-    }
-}
-
 impl<T:Clone + Channel> ToRgb for Rgb<T> {
     #[inline]
     fn to_rgb<U:Channel>(&self) -> Rgb<U> {
@@ -191,107 +220,107 @@ impl<T,C: ToRgb> ToRgb for AlphaColor<T, C> {
     }
 }
 
-impl<T:Channel> Mul for Rgb<T> {
-    type Output = Rgb<T>;
+impl<T:Channel, S:RgbSpace> Mul for Rgb<T, S> {
+    type Output = Rgb<T, S>;
 
     #[inline]
-    fn mul(self, rhs: Rgb<T>) -> Rgb<T> {
-        Rgb::new(self.r.normalized_mul(rhs.r),
-                 self.g.normalized_mul(rhs.g),
-                 self.b.normalized_mul(rhs.b))
+    fn mul(self, rhs: Rgb<T, S>) -> Rgb<T, S> {
+        Rgb::from_components(self.r.normalized_mul(rhs.r),
+                             self.g.normalized_mul(rhs.g),
+                             self.b.normalized_mul(rhs.b))
     }
 }
 
-impl<T:Channel + Mul<T,Output=T>> Mul<T> for Rgb<T> {
-    type Output = Rgb<T>;
+impl<T:Channel + Mul<T,Output=T>, S:RgbSpace> Mul<T> for Rgb<T, S> {
+    type Output = Rgb<T, S>;
 
     #[inline]
-    fn mul(self, rhs: T) -> Rgb<T> {
-        Rgb::new(self.r * rhs,
-                 self.g * rhs,
-                 self.b * rhs)
+    fn mul(self, rhs: T) -> Rgb<T, S> {
+        Rgb::from_components(self.r * rhs,
+                             self.g * rhs,
+                             self.b * rhs)
     }
 }
 
 
-impl<T:Channel> Div for Rgb<T> {
-    type Output = Rgb<T>;
+impl<T:Channel, S:RgbSpace> Div for Rgb<T, S> {
+    type Output = Rgb<T, S>;
 
     #[inline]
-    fn div(self, rhs: Rgb<T>) -> Rgb<T> {
-        Rgb::new(self.r.normalized_div(rhs.r),
-                 self.g.normalized_div(rhs.g),
-                 self.b.normalized_div(rhs.b))
+    fn div(self, rhs: Rgb<T, S>) -> Rgb<T, S> {
+        Rgb::from_components(self.r.normalized_div(rhs.r),
+                             self.g.normalized_div(rhs.g),
+                             self.b.normalized_div(rhs.b))
     }
 }
 
-impl<T:Channel + Div<T,Output=T>> Div<T> for Rgb<T> {
-    type Output = Rgb<T>;
+impl<T:Channel + Div<T,Output=T>, S:RgbSpace> Div<T> for Rgb<T, S> {
+    type Output = Rgb<T, S>;
 
     #[inline]
-    fn div(self, rhs: T) -> Rgb<T> {
-        Rgb::new(self.r / rhs,
-                 self.g / rhs,
-                 self.b / rhs)
+    fn div(self, rhs: T) -> Rgb<T, S> {
+        Rgb::from_components(self.r / rhs,
+                             self.g / rhs,
+                             self.b / rhs)
     }
 }
 
-impl<T:Channel + Add<T,Output=T>> Add for Rgb<T> {
-    type Output = Rgb<T>;
+impl<T:Channel + Add<T,Output=T>, S:RgbSpace> Add for Rgb<T, S> {
+    type Output = Rgb<T, S>;
 
     #[inline]
-    fn add(self, rhs: Rgb<T>) -> Rgb<T> {
-        Rgb::new(self.r + rhs.r,
-                 self.g + rhs.g,
-                 self.b + rhs.b)
+    fn add(self, rhs: Rgb<T, S>) -> Rgb<T, S> {
+        Rgb::from_components(self.r + rhs.r,
+                             self.g + rhs.g,
+                             self.b + rhs.b)
     }
 }
 
-impl<T:Channel + Sub<T,Output=T>> Sub for Rgb<T> {
-    type Output = Rgb<T>;
+impl<T:Channel + Sub<T,Output=T>, S:RgbSpace> Sub for Rgb<T, S> {
+    type Output = Rgb<T, S>;
 
     #[inline]
-    fn sub(self, rhs: Rgb<T>) -> Rgb<T> {
-        Rgb::new(self.r - rhs.r,
-                 self.g - rhs.g,
-                 self.b - rhs.b)
+    fn sub(self, rhs: Rgb<T, S>) -> Rgb<T, S> {
+        Rgb::from_components(self.r - rhs.r,
+                             self.g - rhs.g,
+                             self.b - rhs.b)
     }
 }
 
-impl<T:Channel + Saturating> Saturating for Rgb<T> {
-    fn saturating_add(self, v: Rgb<T>) -> Rgb<T> {
-        Rgb::new(self.r.saturating_add(v.r),
+impl<T:Channel + Saturating, S:RgbSpace> Saturating for Rgb<T, S> {
+    fn saturating_add(self, v: Rgb<T, S>) -> Rgb<T, S> {
+        Rgb::from_components(self.r.saturating_add(v.r),
             self.g.saturating_add(v.g),
             self.b.saturating_add(v.b))
     }
     
-    fn saturating_sub(self, v: Rgb<T>) -> Rgb<T> {
-        Rgb::new(self.r.saturating_sub(v.r),
+    fn saturating_sub(self, v: Rgb<T, S>) -> Rgb<T, S> {
+        Rgb::from_components(self.r.saturating_sub(v.r),
             self.g.saturating_sub(v.g),
             self.b.saturating_sub(v.b))
     }
 }
 
-impl<T> Index<usize> for Rgb<T> {
+impl<T, S> Index<usize> for Rgb<T, S> {
     type Output = T;
-    fn index<'a>(&'a self, index: usize) -> &'a T {
+    fn index(&self, index: usize) -> &T {
         self.as_ref().index(index)
     }
 }
 
-impl<T> IndexMut<usize> for Rgb<T> {
-    fn index_mut<'a>(&'a mut self, index: usize) -> &'a mut T {
+impl<T, S> IndexMut<usize> for Rgb<T, S> {
+    fn index_mut(&mut self, index: usize) -> &mut T {
         self.as_mut().index_mut(index)
     }
 }
 
-impl<T> AsRef<[T]> for Rgb<T> {
+impl<T, S> AsRef<[T]> for Rgb<T, S> {
     fn as_ref(&self) -> &[T] {
         unsafe{ slice::from_raw_parts(&self.r, 3) }
     }
 }
 
-impl<T> AsMut<[T]> for Rgb<T> {
+impl<T, S> AsMut<[T]> for Rgb<T, S> {
     fn as_mut(&mut self) -> &mut [T] {
         unsafe{ slice::from_raw_parts_mut(&mut self.r, 3) }
     }
@@ -326,156 +355,366 @@ impl<T:Channel> ToHsv for Rgb<T> {
     }
 }
 
-/// SVG 1.0 color constants: http://www.w3.org/TR/SVG/types.html#ColorKeywords
+/// CSS Color Module Level 4 named colors, which are the SVG 1.0 color
+/// keywords plus `rebeccapurple`: https://www.w3.org/TR/css-color-4/#named-colors
+///
+/// The keywords are defined as sRGB, so they are stored gamma encoded as
+/// `Srgb<u8>`. Use `to_rgb` to get the linear color.
 pub mod consts {
-    use Rgb;
-
-    pub static ALICEBLUE:               Rgb<u8> = Rgb { r: 0xF0, g: 0xF8, b: 0xFF };
-    pub static ANTIQUEWHITE:            Rgb<u8> = Rgb { r: 0xFA, g: 0xEB, b: 0xD7 };
-    pub static AQUA:                    Rgb<u8> = Rgb { r: 0x00, g: 0xFF, b: 0xFF };
-    pub static AQUAMARINE:              Rgb<u8> = Rgb { r: 0x7F, g: 0xFF, b: 0xD4 };
-    pub static AZURE:                   Rgb<u8> = Rgb { r: 0xF0, g: 0xFF, b: 0xFF };
-    pub static BEIGE:                   Rgb<u8> = Rgb { r: 0xF5, g: 0xF5, b: 0xDC };
-    pub static BISQUE:                  Rgb<u8> = Rgb { r: 0xFF, g: 0xE4, b: 0xC4 };
-    pub static BLACK:                   Rgb<u8> = Rgb { r: 0x00, g: 0x00, b: 0x00 };
-    pub static BLANCHEDALMOND:          Rgb<u8> = Rgb { r: 0xFF, g: 0xEB, b: 0xCD };
-    pub static BLUE:                    Rgb<u8> = Rgb { r: 0x00, g: 0x00, b: 0xFF };
-    pub static BLUEVIOLET:              Rgb<u8> = Rgb { r: 0x8A, g: 0x2B, b: 0xE2 };
-    pub static BROWN:                   Rgb<u8> = Rgb { r: 0xA5, g: 0x2A, b: 0x2A };
-    pub static BURLYWOOD:               Rgb<u8> = Rgb { r: 0xDE, g: 0xB8, b: 0x87 };
-    pub static CADETBLUE:               Rgb<u8> = Rgb { r: 0x5F, g: 0x9E, b: 0xA0 };
-    pub static CHARTREUSE:              Rgb<u8> = Rgb { r: 0x7F, g: 0xFF, b: 0x00 };
-    pub static CHOCOLATE:               Rgb<u8> = Rgb { r: 0xD2, g: 0x69, b: 0x1E };
-    pub static CORAL:                   Rgb<u8> = Rgb { r: 0xFF, g: 0x7F, b: 0x50 };
-    pub static CORNFLOWERBLUE:          Rgb<u8> = Rgb { r: 0x64, g: 0x95, b: 0xED };
-    pub static CORNSILK:                Rgb<u8> = Rgb { r: 0xFF, g: 0xF8, b: 0xDC };
-    pub static CRIMSON:                 Rgb<u8> = Rgb { r: 0xDC, g: 0x14, b: 0x3C };
-    pub static CYAN:                    Rgb<u8> = Rgb { r: 0x00, g: 0xFF, b: 0xFF };
-    pub static DARKBLUE:                Rgb<u8> = Rgb { r: 0x00, g: 0x00, b: 0x8B };
-    pub static DARKCYAN:                Rgb<u8> = Rgb { r: 0x00, g: 0x8B, b: 0x8B };
-    pub static DARKGOLDENROD:           Rgb<u8> = Rgb { r: 0xB8, g: 0x86, b: 0x0B };
-    pub static DARKGRAY:                Rgb<u8> = Rgb { r: 0xA9, g: 0xA9, b: 0xA9 };
-    pub static DARKGREEN:               Rgb<u8> = Rgb { r: 0x00, g: 0x64, b: 0x00 };
-    pub static DARKKHAKI:               Rgb<u8> = Rgb { r: 0xBD, g: 0xB7, b: 0x6B };
-    pub static DARKMAGENTA:             Rgb<u8> = Rgb { r: 0x8B, g: 0x00, b: 0x8B };
-    pub static DARKOLIVEGREEN:          Rgb<u8> = Rgb { r: 0x55, g: 0x6B, b: 0x2F };
-    pub static DARKORANGE:              Rgb<u8> = Rgb { r: 0xFF, g: 0x8C, b: 0x00 };
-    pub static DARKORCHID:              Rgb<u8> = Rgb { r: 0x99, g: 0x32, b: 0xCC };
-    pub static DARKRED:                 Rgb<u8> = Rgb { r: 0x8B, g: 0x00, b: 0x00 };
-    pub static DARKSALMON:              Rgb<u8> = Rgb { r: 0xE9, g: 0x96, b: 0x7A };
-    pub static DARKSEAGREEN:            Rgb<u8> = Rgb { r: 0x8F, g: 0xBC, b: 0x8F };
-    pub static DARKSLATEBLUE:           Rgb<u8> = Rgb { r: 0x48, g: 0x3D, b: 0x8B };
-    pub static DARKSLATEGRAY:           Rgb<u8> = Rgb { r: 0x2F, g: 0x4F, b: 0x4F };
-    pub static DARKTURQUOISE:           Rgb<u8> = Rgb { r: 0x00, g: 0xCE, b: 0xD1 };
-    pub static DARKVIOLET:              Rgb<u8> = Rgb { r: 0x94, g: 0x00, b: 0xD3 };
-    pub static DEEPPINK:                Rgb<u8> = Rgb { r: 0xFF, g: 0x14, b: 0x93 };
-    pub static DEEPSKYBLUE:             Rgb<u8> = Rgb { r: 0x00, g: 0xBF, b: 0xFF };
-    pub static DIMGRAY:                 Rgb<u8> = Rgb { r: 0x69, g: 0x69, b: 0x69 };
-    pub static DODGERBLUE:              Rgb<u8> = Rgb { r: 0x1E, g: 0x90, b: 0xFF };
-    pub static FIREBRICK:               Rgb<u8> = Rgb { r: 0xB2, g: 0x22, b: 0x22 };
-    pub static FLORALWHITE:             Rgb<u8> = Rgb { r: 0xFF, g: 0xFA, b: 0xF0 };
-    pub static FORESTGREEN:             Rgb<u8> = Rgb { r: 0x22, g: 0x8B, b: 0x22 };
-    pub static FUCHSIA:                 Rgb<u8> = Rgb { r: 0xFF, g: 0x00, b: 0xFF };
-    pub static GAINSBORO:               Rgb<u8> = Rgb { r: 0xDC, g: 0xDC, b: 0xDC };
-    pub static GHOSTWHITE:              Rgb<u8> = Rgb { r: 0xF8, g: 0xF8, b: 0xFF };
-    pub static GOLD:                    Rgb<u8> = Rgb { r: 0xFF, g: 0xD7, b: 0x00 };
-    pub static GOLDENROD:               Rgb<u8> = Rgb { r: 0xDA, g: 0xA5, b: 0x20 };
-    pub static GRAY:                    Rgb<u8> = Rgb { r: 0x80, g: 0x80, b: 0x80 };
-    pub static GREEN:                   Rgb<u8> = Rgb { r: 0x00, g: 0x80, b: 0x00 };
-    pub static GREENYELLOW:             Rgb<u8> = Rgb { r: 0xAD, g: 0xFF, b: 0x2F };
-    pub static HONEYDEW:                Rgb<u8> = Rgb { r: 0xF0, g: 0xFF, b: 0xF0 };
-    pub static HOTPINK:                 Rgb<u8> = Rgb { r: 0xFF, g: 0x69, b: 0xB4 };
-    pub static INDIANRED:               Rgb<u8> = Rgb { r: 0xCD, g: 0x5C, b: 0x5C };
-    pub static INDIGO:                  Rgb<u8> = Rgb { r: 0x4B, g: 0x00, b: 0x82 };
-    pub static IVORY:                   Rgb<u8> = Rgb { r: 0xFF, g: 0xFF, b: 0xF0 };
-    pub static KHAKI:                   Rgb<u8> = Rgb { r: 0xF0, g: 0xE6, b: 0x8C };
-    pub static LAVENDER:                Rgb<u8> = Rgb { r: 0xE6, g: 0xE6, b: 0xFA };
-    pub static LAVENDERBLUSH:           Rgb<u8> = Rgb { r: 0xFF, g: 0xF0, b: 0xF5 };
-    pub static LAWNGREEN:               Rgb<u8> = Rgb { r: 0x7C, g: 0xFC, b: 0x00 };
-    pub static LEMONCHIFFON:            Rgb<u8> = Rgb { r: 0xFF, g: 0xFA, b: 0xCD };
-    pub static LIGHTBLUE:               Rgb<u8> = Rgb { r: 0xAD, g: 0xD8, b: 0xE6 };
-    pub static LIGHTCORAL:              Rgb<u8> = Rgb { r: 0xF0, g: 0x80, b: 0x80 };
-    pub static LIGHTCYAN:               Rgb<u8> = Rgb { r: 0xE0, g: 0xFF, b: 0xFF };
-    pub static LIGHTGOLDENRODYELLOW:    Rgb<u8> = Rgb { r: 0xFA, g: 0xFA, b: 0xD2 };
-    pub static LIGHTGREEN:              Rgb<u8> = Rgb { r: 0x90, g: 0xEE, b: 0x90 };
-    pub static LIGHTGREY:               Rgb<u8> = Rgb { r: 0xD3, g: 0xD3, b: 0xD3 };
-    pub static LIGHTPINK:               Rgb<u8> = Rgb { r: 0xFF, g: 0xB6, b: 0xC1 };
-    pub static LIGHTSALMON:             Rgb<u8> = Rgb { r: 0xFF, g: 0xA0, b: 0x7A };
-    pub static LIGHTSEAGREEN:           Rgb<u8> = Rgb { r: 0x20, g: 0xB2, b: 0xAA };
-    pub static LIGHTSKYBLUE:            Rgb<u8> = Rgb { r: 0x87, g: 0xCE, b: 0xFA };
-    pub static LIGHTSLATEGRAY:          Rgb<u8> = Rgb { r: 0x77, g: 0x88, b: 0x99 };
-    pub static LIGHTSTEELBLUE:          Rgb<u8> = Rgb { r: 0xB0, g: 0xC4, b: 0xDE };
-    pub static LIGHTYELLOW:             Rgb<u8> = Rgb { r: 0xFF, g: 0xFF, b: 0xE0 };
-    pub static LIME:                    Rgb<u8> = Rgb { r: 0x00, g: 0xFF, b: 0x00 };
-    pub static LIMEGREEN:               Rgb<u8> = Rgb { r: 0x32, g: 0xCD, b: 0x32 };
-    pub static LINEN:                   Rgb<u8> = Rgb { r: 0xFA, g: 0xF0, b: 0xE6 };
-    pub static MAGENTA:                 Rgb<u8> = Rgb { r: 0xFF, g: 0x00, b: 0xFF };
-    pub static MAROON:                  Rgb<u8> = Rgb { r: 0x80, g: 0x00, b: 0x00 };
-    pub static MEDIUMAQUAMARINE:        Rgb<u8> = Rgb { r: 0x66, g: 0xCD, b: 0xAA };
-    pub static MEDIUMBLUE:              Rgb<u8> = Rgb { r: 0x00, g: 0x00, b: 0xCD };
-    pub static MEDIUMORCHID:            Rgb<u8> = Rgb { r: 0xBA, g: 0x55, b: 0xD3 };
-    pub static MEDIUMPURPLE:            Rgb<u8> = Rgb { r: 0x93, g: 0x70, b: 0xDB };
-    pub static MEDIUMSEAGREEN:          Rgb<u8> = Rgb { r: 0x3C, g: 0xB3, b: 0x71 };
-    pub static MEDIUMSLATEBLUE:         Rgb<u8> = Rgb { r: 0x7B, g: 0x68, b: 0xEE };
-    pub static MEDIUMSPRINGGREEN:       Rgb<u8> = Rgb { r: 0x00, g: 0xFA, b: 0x9A };
-    pub static MEDIUMTURQUOISE:         Rgb<u8> = Rgb { r: 0x48, g: 0xD1, b: 0xCC };
-    pub static MEDIUMVIOLETRED:         Rgb<u8> = Rgb { r: 0xC7, g: 0x15, b: 0x85 };
-    pub static MIDNIGHTBLUE:            Rgb<u8> = Rgb { r: 0x19, g: 0x19, b: 0x70 };
-    pub static MINTCREAM:               Rgb<u8> = Rgb { r: 0xF5, g: 0xFF, b: 0xFA };
-    pub static MISTYROSE:               Rgb<u8> = Rgb { r: 0xFF, g: 0xE4, b: 0xE1 };
-    pub static MOCCASIN:                Rgb<u8> = Rgb { r: 0xFF, g: 0xE4, b: 0xB5 };
-    pub static NAVAJOWHITE:             Rgb<u8> = Rgb { r: 0xFF, g: 0xDE, b: 0xAD };
-    pub static NAVY:                    Rgb<u8> = Rgb { r: 0x00, g: 0x00, b: 0x80 };
-    pub static OLDLACE:                 Rgb<u8> = Rgb { r: 0xFD, g: 0xF5, b: 0xE6 };
-    pub static OLIVE:                   Rgb<u8> = Rgb { r: 0x80, g: 0x80, b: 0x00 };
-    pub static OLIVEDRAB:               Rgb<u8> = Rgb { r: 0x6B, g: 0x8E, b: 0x23 };
-    pub static ORANGE:                  Rgb<u8> = Rgb { r: 0xFF, g: 0xA5, b: 0x00 };
-    pub static ORANGERED:               Rgb<u8> = Rgb { r: 0xFF, g: 0x45, b: 0x00 };
-    pub static ORCHID:                  Rgb<u8> = Rgb { r: 0xDA, g: 0x70, b: 0xD6 };
-    pub static PALEGOLDENROD:           Rgb<u8> = Rgb { r: 0xEE, g: 0xE8, b: 0xAA };
-    pub static PALEGREEN:               Rgb<u8> = Rgb { r: 0x98, g: 0xFB, b: 0x98 };
-    pub static PALEVIOLETRED:           Rgb<u8> = Rgb { r: 0xDB, g: 0x70, b: 0x93 };
-    pub static PAPAYAWHIP:              Rgb<u8> = Rgb { r: 0xFF, g: 0xEF, b: 0xD5 };
-    pub static PEACHPUFF:               Rgb<u8> = Rgb { r: 0xFF, g: 0xDA, b: 0xB9 };
-    pub static PERU:                    Rgb<u8> = Rgb { r: 0xCD, g: 0x85, b: 0x3F };
-    pub static PINK:                    Rgb<u8> = Rgb { r: 0xFF, g: 0xC0, b: 0xCB };
-    pub static PLUM:                    Rgb<u8> = Rgb { r: 0xDD, g: 0xA0, b: 0xDD };
-    pub static POWDERBLUE:              Rgb<u8> = Rgb { r: 0xB0, g: 0xE0, b: 0xE6 };
-    pub static PURPLE:                  Rgb<u8> = Rgb { r: 0x80, g: 0x00, b: 0x80 };
-    pub static RED:                     Rgb<u8> = Rgb { r: 0xFF, g: 0x00, b: 0x00 };
-    pub static ROSYBROWN:               Rgb<u8> = Rgb { r: 0xBC, g: 0x8F, b: 0x8F };
-    pub static ROYALBLUE:               Rgb<u8> = Rgb { r: 0x41, g: 0x69, b: 0xE1 };
-    pub static SADDLEBROWN:             Rgb<u8> = Rgb { r: 0x8B, g: 0x45, b: 0x13 };
-    pub static SALMON:                  Rgb<u8> = Rgb { r: 0xFA, g: 0x80, b: 0x72 };
-    pub static SANDYBROWN:              Rgb<u8> = Rgb { r: 0xFA, g: 0xA4, b: 0x60 };
-    pub static SEAGREEN:                Rgb<u8> = Rgb { r: 0x2E, g: 0x8B, b: 0x57 };
-    pub static SEASHELL:                Rgb<u8> = Rgb { r: 0xFF, g: 0xF5, b: 0xEE };
-    pub static SIENNA:                  Rgb<u8> = Rgb { r: 0xA0, g: 0x52, b: 0x2D };
-    pub static SILVER:                  Rgb<u8> = Rgb { r: 0xC0, g: 0xC0, b: 0xC0 };
-    pub static SKYBLUE:                 Rgb<u8> = Rgb { r: 0x87, g: 0xCE, b: 0xEB };
-    pub static SLATEBLUE:               Rgb<u8> = Rgb { r: 0x6A, g: 0x5A, b: 0xCD };
-    pub static SLATEGRAY:               Rgb<u8> = Rgb { r: 0x70, g: 0x80, b: 0x90 };
-    pub static SNOW:                    Rgb<u8> = Rgb { r: 0xFF, g: 0xFA, b: 0xFA };
-    pub static SPRINGGREEN:             Rgb<u8> = Rgb { r: 0x00, g: 0xFF, b: 0x7F };
-    pub static STEELBLUE:               Rgb<u8> = Rgb { r: 0x46, g: 0x82, b: 0xB4 };
-    pub static TAN:                     Rgb<u8> = Rgb { r: 0xD2, g: 0xB4, b: 0x8C };
-    pub static TEAL:                    Rgb<u8> = Rgb { r: 0x00, g: 0x80, b: 0x80 };
-    pub static THISTLE:                 Rgb<u8> = Rgb { r: 0xD8, g: 0xBF, b: 0xD8 };
-    pub static TOMATO:                  Rgb<u8> = Rgb { r: 0xFF, g: 0x63, b: 0x47 };
-    pub static TURQUOISE:               Rgb<u8> = Rgb { r: 0x40, g: 0xE0, b: 0xD0 };
-    pub static VIOLET:                  Rgb<u8> = Rgb { r: 0xEE, g: 0x82, b: 0xEE };
-    pub static WHEAT:                   Rgb<u8> = Rgb { r: 0xF5, g: 0xDE, b: 0xB3 };
-    pub static WHITE:                   Rgb<u8> = Rgb { r: 0xFF, g: 0xFF, b: 0xFF };
-    pub static WHITESMOKE:              Rgb<u8> = Rgb { r: 0xF5, g: 0xF5, b: 0xF5 };
-    pub static YELLOW:                  Rgb<u8> = Rgb { r: 0xFF, g: 0xFF, b: 0x00 };
-    pub static YELLOWGREEN:             Rgb<u8> = Rgb { r: 0x9A, g: 0xCD, b: 0x32 };
+    use std::slice;
+    use Srgb;
+
+    pub static ALICEBLUE:               Srgb<u8> = Srgb::new(0xF0, 0xF8, 0xFF);
+    pub static ANTIQUEWHITE:            Srgb<u8> = Srgb::new(0xFA, 0xEB, 0xD7);
+    pub static AQUA:                    Srgb<u8> = Srgb::new(0x00, 0xFF, 0xFF);
+    pub static AQUAMARINE:              Srgb<u8> = Srgb::new(0x7F, 0xFF, 0xD4);
+    pub static AZURE:                   Srgb<u8> = Srgb::new(0xF0, 0xFF, 0xFF);
+    pub static BEIGE:                   Srgb<u8> = Srgb::new(0xF5, 0xF5, 0xDC);
+    pub static BISQUE:                  Srgb<u8> = Srgb::new(0xFF, 0xE4, 0xC4);
+    pub static BLACK:                   Srgb<u8> = Srgb::new(0x00, 0x00, 0x00);
+    pub static BLANCHEDALMOND:          Srgb<u8> = Srgb::new(0xFF, 0xEB, 0xCD);
+    pub static BLUE:                    Srgb<u8> = Srgb::new(0x00, 0x00, 0xFF);
+    pub static BLUEVIOLET:              Srgb<u8> = Srgb::new(0x8A, 0x2B, 0xE2);
+    pub static BROWN:                   Srgb<u8> = Srgb::new(0xA5, 0x2A, 0x2A);
+    pub static BURLYWOOD:               Srgb<u8> = Srgb::new(0xDE, 0xB8, 0x87);
+    pub static CADETBLUE:               Srgb<u8> = Srgb::new(0x5F, 0x9E, 0xA0);
+    pub static CHARTREUSE:              Srgb<u8> = Srgb::new(0x7F, 0xFF, 0x00);
+    pub static CHOCOLATE:               Srgb<u8> = Srgb::new(0xD2, 0x69, 0x1E);
+    pub static CORAL:                   Srgb<u8> = Srgb::new(0xFF, 0x7F, 0x50);
+    pub static CORNFLOWERBLUE:          Srgb<u8> = Srgb::new(0x64, 0x95, 0xED);
+    pub static CORNSILK:                Srgb<u8> = Srgb::new(0xFF, 0xF8, 0xDC);
+    pub static CRIMSON:                 Srgb<u8> = Srgb::new(0xDC, 0x14, 0x3C);
+    pub static CYAN:                    Srgb<u8> = Srgb::new(0x00, 0xFF, 0xFF);
+    pub static DARKBLUE:                Srgb<u8> = Srgb::new(0x00, 0x00, 0x8B);
+    pub static DARKCYAN:                Srgb<u8> = Srgb::new(0x00, 0x8B, 0x8B);
+    pub static DARKGOLDENROD:           Srgb<u8> = Srgb::new(0xB8, 0x86, 0x0B);
+    pub static DARKGRAY:                Srgb<u8> = Srgb::new(0xA9, 0xA9, 0xA9);
+    pub static DARKGREEN:               Srgb<u8> = Srgb::new(0x00, 0x64, 0x00);
+    pub static DARKGREY:                Srgb<u8> = Srgb::new(0xA9, 0xA9, 0xA9);
+    pub static DARKKHAKI:               Srgb<u8> = Srgb::new(0xBD, 0xB7, 0x6B);
+    pub static DARKMAGENTA:             Srgb<u8> = Srgb::new(0x8B, 0x00, 0x8B);
+    pub static DARKOLIVEGREEN:          Srgb<u8> = Srgb::new(0x55, 0x6B, 0x2F);
+    pub static DARKORANGE:              Srgb<u8> = Srgb::new(0xFF, 0x8C, 0x00);
+    pub static DARKORCHID:              Srgb<u8> = Srgb::new(0x99, 0x32, 0xCC);
+    pub static DARKRED:                 Srgb<u8> = Srgb::new(0x8B, 0x00, 0x00);
+    pub static DARKSALMON:              Srgb<u8> = Srgb::new(0xE9, 0x96, 0x7A);
+    pub static DARKSEAGREEN:            Srgb<u8> = Srgb::new(0x8F, 0xBC, 0x8F);
+    pub static DARKSLATEBLUE:           Srgb<u8> = Srgb::new(0x48, 0x3D, 0x8B);
+    pub static DARKSLATEGRAY:           Srgb<u8> = Srgb::new(0x2F, 0x4F, 0x4F);
+    pub static DARKSLATEGREY:           Srgb<u8> = Srgb::new(0x2F, 0x4F, 0x4F);
+    pub static DARKTURQUOISE:           Srgb<u8> = Srgb::new(0x00, 0xCE, 0xD1);
+    pub static DARKVIOLET:              Srgb<u8> = Srgb::new(0x94, 0x00, 0xD3);
+    pub static DEEPPINK:                Srgb<u8> = Srgb::new(0xFF, 0x14, 0x93);
+    pub static DEEPSKYBLUE:             Srgb<u8> = Srgb::new(0x00, 0xBF, 0xFF);
+    pub static DIMGRAY:                 Srgb<u8> = Srgb::new(0x69, 0x69, 0x69);
+    pub static DIMGREY:                 Srgb<u8> = Srgb::new(0x69, 0x69, 0x69);
+    pub static DODGERBLUE:              Srgb<u8> = Srgb::new(0x1E, 0x90, 0xFF);
+    pub static FIREBRICK:               Srgb<u8> = Srgb::new(0xB2, 0x22, 0x22);
+    pub static FLORALWHITE:             Srgb<u8> = Srgb::new(0xFF, 0xFA, 0xF0);
+    pub static FORESTGREEN:             Srgb<u8> = Srgb::new(0x22, 0x8B, 0x22);
+    pub static FUCHSIA:                 Srgb<u8> = Srgb::new(0xFF, 0x00, 0xFF);
+    pub static GAINSBORO:               Srgb<u8> = Srgb::new(0xDC, 0xDC, 0xDC);
+    pub static GHOSTWHITE:              Srgb<u8> = Srgb::new(0xF8, 0xF8, 0xFF);
+    pub static GOLD:                    Srgb<u8> = Srgb::new(0xFF, 0xD7, 0x00);
+    pub static GOLDENROD:               Srgb<u8> = Srgb::new(0xDA, 0xA5, 0x20);
+    pub static GRAY:                    Srgb<u8> = Srgb::new(0x80, 0x80, 0x80);
+    pub static GREEN:                   Srgb<u8> = Srgb::new(0x00, 0x80, 0x00);
+    pub static GREENYELLOW:             Srgb<u8> = Srgb::new(0xAD, 0xFF, 0x2F);
+    pub static GREY:                    Srgb<u8> = Srgb::new(0x80, 0x80, 0x80);
+    pub static HONEYDEW:                Srgb<u8> = Srgb::new(0xF0, 0xFF, 0xF0);
+    pub static HOTPINK:                 Srgb<u8> = Srgb::new(0xFF, 0x69, 0xB4);
+    pub static INDIANRED:               Srgb<u8> = Srgb::new(0xCD, 0x5C, 0x5C);
+    pub static INDIGO:                  Srgb<u8> = Srgb::new(0x4B, 0x00, 0x82);
+    pub static IVORY:                   Srgb<u8> = Srgb::new(0xFF, 0xFF, 0xF0);
+    pub static KHAKI:                   Srgb<u8> = Srgb::new(0xF0, 0xE6, 0x8C);
+    pub static LAVENDER:                Srgb<u8> = Srgb::new(0xE6, 0xE6, 0xFA);
+    pub static LAVENDERBLUSH:           Srgb<u8> = Srgb::new(0xFF, 0xF0, 0xF5);
+    pub static LAWNGREEN:               Srgb<u8> = Srgb::new(0x7C, 0xFC, 0x00);
+    pub static LEMONCHIFFON:            Srgb<u8> = Srgb::new(0xFF, 0xFA, 0xCD);
+    pub static LIGHTBLUE:               Srgb<u8> = Srgb::new(0xAD, 0xD8, 0xE6);
+    pub static LIGHTCORAL:              Srgb<u8> = Srgb::new(0xF0, 0x80, 0x80);
+    pub static LIGHTCYAN:               Srgb<u8> = Srgb::new(0xE0, 0xFF, 0xFF);
+    pub static LIGHTGOLDENRODYELLOW:    Srgb<u8> = Srgb::new(0xFA, 0xFA, 0xD2);
+    pub static LIGHTGRAY:               Srgb<u8> = Srgb::new(0xD3, 0xD3, 0xD3);
+    pub static LIGHTGREEN:              Srgb<u8> = Srgb::new(0x90, 0xEE, 0x90);
+    pub static LIGHTGREY:               Srgb<u8> = Srgb::new(0xD3, 0xD3, 0xD3);
+    pub static LIGHTPINK:               Srgb<u8> = Srgb::new(0xFF, 0xB6, 0xC1);
+    pub static LIGHTSALMON:             Srgb<u8> = Srgb::new(0xFF, 0xA0, 0x7A);
+    pub static LIGHTSEAGREEN:           Srgb<u8> = Srgb::new(0x20, 0xB2, 0xAA);
+    pub static LIGHTSKYBLUE:            Srgb<u8> = Srgb::new(0x87, 0xCE, 0xFA);
+    pub static LIGHTSLATEGRAY:          Srgb<u8> = Srgb::new(0x77, 0x88, 0x99);
+    pub static LIGHTSLATEGREY:          Srgb<u8> = Srgb::new(0x77, 0x88, 0x99);
+    pub static LIGHTSTEELBLUE:          Srgb<u8> = Srgb::new(0xB0, 0xC4, 0xDE);
+    pub static LIGHTYELLOW:             Srgb<u8> = Srgb::new(0xFF, 0xFF, 0xE0);
+    pub static LIME:                    Srgb<u8> = Srgb::new(0x00, 0xFF, 0x00);
+    pub static LIMEGREEN:               Srgb<u8> = Srgb::new(0x32, 0xCD, 0x32);
+    pub static LINEN:                   Srgb<u8> = Srgb::new(0xFA, 0xF0, 0xE6);
+    pub static MAGENTA:                 Srgb<u8> = Srgb::new(0xFF, 0x00, 0xFF);
+    pub static MAROON:                  Srgb<u8> = Srgb::new(0x80, 0x00, 0x00);
+    pub static MEDIUMAQUAMARINE:        Srgb<u8> = Srgb::new(0x66, 0xCD, 0xAA);
+    pub static MEDIUMBLUE:              Srgb<u8> = Srgb::new(0x00, 0x00, 0xCD);
+    pub static MEDIUMORCHID:            Srgb<u8> = Srgb::new(0xBA, 0x55, 0xD3);
+    pub static MEDIUMPURPLE:            Srgb<u8> = Srgb::new(0x93, 0x70, 0xDB);
+    pub static MEDIUMSEAGREEN:          Srgb<u8> = Srgb::new(0x3C, 0xB3, 0x71);
+    pub static MEDIUMSLATEBLUE:         Srgb<u8> = Srgb::new(0x7B, 0x68, 0xEE);
+    pub static MEDIUMSPRINGGREEN:       Srgb<u8> = Srgb::new(0x00, 0xFA, 0x9A);
+    pub static MEDIUMTURQUOISE:         Srgb<u8> = Srgb::new(0x48, 0xD1, 0xCC);
+    pub static MEDIUMVIOLETRED:         Srgb<u8> = Srgb::new(0xC7, 0x15, 0x85);
+    pub static MIDNIGHTBLUE:            Srgb<u8> = Srgb::new(0x19, 0x19, 0x70);
+    pub static MINTCREAM:               Srgb<u8> = Srgb::new(0xF5, 0xFF, 0xFA);
+    pub static MISTYROSE:               Srgb<u8> = Srgb::new(0xFF, 0xE4, 0xE1);
+    pub static MOCCASIN:                Srgb<u8> = Srgb::new(0xFF, 0xE4, 0xB5);
+    pub static NAVAJOWHITE:             Srgb<u8> = Srgb::new(0xFF, 0xDE, 0xAD);
+    pub static NAVY:                    Srgb<u8> = Srgb::new(0x00, 0x00, 0x80);
+    pub static OLDLACE:                 Srgb<u8> = Srgb::new(0xFD, 0xF5, 0xE6);
+    pub static OLIVE:                   Srgb<u8> = Srgb::new(0x80, 0x80, 0x00);
+    pub static OLIVEDRAB:               Srgb<u8> = Srgb::new(0x6B, 0x8E, 0x23);
+    pub static ORANGE:                  Srgb<u8> = Srgb::new(0xFF, 0xA5, 0x00);
+    pub static ORANGERED:               Srgb<u8> = Srgb::new(0xFF, 0x45, 0x00);
+    pub static ORCHID:                  Srgb<u8> = Srgb::new(0xDA, 0x70, 0xD6);
+    pub static PALEGOLDENROD:           Srgb<u8> = Srgb::new(0xEE, 0xE8, 0xAA);
+    pub static PALEGREEN:               Srgb<u8> = Srgb::new(0x98, 0xFB, 0x98);
+    pub static PALETURQUOISE:           Srgb<u8> = Srgb::new(0xAF, 0xEE, 0xEE);
+    pub static PALEVIOLETRED:           Srgb<u8> = Srgb::new(0xDB, 0x70, 0x93);
+    pub static PAPAYAWHIP:              Srgb<u8> = Srgb::new(0xFF, 0xEF, 0xD5);
+    pub static PEACHPUFF:               Srgb<u8> = Srgb::new(0xFF, 0xDA, 0xB9);
+    pub static PERU:                    Srgb<u8> = Srgb::new(0xCD, 0x85, 0x3F);
+    pub static PINK:                    Srgb<u8> = Srgb::new(0xFF, 0xC0, 0xCB);
+    pub static PLUM:                    Srgb<u8> = Srgb::new(0xDD, 0xA0, 0xDD);
+    pub static POWDERBLUE:              Srgb<u8> = Srgb::new(0xB0, 0xE0, 0xE6);
+    pub static PURPLE:                  Srgb<u8> = Srgb::new(0x80, 0x00, 0x80);
+    pub static REBECCAPURPLE:           Srgb<u8> = Srgb::new(0x66, 0x33, 0x99);
+    pub static RED:                     Srgb<u8> = Srgb::new(0xFF, 0x00, 0x00);
+    pub static ROSYBROWN:               Srgb<u8> = Srgb::new(0xBC, 0x8F, 0x8F);
+    pub static ROYALBLUE:               Srgb<u8> = Srgb::new(0x41, 0x69, 0xE1);
+    pub static SADDLEBROWN:             Srgb<u8> = Srgb::new(0x8B, 0x45, 0x13);
+    pub static SALMON:                  Srgb<u8> = Srgb::new(0xFA, 0x80, 0x72);
+    pub static SANDYBROWN:              Srgb<u8> = Srgb::new(0xFA, 0xA4, 0x60);
+    pub static SEAGREEN:                Srgb<u8> = Srgb::new(0x2E, 0x8B, 0x57);
+    pub static SEASHELL:                Srgb<u8> = Srgb::new(0xFF, 0xF5, 0xEE);
+    pub static SIENNA:                  Srgb<u8> = Srgb::new(0xA0, 0x52, 0x2D);
+    pub static SILVER:                  Srgb<u8> = Srgb::new(0xC0, 0xC0, 0xC0);
+    pub static SKYBLUE:                 Srgb<u8> = Srgb::new(0x87, 0xCE, 0xEB);
+    pub static SLATEBLUE:               Srgb<u8> = Srgb::new(0x6A, 0x5A, 0xCD);
+    pub static SLATEGRAY:               Srgb<u8> = Srgb::new(0x70, 0x80, 0x90);
+    pub static SLATEGREY:               Srgb<u8> = Srgb::new(0x70, 0x80, 0x90);
+    pub static SNOW:                    Srgb<u8> = Srgb::new(0xFF, 0xFA, 0xFA);
+    pub static SPRINGGREEN:             Srgb<u8> = Srgb::new(0x00, 0xFF, 0x7F);
+    pub static STEELBLUE:               Srgb<u8> = Srgb::new(0x46, 0x82, 0xB4);
+    pub static TAN:                     Srgb<u8> = Srgb::new(0xD2, 0xB4, 0x8C);
+    pub static TEAL:                    Srgb<u8> = Srgb::new(0x00, 0x80, 0x80);
+    pub static THISTLE:                 Srgb<u8> = Srgb::new(0xD8, 0xBF, 0xD8);
+    pub static TOMATO:                  Srgb<u8> = Srgb::new(0xFF, 0x63, 0x47);
+    pub static TURQUOISE:               Srgb<u8> = Srgb::new(0x40, 0xE0, 0xD0);
+    pub static VIOLET:                  Srgb<u8> = Srgb::new(0xEE, 0x82, 0xEE);
+    pub static WHEAT:                   Srgb<u8> = Srgb::new(0xF5, 0xDE, 0xB3);
+    pub static WHITE:                   Srgb<u8> = Srgb::new(0xFF, 0xFF, 0xFF);
+    pub static WHITESMOKE:              Srgb<u8> = Srgb::new(0xF5, 0xF5, 0xF5);
+    pub static YELLOW:                  Srgb<u8> = Srgb::new(0xFF, 0xFF, 0x00);
+    pub static YELLOWGREEN:             Srgb<u8> = Srgb::new(0x9A, 0xCD, 0x32);
+
+    static NAMED: [(&str, &Srgb<u8>); 148] = [
+        ("aliceblue", &ALICEBLUE),
+        ("antiquewhite", &ANTIQUEWHITE),
+        ("aqua", &AQUA),
+        ("aquamarine", &AQUAMARINE),
+        ("azure", &AZURE),
+        ("beige", &BEIGE),
+        ("bisque", &BISQUE),
+        ("black", &BLACK),
+        ("blanchedalmond", &BLANCHEDALMOND),
+        ("blue", &BLUE),
+        ("blueviolet", &BLUEVIOLET),
+        ("brown", &BROWN),
+        ("burlywood", &BURLYWOOD),
+        ("cadetblue", &CADETBLUE),
+        ("chartreuse", &CHARTREUSE),
+        ("chocolate", &CHOCOLATE),
+        ("coral", &CORAL),
+        ("cornflowerblue", &CORNFLOWERBLUE),
+        ("cornsilk", &CORNSILK),
+        ("crimson", &CRIMSON),
+        ("cyan", &CYAN),
+        ("darkblue", &DARKBLUE),
+        ("darkcyan", &DARKCYAN),
+        ("darkgoldenrod", &DARKGOLDENROD),
+        ("darkgray", &DARKGRAY),
+        ("darkgreen", &DARKGREEN),
+        ("darkgrey", &DARKGREY),
+        ("darkkhaki", &DARKKHAKI),
+        ("darkmagenta", &DARKMAGENTA),
+        ("darkolivegreen", &DARKOLIVEGREEN),
+        ("darkorange", &DARKORANGE),
+        ("darkorchid", &DARKORCHID),
+        ("darkred", &DARKRED),
+        ("darksalmon", &DARKSALMON),
+        ("darkseagreen", &DARKSEAGREEN),
+        ("darkslateblue", &DARKSLATEBLUE),
+        ("darkslategray", &DARKSLATEGRAY),
+        ("darkslategrey", &DARKSLATEGREY),
+        ("darkturquoise", &DARKTURQUOISE),
+        ("darkviolet", &DARKVIOLET),
+        ("deeppink", &DEEPPINK),
+        ("deepskyblue", &DEEPSKYBLUE),
+        ("dimgray", &DIMGRAY),
+        ("dimgrey", &DIMGREY),
+        ("dodgerblue", &DODGERBLUE),
+        ("firebrick", &FIREBRICK),
+        ("floralwhite", &FLORALWHITE),
+        ("forestgreen", &FORESTGREEN),
+        ("fuchsia", &FUCHSIA),
+        ("gainsboro", &GAINSBORO),
+        ("ghostwhite", &GHOSTWHITE),
+        ("gold", &GOLD),
+        ("goldenrod", &GOLDENROD),
+        ("gray", &GRAY),
+        ("green", &GREEN),
+        ("greenyellow", &GREENYELLOW),
+        ("grey", &GREY),
+        ("honeydew", &HONEYDEW),
+        ("hotpink", &HOTPINK),
+        ("indianred", &INDIANRED),
+        ("indigo", &INDIGO),
+        ("ivory", &IVORY),
+        ("khaki", &KHAKI),
+        ("lavender", &LAVENDER),
+        ("lavenderblush", &LAVENDERBLUSH),
+        ("lawngreen", &LAWNGREEN),
+        ("lemonchiffon", &LEMONCHIFFON),
+        ("lightblue", &LIGHTBLUE),
+        ("lightcoral", &LIGHTCORAL),
+        ("lightcyan", &LIGHTCYAN),
+        ("lightgoldenrodyellow", &LIGHTGOLDENRODYELLOW),
+        ("lightgray", &LIGHTGRAY),
+        ("lightgreen", &LIGHTGREEN),
+        ("lightgrey", &LIGHTGREY),
+        ("lightpink", &LIGHTPINK),
+        ("lightsalmon", &LIGHTSALMON),
+        ("lightseagreen", &LIGHTSEAGREEN),
+        ("lightskyblue", &LIGHTSKYBLUE),
+        ("lightslategray", &LIGHTSLATEGRAY),
+        ("lightslategrey", &LIGHTSLATEGREY),
+        ("lightsteelblue", &LIGHTSTEELBLUE),
+        ("lightyellow", &LIGHTYELLOW),
+        ("lime", &LIME),
+        ("limegreen", &LIMEGREEN),
+        ("linen", &LINEN),
+        ("magenta", &MAGENTA),
+        ("maroon", &MAROON),
+        ("mediumaquamarine", &MEDIUMAQUAMARINE),
+        ("mediumblue", &MEDIUMBLUE),
+        ("mediumorchid", &MEDIUMORCHID),
+        ("mediumpurple", &MEDIUMPURPLE),
+        ("mediumseagreen", &MEDIUMSEAGREEN),
+        ("mediumslateblue", &MEDIUMSLATEBLUE),
+        ("mediumspringgreen", &MEDIUMSPRINGGREEN),
+        ("mediumturquoise", &MEDIUMTURQUOISE),
+        ("mediumvioletred", &MEDIUMVIOLETRED),
+        ("midnightblue", &MIDNIGHTBLUE),
+        ("mintcream", &MINTCREAM),
+        ("mistyrose", &MISTYROSE),
+        ("moccasin", &MOCCASIN),
+        ("navajowhite", &NAVAJOWHITE),
+        ("navy", &NAVY),
+        ("oldlace", &OLDLACE),
+        ("olive", &OLIVE),
+        ("olivedrab", &OLIVEDRAB),
+        ("orange", &ORANGE),
+        ("orangered", &ORANGERED),
+        ("orchid", &ORCHID),
+        ("palegoldenrod", &PALEGOLDENROD),
+        ("palegreen", &PALEGREEN),
+        ("paleturquoise", &PALETURQUOISE),
+        ("palevioletred", &PALEVIOLETRED),
+        ("papayawhip", &PAPAYAWHIP),
+        ("peachpuff", &PEACHPUFF),
+        ("peru", &PERU),
+        ("pink", &PINK),
+        ("plum", &PLUM),
+        ("powderblue", &POWDERBLUE),
+        ("purple", &PURPLE),
+        ("rebeccapurple", &REBECCAPURPLE),
+        ("red", &RED),
+        ("rosybrown", &ROSYBROWN),
+        ("royalblue", &ROYALBLUE),
+        ("saddlebrown", &SADDLEBROWN),
+        ("salmon", &SALMON),
+        ("sandybrown", &SANDYBROWN),
+        ("seagreen", &SEAGREEN),
+        ("seashell", &SEASHELL),
+        ("sienna", &SIENNA),
+        ("silver", &SILVER),
+        ("skyblue", &SKYBLUE),
+        ("slateblue", &SLATEBLUE),
+        ("slategray", &SLATEGRAY),
+        ("slategrey", &SLATEGREY),
+        ("snow", &SNOW),
+        ("springgreen", &SPRINGGREEN),
+        ("steelblue", &STEELBLUE),
+        ("tan", &TAN),
+        ("teal", &TEAL),
+        ("thistle", &THISTLE),
+        ("tomato", &TOMATO),
+        ("turquoise", &TURQUOISE),
+        ("violet", &VIOLET),
+        ("wheat", &WHEAT),
+        ("white", &WHITE),
+        ("whitesmoke", &WHITESMOKE),
+        ("yellow", &YELLOW),
+        ("yellowgreen", &YELLOWGREEN),
+    ];
+
+    /// Looks up a named color, ignoring ASCII case. Both the `gray` and
+    /// `grey` spellings are accepted.
+    pub fn from_name(name: &str) -> Option<Srgb<u8>> {
+        NAMED.iter()
+             .find(|&&(n, _)| n.eq_ignore_ascii_case(name))
+             .map(|&(_, &color)| color)
+    }
+
+    /// Returns the name of a color if it exactly matches a named color,
+    /// preferring the `gray` spelling.
+    pub fn name_of(color: &Srgb<u8>) -> Option<&'static str> {
+        NAMED.iter()
+             .find(|&&(_, c)| c == color)
+             .map(|&(name, _)| name)
+    }
+
+    /// Returns an iterator over the lower case names and values of all the
+    /// named colors, in alphabetical order.
+    pub fn iter() -> Iter {
+        Iter { inner: NAMED.iter() }
+    }
+
+    /// An iterator over the named colors, created by `iter`.
+    #[derive(Clone, Debug)]
+    pub struct Iter {
+        inner: slice::Iter<'static, (&'static str, &'static Srgb<u8>)>,
+    }
+
+    impl Iterator for Iter {
+        type Item = (&'static str, Srgb<u8>);
+
+        #[inline]
+        fn next(&mut self) -> Option<(&'static str, Srgb<u8>)> {
+            self.inner.next().map(|&(name, &color)| (name, color))
+        }
+
+        #[inline]
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            self.inner.size_hint()
+        }
+    }
+
+    impl ExactSizeIterator for Iter {}
 }
 
 #[cfg(test)]
 mod tests {
     use {Hsv, ToHsv};
     use {Rgb, ToRgb};
-    use FloatColor;
+    use {Srgb, FloatColor};
+    use super::consts;
     use angle::*;
     use num::Saturating;
 
@@ -503,4 +742,42 @@ mod tests {
         assert_eq!( rgb!(1.0f32, 1.0, 1.0) * 2.0, rgb!(2.0, 2.0, 2.0));
         assert_eq!( (rgb!(1.0f32, 1.0, 1.0) * 2.0).saturate(), rgb!(1.0, 1.0, 1.0));
     }
+
+    #[test]
+    fn test_from_name() {
+        assert_eq!(consts::from_name("cornflowerblue"), Some(consts::CORNFLOWERBLUE));
+        assert_eq!(consts::from_name("CornflowerBlue"), Some(consts::CORNFLOWERBLUE));
+        assert_eq!(consts::from_name("rebeccapurple"), Some(Srgb::new(0x66, 0x33, 0x99)));
+        assert_eq!(consts::from_name("slategrey"), Some(consts::SLATEGRAY));
+        assert_eq!(consts::from_name("LightGray"), Some(consts::LIGHTGREY));
+        assert_eq!(consts::from_name("grey"), consts::from_name("gray"));
+        assert_eq!(consts::from_name("blurple"), None);
+        assert_eq!(consts::name_of(&Srgb::new(0x80, 0x80, 0x80)), Some("gray"));
+        assert_eq!(consts::name_of(&Srgb::new(0x80, 0x80, 0x81)), None);
+    }
+
+    #[test]
+    fn test_iter() {
+        assert_eq!(consts::iter().len(), 148);
+        for (name, color) in consts::iter() {
+            assert_eq!(name, name.to_ascii_lowercase());
+            assert_eq!(consts::from_name(name), Some(color));
+        }
+        let names: Vec<_> = consts::iter().map(|(name, _)| name).collect();
+        let mut sorted = names.clone();
+        sorted.sort();
+        assert_eq!(names, sorted);
+    }
+
+    #[test]
+    fn test_nearest_named() {
+        assert_eq!(consts::CRIMSON.to_rgb::<f64>().nearest_named(), ("crimson", consts::CRIMSON));
+        assert_eq!(Srgb::new(0xFE_u8, 0x01, 0x02).to_rgb::<f32>().nearest_named().0, "red");
+        assert_eq!(Srgb::new(0x7F_u8, 0x81, 0x80).to_rgb::<u16>().nearest_named().0, "gray");
+        assert_eq!(Srgb::new(0.4f32, 0.2, 0.6).to_rgb::<f32>().nearest_named().0, "rebeccapurple");
+        // The components are linear, so a half intensity gray is much lighter
+        // than the `gray` keyword, which is encoded 0x80
+        assert_eq!(rgb!(0.5f64, 0.5, 0.5).nearest_named().0, "silver");
+        assert_eq!(consts::GRAY.to_rgb::<f64>().nearest_named().0, "gray");
+    }
 }
@@ -0,0 +1,352 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! RGB working spaces, each defined by its primaries, white point and
+//! transfer function: http://en.wikipedia.org/wiki/RGB_color_spaces
+//!
+//! The spaces are zero sized tags used as the second type parameter of `Rgb`,
+//! as in `Rgb<f32, DisplayP3>`. Converting between spaces with
+//! `Rgb::to_space` goes through XYZ, and colors are adapted with the Bradford
+//! transform when the white points differ.
+
+use std::fmt::Debug;
+use std::marker::PhantomData;
+
+use Channel;
+use Rgb;
+use {Chromaticity, RgbPrimaries};
+use ChromaticAdaptation;
+use matrix::{self, Matrix3};
+use srgb;
+
+/// An RGB working space.
+///
+/// The transfer functions are mirrored for negative values, so that out of
+/// gamut colors survive a round trip through the encoded form.
+pub trait RgbSpace: Copy + Clone + PartialEq + Eq + Debug + Default {
+    /// The primaries and white point of the space.
+    const PRIMARIES: RgbPrimaries;
+
+    /// The matrix converting linear RGB in the space to XYZ.
+    const TO_XYZ: Matrix3 = Self::PRIMARIES.to_xyz_matrix();
+
+    /// The matrix converting XYZ to linear RGB in the space.
+    const FROM_XYZ: Matrix3 = Self::PRIMARIES.from_xyz_matrix();
+
+    /// Converts a linear light value to its encoded form.
+    fn encode(linear: f64) -> f64;
+
+    /// Converts an encoded value back to linear light.
+    fn decode(encoded: f64) -> f64;
+}
+
+/// Applies a transfer function to the magnitude of a value, keeping its sign.
+#[inline]
+fn mirrored<F: Fn(f64) -> f64>(v: f64, f: F) -> f64 {
+    if v < 0.0 { -f(-v) } else { f(v) }
+}
+
+/// sRGB, as specified by IEC 61966-2-1. This is the default space of `Rgb`,
+/// whose components are always linear; gamma encoded sRGB is the `Srgb` color
+/// type.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct LinearSrgb;
+
+impl RgbSpace for LinearSrgb {
+    const PRIMARIES: RgbPrimaries = RgbPrimaries::SRGB;
+
+    #[inline]
+    fn encode(linear: f64) -> f64 { mirrored(linear, srgb::encode) }
+
+    #[inline]
+    fn decode(encoded: f64) -> f64 { mirrored(encoded, srgb::decode) }
+}
+
+/// Display P3, which pairs the DCI-P3 primaries with a D65 white and the sRGB
+/// transfer function.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct DisplayP3;
+
+impl RgbSpace for DisplayP3 {
+    const PRIMARIES: RgbPrimaries = RgbPrimaries {
+        red:   Chromaticity { x: 0.680, y: 0.320 },
+        green: Chromaticity { x: 0.265, y: 0.690 },
+        blue:  Chromaticity { x: 0.150, y: 0.060 },
+        white: Chromaticity::D65,
+    };
+
+    #[inline]
+    fn encode(linear: f64) -> f64 { mirrored(linear, srgb::encode) }
+
+    #[inline]
+    fn decode(encoded: f64) -> f64 { mirrored(encoded, srgb::decode) }
+}
+
+/// DCI-P3, as used for digital cinema projection, with the DCI white and a
+/// pure 2.6 gamma.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct DciP3;
+
+impl RgbSpace for DciP3 {
+    const PRIMARIES: RgbPrimaries = RgbPrimaries {
+        red:   Chromaticity { x: 0.680, y: 0.320 },
+        green: Chromaticity { x: 0.265, y: 0.690 },
+        blue:  Chromaticity { x: 0.150, y: 0.060 },
+        white: Chromaticity { x: 0.314, y: 0.351 },
+    };
+
+    #[inline]
+    fn encode(linear: f64) -> f64 { mirrored(linear, |v| v.powf(1.0 / 2.6)) }
+
+    #[inline]
+    fn decode(encoded: f64) -> f64 { mirrored(encoded, |v| v.powf(2.6)) }
+}
+
+/// ITU-R BT.2020, with the BT.2020 transfer function.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct Rec2020;
+
+const REC2020_ALPHA: f64 = 1.09929682680944;
+const REC2020_BETA: f64 = 0.018053968510807;
+
+impl RgbSpace for Rec2020 {
+    const PRIMARIES: RgbPrimaries = RgbPrimaries {
+        red:   Chromaticity { x: 0.708, y: 0.292 },
+        green: Chromaticity { x: 0.170, y: 0.797 },
+        blue:  Chromaticity { x: 0.131, y: 0.046 },
+        white: Chromaticity::D65,
+    };
+
+    #[inline]
+    fn encode(linear: f64) -> f64 {
+        mirrored(linear, |v| {
+            if v < REC2020_BETA { 4.5 * v } else { REC2020_ALPHA * v.powf(0.45) - (REC2020_ALPHA - 1.0) }
+        })
+    }
+
+    #[inline]
+    fn decode(encoded: f64) -> f64 {
+        mirrored(encoded, |v| {
+            if v < REC2020_BETA * 4.5 { v / 4.5 } else { ((v + REC2020_ALPHA - 1.0) / REC2020_ALPHA).powf(1.0 / 0.45) }
+        })
+    }
+}
+
+/// Adobe RGB (1998), with a pure `563/256` gamma.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct AdobeRgb;
+
+impl RgbSpace for AdobeRgb {
+    const PRIMARIES: RgbPrimaries = RgbPrimaries {
+        red:   Chromaticity { x: 0.640, y: 0.330 },
+        green: Chromaticity { x: 0.210, y: 0.710 },
+        blue:  Chromaticity { x: 0.150, y: 0.060 },
+        white: Chromaticity::D65,
+    };
+
+    #[inline]
+    fn encode(linear: f64) -> f64 { mirrored(linear, |v| v.powf(256.0 / 563.0)) }
+
+    #[inline]
+    fn decode(encoded: f64) -> f64 { mirrored(encoded, |v| v.powf(563.0 / 256.0)) }
+}
+
+/// ProPhoto RGB, also known as ROMM RGB, with a D50 white.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct ProPhotoRgb;
+
+impl RgbSpace for ProPhotoRgb {
+    const PRIMARIES: RgbPrimaries = RgbPrimaries {
+        red:   Chromaticity { x: 0.734699, y: 0.265301 },
+        green: Chromaticity { x: 0.159597, y: 0.840403 },
+        blue:  Chromaticity { x: 0.036598, y: 0.000105 },
+        white: Chromaticity::D50,
+    };
+
+    #[inline]
+    fn encode(linear: f64) -> f64 {
+        mirrored(linear, |v| if v < 1.0 / 512.0 { 16.0 * v } else { v.powf(1.0 / 1.8) })
+    }
+
+    #[inline]
+    fn decode(encoded: f64) -> f64 {
+        mirrored(encoded, |v| if v < 16.0 / 512.0 { v / 16.0 } else { v.powf(1.8) })
+    }
+}
+
+/// The ACES white point, close to D60.
+const ACES_WHITE: Chromaticity = Chromaticity { x: 0.32168, y: 0.33767 };
+
+/// ACES2065-1, the linear ACES interchange space with the AP0 primaries.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct Aces2065_1;
+
+impl RgbSpace for Aces2065_1 {
+    const PRIMARIES: RgbPrimaries = RgbPrimaries {
+        red:   Chromaticity { x: 0.7347, y: 0.2653 },
+        green: Chromaticity { x: 0.0000, y: 1.0000 },
+        blue:  Chromaticity { x: 0.0001, y: -0.0770 },
+        white: ACES_WHITE,
+    };
+
+    #[inline]
+    fn encode(linear: f64) -> f64 { linear }
+
+    #[inline]
+    fn decode(encoded: f64) -> f64 { encoded }
+}
+
+/// ACEScg, the linear ACES working space for rendering with the AP1
+/// primaries.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct AcesCg;
+
+impl RgbSpace for AcesCg {
+    const PRIMARIES: RgbPrimaries = RgbPrimaries {
+        red:   Chromaticity { x: 0.713, y: 0.293 },
+        green: Chromaticity { x: 0.165, y: 0.830 },
+        blue:  Chromaticity { x: 0.128, y: 0.044 },
+        white: ACES_WHITE,
+    };
+
+    #[inline]
+    fn encode(linear: f64) -> f64 { linear }
+
+    #[inline]
+    fn decode(encoded: f64) -> f64 { encoded }
+}
+
+/// Returns the matrix converting linear RGB in space `F` to linear RGB in
+/// space `U`, adapting between their white points with the Bradford
+/// transform.
+#[inline]
+pub fn conversion_matrix<F: RgbSpace, U: RgbSpace>() -> Matrix3 {
+    Conversion::<F, U>::MATRIX
+}
+
+/// Holds the matrix between two spaces, so that it is derived at compile
+/// time.
+struct Conversion<F, U>(PhantomData<(F, U)>);
+
+impl<F: RgbSpace, U: RgbSpace> Conversion<F, U> {
+    const MATRIX: Matrix3 = {
+        let (from, to) = (F::PRIMARIES.white, U::PRIMARIES.white);
+        if from.x == to.x && from.y == to.y {
+            matrix::mul(&U::FROM_XYZ, &F::TO_XYZ)
+        } else {
+            matrix::mul(&U::FROM_XYZ, &matrix::mul(&ChromaticAdaptation::Bradford.between(from, to), &F::TO_XYZ))
+        }
+    };
+}
+
+impl<T:Channel, S:RgbSpace> Rgb<T, S> {
+    /// Converts the color to the working space `U`. The result is not
+    /// clamped, so out of gamut colors have components outside of `(0,1)` for
+    /// float channels.
+    pub fn to_space<U:RgbSpace>(&self) -> Rgb<T, U> {
+        let rgb = matrix::mul_vec(&conversion_matrix::<S, U>(),
+                                  [self.r.to_channel_f64(), self.g.to_channel_f64(), self.b.to_channel_f64()]);
+        Rgb::with_space(Channel::from(rgb[0]), Channel::from(rgb[1]), Channel::from(rgb[2]), U::default())
+    }
+
+    /// Returns the components encoded with the transfer function of the
+    /// space, as they would be stored in an image.
+    pub fn to_encoded(&self) -> [T; 3] {
+        [Channel::from(S::encode(self.r.to_channel_f64())),
+         Channel::from(S::encode(self.g.to_channel_f64())),
+         Channel::from(S::encode(self.b.to_channel_f64()))]
+    }
+
+    /// Creates a color from components encoded with the transfer function of
+    /// the space.
+    pub fn from_encoded(encoded: [T; 3]) -> Rgb<T, S> {
+        Rgb::with_space(Channel::from(S::decode(encoded[0].to_channel_f64())),
+                        Channel::from(S::decode(encoded[1].to_channel_f64())),
+                        Channel::from(S::decode(encoded[2].to_channel_f64())),
+                        S::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use Rgb;
+    use super::*;
+
+    fn assert_rgb_eq<S: RgbSpace>(a: Rgb<f64, S>, b: [f64; 3], epsilon: f64) {
+        assert!((a.r - b[0]).abs() < epsilon && (a.g - b[1]).abs() < epsilon && (a.b - b[2]).abs() < epsilon,
+                "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn test_to_space() {
+        let red = Rgb::new(1.0, 0.0, 0.0);
+        assert_rgb_eq(red.to_space::<LinearSrgb>(), [1.0, 0.0, 0.0], 1e-12);
+        assert_rgb_eq(red.to_space::<DisplayP3>(), [0.8225, 0.0332, 0.0171], 1e-4);
+        assert_rgb_eq(red.to_space::<Rec2020>(), [0.6274, 0.0691, 0.0164], 1e-4);
+        assert_rgb_eq(red.to_space::<AdobeRgb>(), [0.7152, 0.0, 0.0], 1e-4);
+        assert_rgb_eq(red.to_space::<ProPhotoRgb>(), [0.5293, 0.0984, 0.0169], 1e-3);
+        assert_rgb_eq(red.to_space::<AcesCg>(), [0.6131, 0.0702, 0.0206], 1e-3);
+        assert_rgb_eq(red.to_space::<Aces2065_1>(), [0.4397, 0.0898, 0.0175], 1e-3);
+    }
+
+    #[test]
+    fn test_encoded() {
+        // Display P3 red is outside of the sRGB gamut
+        let p3_red = Rgb::<f64, DisplayP3>::from_encoded([1.0, 0.0, 0.0]);
+        let srgb = p3_red.to_space::<LinearSrgb>().to_encoded();
+        assert_rgb_eq(Rgb::new(srgb[0], srgb[1], srgb[2]), [1.0930, -0.2267, -0.1501], 1e-4);
+        let adobe = Rgb::new(1.0, 0.0, 0.0).to_space::<AdobeRgb>().to_encoded();
+        assert_rgb_eq(Rgb::new(adobe[0], adobe[1], adobe[2]), [0.8586, 0.0, 0.0], 1e-4);
+    }
+
+    #[test]
+    fn test_transfer_functions() {
+        fn check<S: RgbSpace>() {
+            for i in -10..=20 {
+                let v = i as f64 / 10.0;
+                assert!((S::decode(S::encode(v)) - v).abs() < 1e-12, "{:?} {}", S::default(), v);
+            }
+            assert_eq!(S::encode(0.0), 0.0);
+            assert!((S::encode(1.0) - 1.0).abs() < 1e-12);
+        }
+        check::<LinearSrgb>();
+        check::<DisplayP3>();
+        check::<DciP3>();
+        check::<Rec2020>();
+        check::<AdobeRgb>();
+        check::<ProPhotoRgb>();
+        check::<Aces2065_1>();
+        check::<AcesCg>();
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let color = Rgb::with_space(0.2, 0.5, 0.8, ProPhotoRgb);
+        assert_rgb_eq(color.to_space::<DciP3>().to_space::<ProPhotoRgb>(), [0.2, 0.5, 0.8], 1e-12);
+        let color = Rgb::with_space(0.2, 0.5, 0.8, Rec2020);
+        assert_rgb_eq(color.to_space::<AcesCg>().to_space::<Rec2020>(), [0.2, 0.5, 0.8], 1e-12);
+        // The DCI white is not D65, so it is adapted to D65 white
+        assert_rgb_eq(Rgb::with_space(1.0, 1.0, 1.0, DciP3).to_space::<DisplayP3>(), [1.0, 1.0, 1.0], 1e-12);
+    }
+
+    #[test]
+    fn test_arithmetic_in_space() {
+        let a = Rgb::with_space(0.25f32, 0.5, 0.75, DisplayP3);
+        let b = Rgb::with_space(0.25f32, 0.25, 0.25, DisplayP3);
+        assert_eq!(a + b, Rgb::with_space(0.5, 0.75, 1.0, DisplayP3));
+        assert_eq!((a - b) * 2.0, Rgb::with_space(0.0, 0.5, 1.0, DisplayP3));
+        assert_eq!(a.bgr(), Rgb::with_space(0.75, 0.5, 0.25, DisplayP3));
+    }
+}
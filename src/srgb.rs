@@ -13,12 +13,231 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+//! Gamma encoded sRGB, as specified by IEC 61966-2-1:
+//! http://en.wikipedia.org/wiki/SRGB
+
+use {Color, FloatColor};
+use {Channel, FloatChannel};
+use {Rgb, ToRgb};
+use {Hsv, AlphaColor};
+
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub struct Srgb<T> { pub r: T, pub g: T, pub b: T }
 
 impl<T> Srgb<T> {
     #[inline]
-    pub fn new(r: T, g: T, b: T) -> Srgb<T> {
-        Srgb { r: r, g: g, b: b }
+    pub const fn new(r: T, g: T, b: T) -> Srgb<T> {
+        Srgb { r, g, b }
+    }
+}
+
+/// Converts a linear light value to its sRGB encoded form using the
+/// piecewise IEC 61966-2-1 curve.
+#[inline]
+pub fn encode(linear: f64) -> f64 {
+    if linear <= 0.0031308 {
+        linear * 12.92
+    } else {
+        1.055 * linear.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// Converts an sRGB encoded value back to linear light using the piecewise
+/// IEC 61966-2-1 curve.
+#[inline]
+pub fn decode(encoded: f64) -> f64 {
+    if encoded <= 0.04045 {
+        encoded / 12.92
+    } else {
+        ((encoded + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Approximates `encode` with a pure 2.2 power curve.
+#[inline]
+pub fn encode_fast(linear: f64) -> f64 {
+    linear.max(0.0).powf(1.0 / 2.2)
+}
+
+/// Approximates `decode` with a pure 2.2 power curve.
+#[inline]
+pub fn decode_fast(encoded: f64) -> f64 {
+    encoded.max(0.0).powf(2.2)
+}
+
+impl<T:Channel> Srgb<T> {
+    /// Encodes a color using the pure gamma 2.2 approximation of the sRGB
+    /// curve. This is cheaper than `to_srgb`, but less accurate near black.
+    #[inline]
+    pub fn from_rgb_fast<C: ToRgb>(color: &C) -> Srgb<T> {
+        let rgb = color.to_rgb::<f64>();
+        Srgb::new(Channel::from(encode_fast(rgb.r)),
+                  Channel::from(encode_fast(rgb.g)),
+                  Channel::from(encode_fast(rgb.b)))
+    }
+
+    /// Decodes the color using the pure gamma 2.2 approximation of the sRGB
+    /// curve. This is cheaper than `to_rgb`, but less accurate near black.
+    #[inline]
+    pub fn to_rgb_fast<U:Channel>(&self) -> Rgb<U> {
+        Rgb::new(Channel::from(decode_fast(self.r.to_channel_f64())),
+                 Channel::from(decode_fast(self.g.to_channel_f64())),
+                 Channel::from(decode_fast(self.b.to_channel_f64())))
+    }
+}
+
+impl<T:Channel> Color<T> for Srgb<T> {
+    /// Clamps the components of the color to the range `(lo,hi)`.
+    #[inline]
+    fn clamp_s(self, lo: T, hi: T) -> Srgb<T> {
+        Srgb::new(self.r.clamp(lo, hi),
+                  self.g.clamp(lo, hi),
+                  self.b.clamp(lo, hi))
+    }
+
+    /// Clamps the components of the color component-wise between `lo` and `hi`.
+    #[inline]
+    fn clamp_c(self, lo: Srgb<T>, hi: Srgb<T>) -> Srgb<T> {
+        Srgb::new(self.r.clamp(lo.r, hi.r),
+                  self.g.clamp(lo.g, hi.g),
+                  self.b.clamp(lo.b, hi.b))
+    }
+
+    /// Inverts the color.
+    #[inline]
+    fn inverse(self) -> Srgb<T> {
+        Srgb::new(self.r.invert_channel(),
+                  self.g.invert_channel(),
+                  self.b.invert_channel())
+    }
+
+    /// Mixes the encoded components, as most image editors do.
+    #[inline]
+    fn mix(self, other: Self, value: T) -> Self {
+        Srgb::new(self.r.mix(other.r, value),
+                  self.g.mix(other.g, value),
+                  self.b.mix(other.b, value))
+    }
+}
+
+impl<T:FloatChannel> FloatColor<T> for Srgb<T> {
+    /// Clamps the components of the color to the range `(0,1)`.
+    #[inline]
+    fn saturate(self) -> Srgb<T> {
+        Srgb::new(self.r.saturate(),
+                  self.g.saturate(),
+                  self.b.saturate())
+    }
+}
+
+pub trait ToSrgb {
+    fn to_srgb<U:Channel>(&self) -> Srgb<U>;
+}
+
+impl<T:Channel> ToSrgb for Srgb<T> {
+    #[inline]
+    fn to_srgb<U:Channel>(&self) -> Srgb<U> {
+        Srgb::new(self.r.to_channel(),
+                  self.g.to_channel(),
+                  self.b.to_channel())
+    }
+}
+
+impl<T:Channel> ToSrgb for Rgb<T> {
+    #[inline]
+    fn to_srgb<U:Channel>(&self) -> Srgb<U> {
+        Srgb::new(Channel::from(encode(self.r.to_channel_f64())),
+                  Channel::from(encode(self.g.to_channel_f64())),
+                  Channel::from(encode(self.b.to_channel_f64())))
+    }
+}
+
+impl<T:Channel> ToSrgb for Hsv<T> {
+    #[inline]
+    fn to_srgb<U:Channel>(&self) -> Srgb<U> {
+        self.to_rgb::<f64>().to_srgb()
+    }
+}
+
+impl<T, C: ToSrgb> ToSrgb for AlphaColor<T, C> {
+    #[inline]
+    fn to_srgb<U:Channel>(&self) -> Srgb<U> {
+        self.c.to_srgb()
+    }
+}
+
+impl<T:Channel> ToRgb for Srgb<T> {
+    #[inline]
+    fn to_rgb<U:Channel>(&self) -> Rgb<U> {
+        Rgb::new(Channel::from(decode(self.r.to_channel_f64())),
+                 Channel::from(decode(self.g.to_channel_f64())),
+                 Channel::from(decode(self.b.to_channel_f64())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {Srgb, ToSrgb};
+    use {Rgb, ToRgb};
+    use {Rgba, Srgba, ToRgba, ToSrgba};
+    use super::{encode, decode};
+
+    fn approx_eq(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-6
+    }
+
+    #[test]
+    fn test_transfer_functions() {
+        assert!(approx_eq(encode(0.0), 0.0));
+        assert!(approx_eq(encode(1.0), 1.0));
+        assert!(approx_eq(encode(0.002), 0.02584));
+        assert!(approx_eq(decode(0.5), 0.21404114));
+        assert!(approx_eq(encode(0.21404114), 0.5));
+        for i in 0..256 {
+            let x = i as f64 / 255.0;
+            assert!(approx_eq(encode(decode(x)), x));
+        }
+    }
+
+    #[test]
+    fn test_srgb_to_rgb() {
+        assert_eq!(Srgb::<u8>::new(0x00, 0x00, 0x00).to_rgb::<u8>(), Rgb::<u8>::new(0x00, 0x00, 0x00));
+        assert_eq!(Srgb::<u8>::new(0xFF, 0xFF, 0xFF).to_rgb::<u8>(), Rgb::<u8>::new(0xFF, 0xFF, 0xFF));
+        let rgb = Srgb::<f64>::new(0.5, 0.0, 1.0).to_rgb::<f64>();
+        assert!(approx_eq(rgb.r, 0.21404114));
+        assert!(approx_eq(rgb.g, 0.0));
+        assert!(approx_eq(rgb.b, 1.0));
+    }
+
+    #[test]
+    fn test_rgb_to_srgb() {
+        let srgb = Rgb::<f64>::new(0.21404114, 0.0, 1.0).to_srgb::<f64>();
+        assert!(approx_eq(srgb.r, 0.5));
+        assert!(approx_eq(srgb.g, 0.0));
+        assert!(approx_eq(srgb.b, 1.0));
+    }
+
+    #[test]
+    fn test_srgb_fast() {
+        let rgb = Srgb::<f64>::new(0.5, 0.0, 1.0).to_rgb_fast::<f64>();
+        assert!((rgb.r - 0.21404114).abs() < 0.01);
+        let srgb = Srgb::<f64>::from_rgb_fast(&rgb);
+        assert!(approx_eq(srgb.r, 0.5));
+        assert!(approx_eq(srgb.b, 1.0));
+    }
+
+    #[test]
+    fn test_srgba_alpha_is_linear() {
+        let srgba: Srgba<f64> = Srgba { c: Srgb::new(0.5, 0.5, 0.5), a: 0.5 };
+        let rgba = srgba.to_rgba::<f64>();
+        assert!(approx_eq(rgba.c.r, 0.21404114));
+        assert_eq!(rgba.a, 0.5);
+        let back = rgba.to_srgba::<f64>();
+        assert!(approx_eq(back.c.r, 0.5));
+        assert_eq!(back.a, 0.5);
+
+        let rgba: Rgba<u8> = rgba!(0x00u8, 0x00, 0x00, 0x80);
+        assert_eq!(rgba.to_srgba::<u8>().a, 0x80);
+        assert_eq!(rgba.to_srgba::<f32>().to_rgba::<u8>(), rgba);
     }
 }
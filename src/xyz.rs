@@ -0,0 +1,404 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The CIE 1931 XYZ and xyY color spaces:
+//! http://en.wikipedia.org/wiki/CIE_1931_color_space
+
+use num;
+use num::traits;
+
+use {Channel, FloatChannel};
+use {Rgb, ToRgb};
+use {Srgb, ToSrgb};
+use {Hsv, Hsl, AlphaColor};
+use matrix::{self, Matrix3};
+use space::{RgbSpace, LinearSrgb};
+
+fn cast<T: num::NumCast, U: num::NumCast>(n: T) -> U {
+    traits::cast(n).unwrap()
+}
+
+/// A CIE XYZ tristimulus value, where `Y` is the relative luminance.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Xyz<T> { pub x: T, pub y: T, pub z: T }
+
+/// A CIE xyY color, where `x` and `y` are the chromaticity coordinates and
+/// `luminance` is the `Y` tristimulus value.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Yxy<T> { pub x: T, pub y: T, pub luminance: T }
+
+impl<T> Xyz<T> {
+    #[inline]
+    pub fn new(x: T, y: T, z: T) -> Xyz<T> {
+        Xyz { x, y, z }
+    }
+}
+
+impl<T> Yxy<T> {
+    #[inline]
+    pub fn new(x: T, y: T, luminance: T) -> Yxy<T> {
+        Yxy { x, y, luminance }
+    }
+}
+
+/// CIE xy chromaticity coordinates.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Chromaticity { pub x: f64, pub y: f64 }
+
+impl Chromaticity {
+    /// The CIE standard illuminant D65, as used by sRGB.
+    pub const D65: Chromaticity = Chromaticity { x: 0.3127, y: 0.3290 };
+    /// The CIE standard illuminant D50, as used by ICC profiles.
+    pub const D50: Chromaticity = Chromaticity { x: 0.3457, y: 0.3585 };
+
+    #[inline]
+    pub fn new(x: f64, y: f64) -> Chromaticity {
+        Chromaticity { x, y }
+    }
+
+    /// Returns the tristimulus value of the chromaticity with a luminance
+    /// of one.
+    #[inline]
+    pub fn to_xyz<T:FloatChannel>(&self) -> Xyz<T> {
+        let xyz = self.tristimulus();
+        Xyz::new(cast(xyz[0]), cast(xyz[1]), cast(xyz[2]))
+    }
+
+    /// The components of `to_xyz`, usable in constants.
+    #[inline]
+    pub const fn tristimulus(&self) -> [f64; 3] {
+        [self.x / self.y, 1.0, (1.0 - self.x - self.y) / self.y]
+    }
+}
+
+/// The chromaticities of the primaries and white point of an RGB space.
+/// The conversion matrices are derived from these, rather than hardcoded.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct RgbPrimaries {
+    pub red: Chromaticity,
+    pub green: Chromaticity,
+    pub blue: Chromaticity,
+    pub white: Chromaticity,
+}
+
+impl RgbPrimaries {
+    /// The ITU-R BT.709 primaries with a D65 white point, shared by sRGB.
+    pub const SRGB: RgbPrimaries = RgbPrimaries {
+        red:   Chromaticity { x: 0.64, y: 0.33 },
+        green: Chromaticity { x: 0.30, y: 0.60 },
+        blue:  Chromaticity { x: 0.15, y: 0.06 },
+        white: Chromaticity::D65,
+    };
+
+    #[inline]
+    pub fn new(red: Chromaticity, green: Chromaticity, blue: Chromaticity, white: Chromaticity) -> RgbPrimaries {
+        RgbPrimaries { red, green, blue, white }
+    }
+
+    /// Derives the matrix converting linear RGB in this space to XYZ.
+    pub const fn to_xyz_matrix(&self) -> Matrix3 {
+        let r = self.red.tristimulus();
+        let g = self.green.tristimulus();
+        let b = self.blue.tristimulus();
+        let m = [[r[0], g[0], b[0]],
+                 [r[1], g[1], b[1]],
+                 [r[2], g[2], b[2]]];
+        let s = matrix::mul_vec(&matrix::invert(&m), self.white.tristimulus());
+        matrix::mul(&m, &matrix::diagonal(s))
+    }
+
+    /// Derives the matrix converting XYZ to linear RGB in this space.
+    #[inline]
+    pub const fn from_xyz_matrix(&self) -> Matrix3 {
+        matrix::invert(&self.to_xyz_matrix())
+    }
+}
+
+/// The matrices of `RgbPrimaries::SRGB`, derived once for the conversions
+/// that do not take primaries.
+pub const SRGB_TO_XYZ: Matrix3 = RgbPrimaries::SRGB.to_xyz_matrix();
+pub const XYZ_TO_SRGB: Matrix3 = RgbPrimaries::SRGB.from_xyz_matrix();
+
+impl<T:FloatChannel> Xyz<T> {
+    /// Converts untagged linear RGB components, relative to the given
+    /// primaries, to XYZ. This is for primaries only known at run time; an
+    /// `Rgb` tagged with its working space converts with `to_xyz`. The matrix
+    /// is derived on each call, except for `RgbPrimaries::SRGB`.
+    #[inline]
+    pub fn from_rgb_components<C:Channel>(rgb: [C; 3], primaries: &RgbPrimaries) -> Xyz<T> {
+        let rgb = [rgb[0].to_channel_f64(), rgb[1].to_channel_f64(), rgb[2].to_channel_f64()];
+        if *primaries == RgbPrimaries::SRGB {
+            Xyz::from_components_by(rgb, &SRGB_TO_XYZ)
+        } else {
+            Xyz::from_components_by(rgb, &primaries.to_xyz_matrix())
+        }
+    }
+
+    fn from_rgb_by<C: ToRgb>(color: &C, m: &Matrix3) -> Xyz<T> {
+        let rgb = color.to_rgb::<f64>();
+        Xyz::from_components_by([rgb.r, rgb.g, rgb.b], m)
+    }
+
+    fn from_components_by(rgb: [f64; 3], m: &Matrix3) -> Xyz<T> {
+        let xyz = matrix::mul_vec(m, rgb);
+        Xyz::new(cast(xyz[0]), cast(xyz[1]), cast(xyz[2]))
+    }
+
+    /// Converts the color to untagged linear RGB components relative to the
+    /// given primaries. The result is not clamped, so out of gamut colors
+    /// have components outside of `(0,1)` for float channels. The matrix is
+    /// derived on each call, except for `RgbPrimaries::SRGB`.
+    #[inline]
+    pub fn to_rgb_components<U:Channel>(&self, primaries: &RgbPrimaries) -> [U; 3] {
+        if *primaries == RgbPrimaries::SRGB {
+            self.components_by(&XYZ_TO_SRGB)
+        } else {
+            self.components_by(&primaries.from_xyz_matrix())
+        }
+    }
+
+    /// Converts the color to linear RGB in the working space `S`, taking the
+    /// XYZ value as relative to the white point of `S`. The result is not
+    /// clamped.
+    #[inline]
+    pub fn to_rgb_in<U:Channel, S:RgbSpace>(&self) -> Rgb<U, S> {
+        let rgb = self.components_by(&S::FROM_XYZ);
+        Rgb::with_space(rgb[0], rgb[1], rgb[2], S::default())
+    }
+
+    fn components_by<U:Channel>(&self, m: &Matrix3) -> [U; 3] {
+        let rgb = matrix::mul_vec(m, [cast(self.x), cast(self.y), cast(self.z)]);
+        [Channel::from(rgb[0]), Channel::from(rgb[1]), Channel::from(rgb[2])]
+    }
+}
+
+pub trait ToXyz {
+    fn to_xyz<U:FloatChannel>(&self) -> Xyz<U>;
+}
+
+impl<T:FloatChannel> ToXyz for Xyz<T> {
+    #[inline]
+    fn to_xyz<U:FloatChannel>(&self) -> Xyz<U> {
+        Xyz::new(cast(self.x), cast(self.y), cast(self.z))
+    }
+}
+
+impl<T:FloatChannel> ToXyz for Yxy<T> {
+    fn to_xyz<U:FloatChannel>(&self) -> Xyz<U> {
+        let x: f64 = cast(self.x);
+        let y: f64 = cast(self.y);
+        let luminance: f64 = cast(self.luminance);
+        if y == 0.0 {
+            Xyz::new(cast(0.0), cast(0.0), cast(0.0))
+        } else {
+            Xyz::new(cast(x * luminance / y),
+                     cast(luminance),
+                     cast((1.0 - x - y) * luminance / y))
+        }
+    }
+}
+
+/// Converts with the matrix of the working space, so the result is relative
+/// to the white point of `S`.
+impl<T:Channel, S:RgbSpace> ToXyz for Rgb<T, S> {
+    #[inline]
+    fn to_xyz<U:FloatChannel>(&self) -> Xyz<U> {
+        Xyz::from_components_by([self.r.to_channel_f64(), self.g.to_channel_f64(), self.b.to_channel_f64()], &S::TO_XYZ)
+    }
+}
+
+impl<T:Channel> ToXyz for Srgb<T> {
+    #[inline]
+    fn to_xyz<U:FloatChannel>(&self) -> Xyz<U> {
+        Xyz::from_rgb_by(self, &SRGB_TO_XYZ)
+    }
+}
+
+impl<T:Channel> ToXyz for Hsv<T> {
+    #[inline]
+    fn to_xyz<U:FloatChannel>(&self) -> Xyz<U> {
+        Xyz::from_rgb_by(self, &SRGB_TO_XYZ)
+    }
+}
+
+impl<T:Channel> ToXyz for Hsl<T> {
+    #[inline]
+    fn to_xyz<U:FloatChannel>(&self) -> Xyz<U> {
+        Xyz::from_rgb_by(self, &SRGB_TO_XYZ)
+    }
+}
+
+impl<T, C: ToXyz> ToXyz for AlphaColor<T, C> {
+    #[inline]
+    fn to_xyz<U:FloatChannel>(&self) -> Xyz<U> {
+        self.c.to_xyz()
+    }
+}
+
+pub trait ToYxy {
+    fn to_yxy<U:FloatChannel>(&self) -> Yxy<U>;
+}
+
+impl<T:FloatChannel> ToYxy for Yxy<T> {
+    #[inline]
+    fn to_yxy<U:FloatChannel>(&self) -> Yxy<U> {
+        Yxy::new(cast(self.x), cast(self.y), cast(self.luminance))
+    }
+}
+
+impl<T:FloatChannel> ToYxy for Xyz<T> {
+    /// Converts the color to xyY. Black has no chromaticity, so it is given
+    /// that of the D65 white point.
+    fn to_yxy<U:FloatChannel>(&self) -> Yxy<U> {
+        let x: f64 = cast(self.x);
+        let y: f64 = cast(self.y);
+        let sum = x + y + cast::<T, f64>(self.z);
+        if sum == 0.0 {
+            let white = Chromaticity::D65;
+            Yxy::new(cast(white.x), cast(white.y), cast(0.0))
+        } else {
+            Yxy::new(cast(x / sum), cast(y / sum), cast(y))
+        }
+    }
+}
+
+impl<T:Channel> ToYxy for Rgb<T> {
+    #[inline]
+    fn to_yxy<U:FloatChannel>(&self) -> Yxy<U> {
+        self.to_xyz::<f64>().to_yxy()
+    }
+}
+
+impl<T:Channel> ToYxy for Srgb<T> {
+    #[inline]
+    fn to_yxy<U:FloatChannel>(&self) -> Yxy<U> {
+        self.to_xyz::<f64>().to_yxy()
+    }
+}
+
+impl<T, C: ToYxy> ToYxy for AlphaColor<T, C> {
+    #[inline]
+    fn to_yxy<U:FloatChannel>(&self) -> Yxy<U> {
+        self.c.to_yxy()
+    }
+}
+
+impl<T:FloatChannel> ToRgb for Xyz<T> {
+    #[inline]
+    fn to_rgb<U:Channel>(&self) -> Rgb<U> {
+        self.to_rgb_in::<U, LinearSrgb>()
+    }
+}
+
+impl<T:FloatChannel> ToRgb for Yxy<T> {
+    #[inline]
+    fn to_rgb<U:Channel>(&self) -> Rgb<U> {
+        self.to_xyz::<f64>().to_rgb()
+    }
+}
+
+impl<T:FloatChannel> ToSrgb for Xyz<T> {
+    #[inline]
+    fn to_srgb<U:Channel>(&self) -> Srgb<U> {
+        self.to_rgb::<f64>().to_srgb()
+    }
+}
+
+impl<T:FloatChannel> ToSrgb for Yxy<T> {
+    #[inline]
+    fn to_srgb<U:Channel>(&self) -> Srgb<U> {
+        self.to_rgb::<f64>().to_srgb()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {Xyz, ToXyz, Yxy, ToYxy, Chromaticity, RgbPrimaries};
+    use {Rgb, ToRgb};
+    use matrix;
+    use space::{RgbSpace, LinearSrgb, AdobeRgb, DisplayP3, conversion_matrix};
+    use super::{SRGB_TO_XYZ, XYZ_TO_SRGB};
+
+    fn assert_matrix_eq(a: [[f64; 3]; 3], b: [[f64; 3]; 3]) {
+        for i in 0..3 {
+            for j in 0..3 {
+                assert!((a[i][j] - b[i][j]).abs() < 1e-4, "{:?} != {:?}", a, b);
+            }
+        }
+    }
+
+    fn assert_xyz_eq(a: Xyz<f64>, b: Xyz<f64>) {
+        assert!((a.x - b.x).abs() < 1e-4, "{:?} != {:?}", a, b);
+        assert!((a.y - b.y).abs() < 1e-4, "{:?} != {:?}", a, b);
+        assert!((a.z - b.z).abs() < 1e-4, "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn test_srgb_matrix() {
+        assert_matrix_eq(RgbPrimaries::SRGB.to_xyz_matrix(),
+                         [[0.4124, 0.3576, 0.1805],
+                          [0.2126, 0.7152, 0.0722],
+                          [0.0193, 0.1192, 0.9505]]);
+        assert_matrix_eq(RgbPrimaries::SRGB.from_xyz_matrix(),
+                         [[ 3.2410, -1.5374, -0.4986],
+                          [-0.9692,  1.8760,  0.0416],
+                          [ 0.0556, -0.2040,  1.0570]]);
+        // The constants are derived at compile time with the same arithmetic
+        assert_eq!(SRGB_TO_XYZ, RgbPrimaries::SRGB.to_xyz_matrix());
+        assert_eq!(XYZ_TO_SRGB, RgbPrimaries::SRGB.from_xyz_matrix());
+        assert_eq!(LinearSrgb::FROM_XYZ, XYZ_TO_SRGB);
+        assert_matrix_eq(conversion_matrix::<LinearSrgb, LinearSrgb>(), matrix::diagonal([1.0, 1.0, 1.0]));
+    }
+
+    #[test]
+    fn test_rgb_to_xyz() {
+        assert_xyz_eq(Rgb::<f64>::new(1.0, 1.0, 1.0).to_xyz(), Xyz::new(0.9505, 1.0, 1.0890));
+        assert_xyz_eq(Rgb::<f64>::new(1.0, 0.0, 0.0).to_xyz(), Xyz::new(0.4124, 0.2126, 0.0193));
+        assert_xyz_eq(Rgb::<u8>::new(0x00, 0x00, 0x00).to_xyz(), Xyz::new(0.0, 0.0, 0.0));
+        let white = Chromaticity::D65.to_xyz::<f64>().to_rgb::<f64>();
+        assert!((white.r - 1.0).abs() < 1e-4 && (white.g - 1.0).abs() < 1e-4 && (white.b - 1.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_custom_primaries() {
+        // Adobe RGB (1998)
+        let adobe = RgbPrimaries::new(Chromaticity::new(0.64, 0.33),
+                                      Chromaticity::new(0.21, 0.71),
+                                      Chromaticity::new(0.15, 0.06),
+                                      Chromaticity::D65);
+        let green = Xyz::<f64>::from_rgb_components([0.0f64, 1.0, 0.0], &adobe);
+        assert_xyz_eq(green, Xyz::new(0.1856, 0.6274, 0.0707));
+        let back = green.to_rgb_components::<f64>(&adobe);
+        assert!((back[1] - 1.0).abs() < 1e-9 && back[0].abs() < 1e-9 && back[2].abs() < 1e-9);
+        // Colors tagged with their space use its matrix instead
+        let tagged = Rgb::with_space(0.0f64, 1.0, 0.0, AdobeRgb);
+        assert_xyz_eq(tagged.to_xyz(), green);
+        let back = green.to_rgb_in::<f64, AdobeRgb>();
+        assert!((back.g - 1.0).abs() < 1e-9 && back.r.abs() < 1e-9 && back.b.abs() < 1e-9);
+        let red = Rgb::with_space(1.0f64, 0.0, 0.0, DisplayP3);
+        assert_xyz_eq(red.to_xyz(), red.to_space::<LinearSrgb>().to_xyz());
+    }
+
+    #[test]
+    fn test_yxy() {
+        let white = Rgb::<f64>::new(1.0, 1.0, 1.0).to_yxy::<f64>();
+        assert!((white.x - 0.3127).abs() < 1e-4);
+        assert!((white.y - 0.3290).abs() < 1e-4);
+        assert!((white.luminance - 1.0).abs() < 1e-9);
+        assert_xyz_eq(white.to_xyz(), Xyz::new(0.9505, 1.0, 1.0890));
+        assert_eq!(Xyz::<f64>::new(0.0, 0.0, 0.0).to_yxy::<f64>(), Yxy::new(0.3127, 0.3290, 0.0));
+        assert_eq!(Yxy::<f64>::new(0.3127, 0.3290, 0.0).to_xyz::<f64>(), Xyz::new(0.0, 0.0, 0.0));
+    }
+}
@@ -14,13 +14,291 @@
 // limitations under the License.
 
 //! http://en.wikipedia.org/wiki/YCbCr
+//!
+//! `YCbCr` values are derived from gamma encoded components, so conversions
+//! from linear `Rgb` pass through the sRGB curve first. The chroma components
+//! are stored with an offset, so that the neutral axis sits at the middle of
+//! the channel: `128` for `u8`, `32768` for `u16` and `0.5` for floats.
+
+use num;
+use num::traits;
+
+use Color;
+use Channel;
+use {Rgb, ToRgb};
+use {Srgb, ToSrgb};
+use {Hsv, AlphaColor};
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub struct YCbCr<T> { pub y: T, pub cb: T, pub cr: T }
 
+fn cast<T: num::NumCast, U: num::NumCast>(n: T) -> U {
+    traits::cast(n).unwrap()
+}
+
+/// The luma coefficients used to derive `Y'` from `R'G'B'`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum YCbCrMatrix {
+    /// ITU-R BT.601, used by JPEG and standard definition video.
+    Bt601,
+    /// ITU-R BT.709, used by high definition video.
+    Bt709,
+    /// ITU-R BT.2020 non-constant luminance, used by UHD video.
+    Bt2020,
+    /// SMPTE 240M, the interim HDTV standard.
+    Smpte240m,
+}
+
+impl YCbCrMatrix {
+    /// Returns the `(Kr, Kb)` luma coefficients of the matrix.
+    #[inline]
+    pub fn coefficients(self) -> (f64, f64) {
+        match self {
+            YCbCrMatrix::Bt601     => (0.299, 0.114),
+            YCbCrMatrix::Bt709     => (0.2126, 0.0722),
+            YCbCrMatrix::Bt2020    => (0.2627, 0.0593),
+            YCbCrMatrix::Smpte240m => (0.212, 0.087),
+        }
+    }
+}
+
+/// The quantization range of the components.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum YCbCrRange {
+    /// The components use the whole range of the channel, as in JPEG.
+    Full,
+    /// The components use the studio swing of `16-235` for luma and
+    /// `16-240` for chroma (scaled accordingly for wider channels).
+    Limited,
+}
+
+/// Returns the `(luma offset, luma scale, chroma offset, chroma scale)` used
+/// to quantize normalized components into a channel of type `T`.
+fn quantization<T:Channel>(range: YCbCrRange) -> (f64, f64, f64, f64) {
+    let max: f64 = cast(T::max());
+    let integer = max > 1.0;
+    match range {
+        YCbCrRange::Full if integer => (0.0, max, (max + 1.0) / 2.0, max),
+        YCbCrRange::Full            => (0.0, 1.0, 0.5, 1.0),
+        YCbCrRange::Limited => {
+            let unit = if integer { (max + 1.0) / 256.0 } else { 1.0 / 255.0 };
+            (16.0 * unit, 219.0 * unit, 128.0 * unit, 224.0 * unit)
+        }
+    }
+}
+
+/// Converts a value in the units of `T` to `T`, rounding to the nearest
+/// integer and saturating for integer channels.
+fn quantize<T:Channel>(value: f64) -> T {
+    let max: f64 = cast(T::max());
+    if max > 1.0 {
+        cast(value.round().max(0.0).min(max))
+    } else {
+        cast(value)
+    }
+}
+
 impl<T> YCbCr<T> {
     #[inline]
     pub fn new(y: T, cb: T, cr: T) -> YCbCr<T> {
-        YCbCr { y: y, cb: cb, cr: cr }
+        YCbCr { y, cb, cr }
+    }
+}
+
+impl<T:Channel> YCbCr<T> {
+    /// Converts a color to `YCbCr` using the given matrix and range.
+    pub fn from_srgb<C: ToSrgb>(color: &C, matrix: YCbCrMatrix, range: YCbCrRange) -> YCbCr<T> {
+        let srgb = color.to_srgb::<f64>();
+        let (kr, kb) = matrix.coefficients();
+        let kg = 1.0 - kr - kb;
+
+        let y = kr * srgb.r + kg * srgb.g + kb * srgb.b;
+        let cb = (srgb.b - y) / (2.0 * (1.0 - kb));
+        let cr = (srgb.r - y) / (2.0 * (1.0 - kr));
+
+        let (oy, sy, oc, sc) = quantization::<T>(range);
+        YCbCr::new(quantize(oy + sy * y),
+                   quantize(oc + sc * cb),
+                   quantize(oc + sc * cr))
+    }
+
+    /// Converts the color to gamma encoded sRGB, interpreting the components
+    /// with the given matrix and range.
+    pub fn to_srgb_with<U:Channel>(&self, matrix: YCbCrMatrix, range: YCbCrRange) -> Srgb<U> {
+        let (oy, sy, oc, sc) = quantization::<T>(range);
+        let y = (cast::<T, f64>(self.y) - oy) / sy;
+        let cb = (cast::<T, f64>(self.cb) - oc) / sc;
+        let cr = (cast::<T, f64>(self.cr) - oc) / sc;
+
+        let (kr, kb) = matrix.coefficients();
+        let kg = 1.0 - kr - kb;
+
+        let r = y + 2.0 * (1.0 - kr) * cr;
+        let b = y + 2.0 * (1.0 - kb) * cb;
+        let g = (y - kr * r - kb * b) / kg;
+
+        let max: f64 = cast(U::max());
+        Srgb::new(quantize(r.clamp(0.0, 1.0) * max),
+                  quantize(g.clamp(0.0, 1.0) * max),
+                  quantize(b.clamp(0.0, 1.0) * max))
+    }
+
+    /// Converts the color to linear `Rgb`, interpreting the components with
+    /// the given matrix and range.
+    #[inline]
+    pub fn to_rgb_with<U:Channel>(&self, matrix: YCbCrMatrix, range: YCbCrRange) -> Rgb<U> {
+        self.to_srgb_with::<f64>(matrix, range).to_rgb()
+    }
+}
+
+impl<T:Channel> Color<T> for YCbCr<T> {
+    /// Clamps the components of the color to the range `(lo,hi)`.
+    #[inline]
+    fn clamp_s(self, lo: T, hi: T) -> YCbCr<T> {
+        YCbCr::new(self.y.clamp(lo, hi),
+                   self.cb.clamp(lo, hi),
+                   self.cr.clamp(lo, hi))
+    }
+
+    /// Clamps the components of the color component-wise between `lo` and `hi`.
+    #[inline]
+    fn clamp_c(self, lo: YCbCr<T>, hi: YCbCr<T>) -> YCbCr<T> {
+        YCbCr::new(self.y.clamp(lo.y, hi.y),
+                   self.cb.clamp(lo.cb, hi.cb),
+                   self.cr.clamp(lo.cr, hi.cr))
+    }
+
+    /// Inverts the color.
+    #[inline]
+    fn inverse(self) -> YCbCr<T> {
+        YCbCr::new(self.y.invert_channel(),
+                   self.cb.invert_channel(),
+                   self.cr.invert_channel())
+    }
+
+    #[inline]
+    fn mix(self, other: Self, value: T) -> Self {
+        YCbCr::new(self.y.mix(other.y, value),
+                   self.cb.mix(other.cb, value),
+                   self.cr.mix(other.cr, value))
+    }
+}
+
+pub trait ToYCbCr {
+    fn to_ycbcr_with<U:Channel>(&self, matrix: YCbCrMatrix, range: YCbCrRange) -> YCbCr<U>;
+
+    /// Converts the color using the full range BT.601 matrix, as used by JPEG.
+    #[inline]
+    fn to_ycbcr<U:Channel>(&self) -> YCbCr<U> {
+        self.to_ycbcr_with(YCbCrMatrix::Bt601, YCbCrRange::Full)
+    }
+}
+
+impl<T:Channel> ToYCbCr for Rgb<T> {
+    #[inline]
+    fn to_ycbcr_with<U:Channel>(&self, matrix: YCbCrMatrix, range: YCbCrRange) -> YCbCr<U> {
+        YCbCr::from_srgb(self, matrix, range)
+    }
+}
+
+impl<T:Channel> ToYCbCr for Srgb<T> {
+    #[inline]
+    fn to_ycbcr_with<U:Channel>(&self, matrix: YCbCrMatrix, range: YCbCrRange) -> YCbCr<U> {
+        YCbCr::from_srgb(self, matrix, range)
+    }
+}
+
+impl<T:Channel> ToYCbCr for Hsv<T> {
+    #[inline]
+    fn to_ycbcr_with<U:Channel>(&self, matrix: YCbCrMatrix, range: YCbCrRange) -> YCbCr<U> {
+        YCbCr::from_srgb(self, matrix, range)
+    }
+}
+
+impl<T, C: ToYCbCr> ToYCbCr for AlphaColor<T, C> {
+    #[inline]
+    fn to_ycbcr_with<U:Channel>(&self, matrix: YCbCrMatrix, range: YCbCrRange) -> YCbCr<U> {
+        self.c.to_ycbcr_with(matrix, range)
+    }
+}
+
+impl<T:Channel> ToSrgb for YCbCr<T> {
+    /// Converts the color using the full range BT.601 matrix.
+    #[inline]
+    fn to_srgb<U:Channel>(&self) -> Srgb<U> {
+        self.to_srgb_with(YCbCrMatrix::Bt601, YCbCrRange::Full)
+    }
+}
+
+impl<T:Channel> ToRgb for YCbCr<T> {
+    /// Converts the color using the full range BT.601 matrix.
+    #[inline]
+    fn to_rgb<U:Channel>(&self) -> Rgb<U> {
+        self.to_rgb_with(YCbCrMatrix::Bt601, YCbCrRange::Full)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {YCbCr, ToYCbCr, YCbCrMatrix, YCbCrRange};
+    use {Srgb, ToSrgb};
+    use {YCbCra, ToYCbCra, ToRgba};
+    use consts;
+
+    #[test]
+    fn test_srgb_to_ycbcr_full() {
+        assert_eq!(Srgb::<u8>::new(0xFF, 0xFF, 0xFF).to_ycbcr::<u8>(), YCbCr::<u8>::new(255, 128, 128));
+        assert_eq!(Srgb::<u8>::new(0x00, 0x00, 0x00).to_ycbcr::<u8>(), YCbCr::<u8>::new(0, 128, 128));
+        assert_eq!(Srgb::<u8>::new(0xFF, 0x00, 0x00).to_ycbcr::<u8>(), YCbCr::<u8>::new(76, 85, 255));
+
+        let white = Srgb::<f64>::new(1.0, 1.0, 1.0).to_ycbcr::<f64>();
+        assert!((white.y - 1.0).abs() < 1e-9);
+        assert!((white.cb - 0.5).abs() < 1e-9);
+        assert!((white.cr - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_srgb_to_ycbcr_limited() {
+        let red = Srgb::<u8>::new(0xFF, 0x00, 0x00);
+        assert_eq!(red.to_ycbcr_with::<u8>(YCbCrMatrix::Bt601, YCbCrRange::Limited), YCbCr::<u8>::new(81, 90, 240));
+        assert_eq!(red.to_ycbcr_with::<u8>(YCbCrMatrix::Bt709, YCbCrRange::Limited), YCbCr::<u8>::new(63, 102, 240));
+
+        let white = Srgb::<u8>::new(0xFF, 0xFF, 0xFF);
+        assert_eq!(white.to_ycbcr_with::<u8>(YCbCrMatrix::Bt2020, YCbCrRange::Limited), YCbCr::<u8>::new(235, 128, 128));
+        assert_eq!(white.to_ycbcr_with::<u16>(YCbCrMatrix::Bt2020, YCbCrRange::Limited), YCbCr::<u16>::new(60160, 32768, 32768));
+
+        let black = Srgb::<u8>::new(0x00, 0x00, 0x00);
+        assert_eq!(black.to_ycbcr_with::<u16>(YCbCrMatrix::Smpte240m, YCbCrRange::Limited), YCbCr::<u16>::new(4096, 32768, 32768));
+    }
+
+    #[test]
+    fn test_ycbcr_round_trip() {
+        let matrices = [YCbCrMatrix::Bt601, YCbCrMatrix::Bt709, YCbCrMatrix::Bt2020, YCbCrMatrix::Smpte240m];
+        let ranges = [YCbCrRange::Full, YCbCrRange::Limited];
+        let colors = [consts::CORNFLOWERBLUE, consts::CHOCOLATE, consts::SEAGREEN, consts::WHITE, consts::BLACK];
+        for &matrix in matrices.iter() {
+            for &range in ranges.iter() {
+                for c in colors.iter() {
+                    let srgb = *c;
+                    let ycbcr = srgb.to_ycbcr_with::<u16>(matrix, range);
+                    assert_eq!(ycbcr.to_srgb_with::<u8>(matrix, range), srgb);
+
+                    let ycbcr = srgb.to_ycbcr_with::<f64>(matrix, range);
+                    let back = ycbcr.to_srgb_with::<f64>(matrix, range);
+                    assert!((back.r - c.r as f64 / 255.0).abs() < 1e-9);
+                    assert!((back.g - c.g as f64 / 255.0).abs() < 1e-9);
+                    assert!((back.b - c.b as f64 / 255.0).abs() < 1e-9);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_ycbcra() {
+        let ycbcra = YCbCra { c: YCbCr::<u8>::new(255, 128, 128), a: 0x80 };
+        let rgba = ycbcra.to_rgba::<u8>();
+        assert_eq!(rgba, rgba!(0xFFu8, 0xFF, 0xFF, 0x80));
+        assert_eq!(rgba.to_ycbcra::<u8>(), ycbcra);
+        assert_eq!(ycbcra.to_srgb::<u8>(), Srgb::new(0xFF, 0xFF, 0xFF));
     }
 }